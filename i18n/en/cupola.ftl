@@ -7,9 +7,14 @@ app-description = An image viewer made for COSMIC, but can be used anywhere.
 menu-file = File
 menu-open = Open
 menu-open-folder = Open Folder
+menu-pin-folder = Pin Folder
+menu-unpin-folder = Unpin Folder
 menu-recent-folders = Recent Folders
 menu-no-recent-folders = No recent folders
 menu-clear-recent = Clear Recent
+menu-rename = Rename…
+menu-copy-to = Copy To…
+menu-move-to = Move To…
 menu-set-wallpaper = Set as Wallpaper
 menu-save = Save
 menu-save-as = Save As...
@@ -27,10 +32,15 @@ menu-fullscreen = Fullscreen
 menu-slideshow-start = Start Slideshow
 menu-slideshow-stop = Stop Slideshow
 menu-gallery = Gallery View
+menu-filter = Filter by Name
 menu-sort = Sort
 menu-sort-name = By Name
 menu-sort-date = By Date
 menu-sort-size = By Size
+menu-sort-date-taken = By Date Taken
+menu-sort-dimensions = By Dimensions
+menu-sort-type = By Type
+menu-sort-random = Shuffle
 menu-sort-ascending = Ascending
 menu-sort-descending = Descending
 
@@ -44,6 +54,8 @@ menu-crop = Crop
 crop-apply = Apply Crop
 crop-cancel = Cancel
 menu-undo = Undo
+menu-select-all = Select All
+menu-select-none = Select None
 
 # Menu - Navigate
 menu-nav = Navigate
@@ -51,6 +63,7 @@ menu-next = Next Image
 menu-prev = Previous Image
 menu-first = First Image
 menu-last = Last Image
+menu-go-up = Parent Folder
 
 # Menu - Help
 menu-help = Help
@@ -81,17 +94,37 @@ settings-fit-to-window = Fit Images to Window
 settings-smooth-scaling = Smooth Image Scaling
 settings-gallery = Gallery Settings
 settings-thumbnail-size = Thumbnail Size
-settings-thumbnail-small = Small (64px)
-settings-thumbnail-medium = Medium (128px)
-settings-thumbnail-large = Large (192px)
-settings-thumbnail-xlarge = Extra Large (256px)
+settings-thumbnail-pixels = { $pixels } px
+settings-gallery-layout = Layout
+settings-gallery-layout-grid = Grid
+settings-gallery-layout-justified = Justified rows
+settings-gallery-layout-list = Details list
+settings-date-grouping = Group by Date Taken
+settings-date-grouping-off = Off
+settings-date-grouping-day = Day
+settings-date-grouping-month = Month
+settings-date-grouping-year = Year
+settings-badge-format = Format Badge
+settings-badge-animation = Animation Badge
+settings-badge-rating = Rating Badge
+settings-badge-edited = Edited Badge
+settings-badge-failed = Failed to Load Badge
 settings-show-hidden = Show Hidden Files
+settings-sort-mode = Sort By
+settings-sort-order = Sort Order
+settings-include-subfolders = Include Subfolders
+settings-subfolder-depth = Subfolder Depth
+settings-group-by-folder = Group by Folder
 settings-slideshow = Slideshow Settings
 settings-slideshow-interval = Slideshow Interval (seconds)
 settings-performance = Performance
 settings-cache-size = Image Cache Size
 settings-directory = Directory Settings
 settings-remember-dir = Remember Last Directory
+settings-quick-sort = Quick Sort
+settings-quick-sort-slot = Alt+{ $slot }
+settings-quick-sort-unset = Not set
+settings-quick-sort-choose = Choose…
 settings-wallpaper = Wallpaper Settings
 settings-wallpaper-behavior = When setting wallpaper
 
@@ -100,6 +133,45 @@ status-loading = Loading...
 status-no-image = No image loaded
 status-image-count = Image { $current } of { $total }
 status-zoom-level = Zoom: { $percent }%
+status-selected = { $count ->
+    [one] 1 selected
+   *[other] { $count } selected
+}
+status-unreadable = { $count ->
+    [one] 1 file couldn't be read
+   *[other] { $count } files couldn't be read
+}
+status-unreadable-in = { $folder }: { $count ->
+    [one] 1 file couldn't be read
+   *[other] { $count } files couldn't be read
+}
+
+# Load errors
+load-error-title = Couldn't open { $name }
+load-error-retry = Retry
+load-error-open-with = Open With…
+
+# Filter
+filter-placeholder = Filter by name, glob (*.png) or regex (re:...)
+filter-no-matches = No images match the filter
+
+# Details list
+details-name = Name
+details-dimensions = Dimensions
+details-size = Size
+details-type = Type
+details-modified = Modified
+details-taken = Taken
+details-type-folder = Folder
+
+# Timeline
+timeline-folders = Folders
+timeline-undated = Unknown date
+timeline-section = { $title } · { $count ->
+    [one] 1 photo
+   *[other] { $count } photos
+}
+timeline-select-all = Select all
 
 # Edit Status
 edit-modified = Modified
@@ -125,9 +197,133 @@ wallpaper-cancel = Cancel
 
 # Delete Dialog
 delete-dialog-title = Delete Image
+delete-dialog-title-many = Delete { $count } Images
+delete-dialog-more = and { $count } more
+delete-dialog-summary = { $count ->
+    [one] 1 image, { $size }
+   *[other] { $count } images, { $size }
+}
 delete-trash = Move to Trash
 delete-permanent = Delete Permanently
 delete-cancel = Cancel
+delete-progress = Deleting { $done } of { $total }…
+delete-done-trash = { $count ->
+    [one] Moved 1 image to the trash
+   *[other] Moved { $count } images to the trash
+}
+delete-done-permanent = { $count ->
+    [one] Deleted 1 image
+   *[other] Deleted { $count } images
+}
+delete-failed = { $count ->
+    [one] 1 file couldn't be deleted
+   *[other] { $count } files couldn't be deleted
+}
+delete-undo = Undo
+
+# Rotate or flip selected images
+transform-dialog-rotate-90 = { $count ->
+    [one] Rotate 1 Image?
+   *[other] Rotate { $count } Images?
+}
+transform-dialog-rotate-180 = { $count ->
+    [one] Rotate 1 Image by 180°?
+   *[other] Rotate { $count } Images by 180°?
+}
+transform-dialog-flip-horizontal = { $count ->
+    [one] Flip 1 Image Horizontally?
+   *[other] Flip { $count } Images Horizontally?
+}
+transform-dialog-flip-vertical = { $count ->
+    [one] Flip 1 Image Vertically?
+   *[other] Flip { $count } Images Vertically?
+}
+transform-dialog-body = The files are saved over. A copy of each is kept so this can be undone.
+transform-confirm = Save
+transform-cancel = Cancel
+transform-progress = Saving { $done } of { $total }…
+transform-done = { $count ->
+    [one] Saved 1 image
+   *[other] Saved { $count } images
+}
+transform-failed = { $count ->
+    [one] 1 image couldn't be saved
+   *[other] { $count } images couldn't be saved
+}
+transform-undo = Undo
+
+# Toasts
+toast-saved = Saved { $name }
+toast-save-failed = Couldn't save the image: { $error }
+toast-wallpaper-set = Wallpaper set
+toast-wallpaper-failed = Couldn't set the wallpaper: { $error }
+toast-restored = { $count ->
+    [one] Restored 1 image from the trash
+   *[other] Restored { $count } images from the trash
+}
+toast-restore-failed = Couldn't restore from the trash: { $error }
+toast-transform-restored = { $count ->
+    [one] Restored 1 image
+   *[other] Restored { $count } images
+}
+toast-transform-restore-failed = Couldn't restore the images: { $error }
+toast-config-failed = Couldn't save settings: { $error }
+
+# Rename
+rename-label = Rename
+rename-placeholder = New name
+rename-confirm = Rename
+rename-cancel = Cancel
+rename-dialog-title = Rename { $count } Images
+rename-pattern-help = {"{"}name{"}"}, {"{"}ext{"}"}, {"{"}n:03{"}"}, {"{"}date:%Y-%m-%d{"}"}, {"{"}camera{"}"}, {"{"}width{"}"}x{"{"}height{"}"}
+rename-reading = Reading image details…
+rename-more = and { $count } more
+rename-conflicts = { $count ->
+    [one] 1 name needs changing before renaming
+   *[other] { $count } names need changing before renaming
+}
+rename-problem-empty = Name is empty
+rename-problem-invalid = Not a valid name
+rename-problem-duplicate = Used twice
+rename-problem-exists = Already exists
+rename-done = { $count ->
+    [one] Renamed 1 image
+   *[other] Renamed { $count } images
+}
+rename-failed = { $count ->
+    [one] 1 file couldn't be renamed
+   *[other] { $count } files couldn't be renamed
+}
+rename-undo = Undo
+
+# Copy and Move
+transfer-collision-title = Files Already Exist
+transfer-collision-body = { $count ->
+    [one] 1 file with the same name is already in { $folder }.
+   *[other] { $count } files with the same names are already in { $folder }.
+}
+transfer-skip = Skip
+transfer-rename = Keep Both
+transfer-overwrite = Replace
+transfer-cancel = Cancel
+transfer-progress-copy = Copying { $done } of { $total }…
+transfer-progress-move = Moving { $done } of { $total }…
+transfer-done-copy = { $count ->
+    [one] Copied 1 image to { $folder }
+   *[other] Copied { $count } images to { $folder }
+}
+transfer-done-move = { $count ->
+    [one] Moved 1 image to { $folder }
+   *[other] Moved { $count } images to { $folder }
+}
+transfer-skipped = { $count ->
+    [one] 1 skipped
+   *[other] { $count } skipped
+}
+transfer-failed = { $count ->
+    [one] 1 file couldn't be transferred
+   *[other] { $count } files couldn't be transferred
+}
 
 # Save Dialog
 save-dialog-title = Save Image As
@@ -137,3 +333,15 @@ save-dialog-filter = Images
 about-repo = Repository
 about-support = Support
 about-website = Website
+
+# Folder tree
+folder-tree-home = Home
+
+# Collections
+collection-title = Collection of { $count } images
+
+# Path bar
+breadcrumb-edit = Edit Path
+breadcrumb-placeholder = Folder path
+breadcrumb-siblings = Other Folders Here
+breadcrumb-not-folder = { $path } is not a folder
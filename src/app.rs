@@ -1,22 +1,34 @@
 //! Main app state
 
 use crate::{
-    config::{AppTheme, ThumbnailSize, ViewerConfig, WallpaperBehavior},
+    cli,
+    config::{
+        AppTheme, Badge, DateGrouping, GalleryLayout, QUICK_SORT_SLOTS, SortMode, SortOrder,
+        ThumbnailSize, ViewerConfig, WallpaperBehavior,
+    },
+    dbus,
     edit::{EditState, Transform},
-    fl,
-    image::{self, CachedImage, ImageCache},
+    file_ops, fl,
+    folder_tree::{self, FolderTree},
+    format::{format_size, format_system_time},
+    image::{self, CachedImage, ImageCache, ThumbnailScheduler},
     key_binds::{self, MenuAction},
     menu::menu_bar,
     message::{
-        ContextPage, DeleteAction, DragHandle, EditMessage, ImageMessage, Message, NavMessage,
-        SettingsMessage, ViewMessage,
+        BreadcrumbMessage, Collision, ContextPage, DeleteAction, DragHandle, EditMessage,
+        FolderTreeMessage, ImageMessage, Message, NavMessage, RenameMessage, SettingsMessage,
+        TransferKind, ViewMessage,
     },
-    nav::{self, NavState},
-    views::{GalleryView, ImageViewState},
+    nav::{self, NavFilter, NavState},
+    rename::{self, RenameReport},
+    views::{Breadcrumb, GalleryView, ImageViewState, RenameDialog, breadcrumb},
     watcher,
 };
 use ashpd::{
-    desktop::wallpaper::{SetOn, WallpaperRequest},
+    desktop::{
+        open_uri::OpenFileRequest,
+        wallpaper::{SetOn, WallpaperRequest},
+    },
     url::Url,
 };
 use cosmic::{
@@ -30,39 +42,236 @@ use cosmic::{
     iced_widget::{scrollable, toggler},
     task::future,
     widget::{
-        Id, button, column, dropdown,
+        Id, Toast, Toasts, button, column, dropdown,
         menu::key_bind::{KeyBind, Modifier},
-        radio, settings, slider, spin_button, text,
+        nav_bar, radio, settings, slider, spin_button, text, toaster,
     },
 };
 use rfd::AsyncFileDialog;
-use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::sync::watch;
+
+/// How long the wheel rests before a gallery zoom is written to the config
+const ZOOM_SAVE_DELAY: Duration = Duration::from_millis(500);
 
 pub struct ImageViewer {
     core: Core,
     config: ViewerConfig,
+    /// Config as saved, before command line overrides
+    saved_config: ViewerConfig,
+    /// Settings replaced from the command line for this session
+    overrides: cli::Overrides,
     config_handler: Option<Config>,
     key_binds: HashMap<KeyBind, MenuAction>,
     nav: NavState,
     cache: ImageCache,
+    thumbnails: ThumbnailScheduler,
     image_state: ImageViewState,
     gallery_view: GalleryView,
+    folder_tree: FolderTree,
+    breadcrumb: Breadcrumb,
     context_page: Option<ContextPage>,
     is_loading: bool,
     is_fullscreen: bool,
     is_slideshow_active: bool,
     wallpaper_dialog: Option<PathBuf>,
     available_outputs: Vec<String>,
-    delete_dialog: Option<PathBuf>,
+    delete_dialog: Option<file_ops::DeleteRequest>,
+    /// Files done and total of the running delete
+    delete_progress: Option<(usize, usize)>,
+    /// Last finished delete, which Ctrl+Z takes back after trashing
+    delete_report: Option<file_ops::DeleteReport>,
+    /// Copy or move waiting for the user to say what to do about name collisions
+    transfer_dialog: Option<file_ops::TransferRequest>,
+    /// Kind, files done and total of the running copy or move
+    transfer_progress: Option<(TransferKind, usize, usize)>,
+    /// Selected images waiting for the user to confirm rotating or flipping
+    /// them on disk
+    transform_dialog: Option<(Vec<PathBuf>, Transform)>,
+    /// Files done and total of the running rotate or flip
+    transform_progress: Option<(usize, usize)>,
+    /// Last finished rotate or flip, with copies of the originals for undo
+    transform_report: Option<file_ops::TransformReport>,
+    /// Inline rename of one image, or the batch rename dialog
+    rename: Option<RenameDialog>,
+    /// Last finished rename, which its toast or Ctrl+Z takes back
+    rename_report: Option<RenameReport>,
+    /// Results of saves, deletes and other operations, shown over the window
+    toasts: Toasts<Message>,
+    /// Renames started but not finished, old path to new, so a refresh that
+    /// lands in between keeps the renamed images in view
+    pending_renames: HashMap<PathBuf, PathBuf>,
     edit_state: EditState,
     _save_dialog: Option<PathBuf>,
+    /// Identifies the running directory scan so stale batches can be dropped
+    scan_id: u64,
+    /// Whether the running scan has delivered any batches yet
+    scan_streamed: bool,
+    /// Whether the requested image was opened while the scan was streaming
+    scan_target_opened: bool,
+    /// One past the last tile the latest thumbnail pass wanted; tiles
+    /// streamed in after it aren't near the view
+    thumbnail_reach: usize,
+    /// When Ctrl+wheel last changed the thumbnail size, until it is saved
+    zoom_unsaved_since: Option<Instant>,
+    /// Start the slideshow once the first scan finishes
+    start_slideshow: bool,
+    /// Bus name claimed at startup, so later launches open here
+    bus: Option<dbus::Bus>,
+    /// What the D-Bus control interface reports
+    status: watch::Sender<dbus::Status>,
 }
 
 impl ImageViewer {
     pub const APP_ID: &'static str = "org.codeberg.bhh32.Cupola";
 
+    /// Let the D-Bus interface know when the image, slideshow or fullscreen changed
+    fn publish_status(&self) {
+        let status = dbus::Status {
+            current_image: self.nav.current().cloned(),
+            current_index: self.nav.index(),
+            image_count: self.nav.total(),
+            slideshow_active: self.is_slideshow_active,
+            fullscreen: self.is_fullscreen,
+        };
+
+        self.status.send_if_modified(|published| {
+            let changed = *published != status;
+            *published = status;
+            changed
+        });
+    }
+
+    /// Save the config, leaving out the command line overrides
+    fn write_config(&mut self) -> Task<Action<Message>> {
+        let Some(ref handler) = self.config_handler else {
+            return Task::none();
+        };
+
+        let persisted = self.overrides.persisted(&self.config, &self.saved_config);
+        let result = persisted.write_entry(handler);
+        self.saved_config = persisted;
+
+        match result {
+            Ok(()) => Task::none(),
+            Err(err) => {
+                tracing::error!("Failed to save settings: {err}");
+                self.toast(fl!("toast-config-failed", error = err.to_string()))
+            }
+        }
+    }
+
+    /// Show `message` in a toast
+    fn toast(&mut self, message: String) -> Task<Action<Message>> {
+        self.toasts.push(Toast::new(message)).map(Action::App)
+    }
+
+    /// Images an action applies to: the open image, else the gallery
+    /// selection, else the focused thumbnail
+    fn target_paths(&self) -> Vec<PathBuf> {
+        if let Some(path) = self.nav.current() {
+            return vec![path.clone()];
+        }
+
+        let selected = self.gallery_view.selected_paths(&self.nav);
+        if !selected.is_empty() {
+            return selected;
+        }
+
+        self.focused_path().into_iter().collect()
+    }
+
+    /// Where `path` is going if a rename of it is under way
+    fn renamed_path(&self, path: PathBuf) -> PathBuf {
+        match self.pending_renames.get(&path) {
+            Some(to) => to.clone(),
+            None => path,
+        }
+    }
+
+    /// Rename every `(from, to)` pair in the background
+    fn start_rename(
+        &mut self,
+        renames: Vec<(PathBuf, PathBuf)>,
+        done: fn(RenameReport) -> RenameMessage,
+    ) -> Task<Action<Message>> {
+        self.pending_renames.extend(renames.iter().cloned());
+
+        cosmic::task::future(async move { Message::Rename(done(rename::rename(renames).await)) })
+    }
+
+    /// Follow renamed files in the image list, selection and caches
+    fn apply_renames(&mut self, report: &RenameReport) -> Task<Action<Message>> {
+        // Only one rename runs at a time, so none is under way any more
+        self.pending_renames.clear();
+
+        if report.renamed.is_empty() {
+            return Task::none();
+        }
+
+        // Move decoded images over to their new paths so nothing is decoded again
+        for (from, to) in &report.renamed {
+            if let Some(thumbnail) = self.cache.get_thumbnail(from) {
+                self.cache.remove_thumbnail(from);
+                self.cache.insert_thumbnail(to.clone(), thumbnail);
+            }
+            if let Some(full) = self.cache.get_full(from) {
+                self.cache.remove_full(from);
+                self.cache.insert_full(to.clone(), full);
+            }
+        }
+
+        let current = self.nav.current().cloned();
+        self.nav.rename_images(&report.renamed);
+        self.gallery_view.rename_selection(&report.renamed);
+
+        let mut tasks = vec![self.load_thumbnails()];
+        if self.nav.current() != current.as_ref() {
+            tasks.push(self.update_title().map(Action::from));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Copy or move the files of `request`, reporting progress as it goes
+    fn start_transfer(
+        &mut self,
+        request: file_ops::TransferRequest,
+        collision: Collision,
+    ) -> Task<Action<Message>> {
+        self.transfer_progress = Some((request.kind, 0, request.paths.len()));
+        self.gallery_view.clear_selection();
+
+        Task::run(file_ops::transfer(request, collision), |event| {
+            Action::from(match event {
+                file_ops::TransferEvent::Progress { done, total } => {
+                    Message::TransferProgress { done, total }
+                }
+                file_ops::TransferEvent::Done(report) => Message::TransferFinished(report),
+            })
+        })
+    }
+
+    /// Ask before rotating or flipping every selected image on disk
+    fn transform_selection(&mut self, transform: Transform) {
+        let paths = self.gallery_view.selected_paths(&self.nav);
+
+        // One at a time so progress and undo stay unambiguous
+        if !paths.is_empty() && self.transform_progress.is_none() {
+            self.transform_dialog = Some((paths, transform));
+        }
+    }
+
     fn load_image(&mut self, path: PathBuf) -> Task<Action<Message>> {
-        if self.cache.get_full(&path).is_some() || self.cache.is_pending(&path) {
+        // Failed loads wait for a retry
+        if self.cache.get_full(&path).is_some()
+            || self.cache.is_pending(&path)
+            || self.cache.failure(&path).is_some()
+        {
             return Task::none();
         }
 
@@ -97,6 +306,9 @@ impl ImageViewer {
         // Remove from cache to force reload
         self.cache.remove_thumbnail(&path);
         self.cache.clear_pending_thumbnail(&path);
+        self.cache.clear_failed(&path);
+        self.thumbnails.retry(&path);
+        self.gallery_view.set_unreadable(&path, false);
 
         // Load fresh thumbnail
         if self.cache.is_thumbnail_pending(&path) {
@@ -104,7 +316,7 @@ impl ImageViewer {
         }
 
         self.cache.set_thumbnail_pending(path.clone());
-        let max_size = self.config.thumbnail_size.pixels();
+        let max_size = self.config.thumbnail_size.tier();
 
         cosmic::task::future(async move {
             match image::load_thumbnail(path.clone(), max_size).await {
@@ -112,9 +324,9 @@ impl ImageViewer {
                     path,
                     handle: img.handle,
                 }),
-                Err(_) => Message::Image(ImageMessage::LoadFailed {
+                Err(e) => Message::Image(ImageMessage::ThumbnailFailed {
                     path,
-                    error: "Failed to load thumbnail".to_string(),
+                    error: e.to_string(),
                 }),
             }
         })
@@ -131,40 +343,97 @@ impl ImageViewer {
             .count()
     }
 
-    // Load all thumbnails - rayon's thread pool handles parallelism
+    /// Thumbnails the gallery needs now, most urgent first
+    fn wanted_thumbnails(&mut self) -> Vec<PathBuf> {
+        let folder_tiles = GalleryView::folder_tile_count(&self.nav);
+        let has_parent = !self.nav.is_filtered() && self.nav.parent_dir().is_some();
+
+        let tiles = self
+            .gallery_view
+            .tiles_to_load(&self.nav, &self.cache, &self.config);
+        self.thumbnail_reach = tiles.iter().max().map_or(0, |&tile| tile + 1);
+
+        tiles
+            .into_iter()
+            .flat_map(|tile| {
+                if tile >= folder_tiles {
+                    self.nav
+                        .get(tile - folder_tiles)
+                        .cloned()
+                        .into_iter()
+                        .collect()
+                } else {
+                    // Folder tiles show a mosaic of the images inside them
+                    tile.checked_sub(usize::from(has_parent))
+                        .and_then(|folder| self.nav.folders().get(folder))
+                        .map(|folder| folder.previews.clone())
+                        .unwrap_or_default()
+                }
+            })
+            .collect()
+    }
+
+    // Decode thumbnails near the viewport first, a few at a time; finished and
+    // failed decodes and scrolling call this again for the next ones
     fn load_thumbnails(&mut self) -> Task<Action<Message>> {
-        let thumbnail_size = self.config.thumbnail_size.pixels();
-        let mut tasks = Vec::new();
+        let thumbnail_size = self.config.thumbnail_size.tier();
+        let wanted = self.wanted_thumbnails();
 
-        for path in self.nav.images().iter().cloned() {
-            // Skip if already cached or already loading
-            if self.cache.get_thumbnail(&path).is_some() || self.cache.is_thumbnail_pending(&path) {
-                continue;
-            }
+        for path in self.thumbnails.cancel_unwanted(&wanted) {
+            self.cache.clear_pending_thumbnail(&path);
+        }
 
+        let mut tasks = Vec::new();
+        for path in self.thumbnails.next(&wanted, &self.cache) {
             // Mark as pending before spawning task
             self.cache.set_thumbnail_pending(path.clone());
 
-            tasks.push(cosmic::task::future(async move {
-                match image::load_thumbnail(path.clone(), thumbnail_size).await {
-                    Ok(img) => Message::Image(ImageMessage::ThumbnailReady {
-                        path,
-                        handle: img.handle,
-                    }),
-                    Err(e) => {
-                        tracing::warn!("Thumbnail failed to load: {e}");
-                        Message::Image(ImageMessage::LoadFailed {
+            let (task, handle) = cosmic::task::future({
+                let path = path.clone();
+                async move {
+                    match image::load_thumbnail(path.clone(), thumbnail_size).await {
+                        Ok(img) => Message::Image(ImageMessage::ThumbnailReady {
+                            path,
+                            handle: img.handle,
+                        }),
+                        Err(e) => Message::Image(ImageMessage::ThumbnailFailed {
                             path,
                             error: e.to_string(),
-                        })
+                        }),
                     }
                 }
-            }));
+            })
+            .abortable();
+
+            self.thumbnails.started(path, handle);
+            tasks.push(task);
         }
 
+        // The details list reads its columns for the same rows
+        tasks.push(self.load_details());
+
         Task::batch(tasks)
     }
 
+    /// Read the details the details list or the thumbnail badges show for
+    /// the images near the view
+    fn load_details(&mut self) -> Task<Action<Message>> {
+        let paths = self
+            .gallery_view
+            .details_to_read(&self.nav, &self.cache, &self.config);
+        if paths.is_empty() {
+            return Task::none();
+        }
+
+        let fields = nav::DetailFields::from_config(&self.config);
+
+        cosmic::task::future(async move {
+            Message::View(ViewMessage::DetailsLoaded(
+                nav::read_details(paths, fields).await,
+            ))
+        })
+    }
+
     // Preload around a specific index (for gallery hover/focus)
     fn preload_around(&mut self, target_idx: usize) -> Task<Action<Message>> {
         self.preload_images_at(target_idx)
@@ -194,7 +463,10 @@ impl ImageViewer {
         for idx in start..end {
             let path = images[idx].clone();
 
-            if self.cache.get_full(&path).is_some() || self.cache.is_pending(&path) {
+            if self.cache.get_full(&path).is_some()
+                || self.cache.is_pending(&path)
+                || self.cache.failure(&path).is_some()
+            {
                 continue;
             }
 
@@ -219,6 +491,58 @@ impl ImageViewer {
         Task::batch(tasks)
     }
 
+    /// Path of the focused gallery thumbnail
+    fn focused_path(&self) -> Option<PathBuf> {
+        self.gallery_view
+            .focused_index
+            .and_then(|idx| self.nav.get(idx).cloned())
+    }
+
+    fn apply_nav_filter(&mut self, filter: Option<NavFilter>) -> Task<Action<Message>> {
+        let focused = self.focused_path();
+        self.nav.set_filter(filter);
+
+        // Keep focus on the same image if it survived the filter
+        self.gallery_view.focused_index = focused
+            .and_then(|path| self.nav.position(&path))
+            .or(if self.nav.is_empty() { None } else { Some(0) });
+
+        // Images that were hidden before may not have thumbnails yet
+        self.load_thumbnails()
+    }
+
+    fn set_thumbnail_size(&mut self, size: ThumbnailSize) -> Task<Action<Message>> {
+        let previous_tier = self.config.thumbnail_size.tier();
+        self.config.thumbnail_size = size;
+        // Within a tier the thumbnails are only scaled; past one they are
+        // decoded again at its resolution, the old ones shown until then
+        if size.tier() != previous_tier {
+            self.thumbnails.reset();
+            self.cache.mark_thumbnails_stale();
+            self.gallery_view
+                .recount_unreadable(&self.nav, &self.cache, &self.thumbnails);
+        }
+        self.load_thumbnails()
+    }
+
+    /// Focus the image that was focused before the filter was typed
+    fn restore_filter_focus(&mut self) {
+        if let Some(path) = self.gallery_view.filter_prev_focus.take()
+            && let Some(idx) = self.nav.position(&path)
+        {
+            self.gallery_view.focused_index = Some(idx);
+        }
+    }
+
+    /// Fit a newly opened image to the window, unless `--zoom` asked for
+    /// something else this session
+    fn reset_zoom(&mut self) {
+        match self.overrides.fit_to_window {
+            Some(fit) => self.image_state.reset_zoom(fit, self.config.default_zoom),
+            None => self.image_state.zoom_fit(),
+        }
+    }
+
     fn update_fit_zoom(&mut self) {
         if let Some(path) = self.nav.current()
             && let Some(cached) = self.cache.get_full(path)
@@ -230,39 +554,119 @@ impl ImageViewer {
 
     fn scan_and_nav(&mut self, path: PathBuf) -> Task<Action<Message>> {
         let dir = nav::get_image_dir(&path);
-        let include_hidden = self.config.show_hidden_files;
-        let sort_mode = self.config.sort_mode;
-        let sort_order = self.config.sort_order;
-        let target = path.clone();
+        self.scan_dir_at(dir, path)
+    }
+
+    /// Show the images of `dir`, opening `target` once it's among them, or
+    /// the gallery when `target` is a folder
+    fn scan_dir_at(&mut self, dir: Option<PathBuf>, target: PathBuf) -> Task<Action<Message>> {
+        let options = nav::ScanOptions::from_config(&self.config);
 
         // Track folder in recent folders
+        let mut config_task = Task::none();
         if let Some(ref folder_dir) = dir
             && let Some(folder_str) = folder_dir.to_str()
         {
             self.config.add_recent_folder(folder_str.to_string());
-            if let Some(ref handler) = self.config_handler {
-                let _ = self.config.write_entry(handler);
-            }
+            config_task = self.write_config();
         }
 
-        cosmic::task::future(async move {
-            let images = if let Some(dir) = dir {
-                nav::scan_dir(&dir, include_hidden, sort_mode, sort_order).await
-            } else {
-                Vec::new()
-            };
+        self.scan_id += 1;
+        self.scan_streamed = false;
+        self.scan_target_opened = false;
+        let scan_id = self.scan_id;
+        if self.nav.dir() != dir.as_ref() {
+            self.gallery_view.clear_selection();
+        }
+        self.nav.set_dir(dir.clone(), options.is_recursive());
+
+        let to_load = self.folder_tree.set_current(dir.clone());
+        let tree_task = self.load_folder_children(to_load);
+        let folders_task = self.load_gallery_folders(dir.clone());
+        self.gallery_view.focused_folder = None;
+
+        self.breadcrumb.cancel_edit();
+        self.breadcrumb.set_dir(dir.clone());
+        let crumbs_task = self.load_breadcrumb_siblings(dir.clone());
+
+        let Some(dir) = dir else {
+            let scan_task = cosmic::task::future(async move {
+                Message::Nav(NavMessage::DirectoryScanned {
+                    scan_id,
+                    images: Vec::new(),
+                    target,
+                })
+            });
+            return Task::batch([config_task, scan_task]);
+        };
+
+        // Batches populate the gallery while the rest of the directory is read
+        let scan_task = Task::run(nav::scan_dir_streaming(dir, options), move |event| {
+            let target = target.clone();
+            Action::from(Message::Nav(match event {
+                nav::ScanEvent::Batch(images) => NavMessage::DirectoryBatch {
+                    scan_id,
+                    images,
+                    target,
+                },
+                nav::ScanEvent::Done(images) => NavMessage::DirectoryScanned {
+                    scan_id,
+                    images,
+                    target,
+                },
+            }))
+        });
+
+        Task::batch([config_task, tree_task, folders_task, crumbs_task, scan_task])
+    }
 
-            Message::Nav(NavMessage::DirectoryScanned { images, target })
+    /// Show several files and folders together as one list
+    fn open_collection(
+        &mut self,
+        paths: Vec<PathBuf>,
+        open: Option<PathBuf>,
+    ) -> Task<Action<Message>> {
+        let options = nav::ScanOptions::from_config(&self.config);
+        let open = open.or_else(|| paths.iter().find(|path| path.is_file()).cloned());
+
+        self.scan_id += 1;
+        self.scan_streamed = false;
+        self.scan_target_opened = false;
+        let scan_id = self.scan_id;
+
+        self.nav.set_collection(paths.clone());
+        self.folder_tree.set_current(None);
+        self.breadcrumb.cancel_edit();
+        self.breadcrumb.set_dir(None);
+        self.gallery_view.focused_folder = None;
+        self.gallery_view.clear_selection();
+
+        cosmic::task::future(async move {
+            let images = nav::scan_collection(paths, options).await;
+            Message::Nav(NavMessage::CollectionScanned {
+                scan_id,
+                images,
+                open,
+            })
         })
     }
 
     fn reload_image_list(&mut self) -> Task<Action<Message>> {
-        let include_hidden = self.config.show_hidden_files;
-        let sort_mode = self.config.sort_mode;
-        let sort_order = self.config.sort_order;
+        let options = nav::ScanOptions::from_config(&self.config);
 
-        // If an image is selected, use its parent directory
-        let dir_option: Option<PathBuf> = if let Some(current) = self.nav.current() {
+        if let Some(sources) = self.nav.collection() {
+            let sources = sources.to_vec();
+            return cosmic::task::future(async move {
+                let images = nav::scan_collection(sources, options).await;
+                Message::Nav(NavMessage::DirectoryRefreshed { images })
+            });
+        }
+
+        // Prefer the folder that was scanned, since with subfolders included the
+        // selected image may live further down; otherwise use its parent directory
+        let dir_option: Option<PathBuf> = if let Some(dir) = self.nav.dir() {
+            Some(dir.clone())
+        } else if let Some(current) = self.nav.current() {
             nav::get_image_dir(current)
         } else if let Some(dir_str) = self.config.last_dir.as_ref() {
             Some(PathBuf::from(dir_str.clone()))
@@ -271,26 +675,105 @@ impl ImageViewer {
         };
 
         if let Some(dir) = dir_option {
-            return cosmic::task::future(async move {
-                let images = nav::scan_dir(&dir, include_hidden, sort_mode, sort_order).await;
+            self.nav.set_dir(Some(dir.clone()), options.is_recursive());
+            let folders_task = self.load_gallery_folders(Some(dir.clone()));
+
+            let scan_task = cosmic::task::future(async move {
+                let images = nav::scan_dir(&dir, options).await;
                 Message::Nav(NavMessage::DirectoryRefreshed { images })
             });
+
+            return Task::batch([folders_task, scan_task]);
         }
 
         Task::none()
     }
 
+    /// Read the subfolders of a folder tree node, if one needs them
+    fn load_folder_children(&self, dir: Option<PathBuf>) -> Task<Action<Message>> {
+        let Some(dir) = dir else {
+            return Task::none();
+        };
+        let include_hidden = self.config.show_hidden_files;
+
+        cosmic::task::future(async move {
+            let folders = folder_tree::load_children(dir.clone(), include_hidden).await;
+            Message::FolderTree(FolderTreeMessage::ChildrenLoaded {
+                parent: dir,
+                folders,
+            })
+        })
+    }
+
+    /// Read the subfolders of the open directory for the gallery's folder tiles
+    fn load_gallery_folders(&self, dir: Option<PathBuf>) -> Task<Action<Message>> {
+        let Some(dir) = dir else {
+            return Task::none();
+        };
+        let include_hidden = self.config.show_hidden_files;
+
+        cosmic::task::future(async move {
+            let folders = folder_tree::load_children(dir.clone(), include_hidden).await;
+            Message::Nav(NavMessage::FoldersScanned { dir, folders })
+        })
+    }
+
+    /// List the sibling folders for each segment of the path bar
+    fn load_breadcrumb_siblings(&self, dir: Option<PathBuf>) -> Task<Action<Message>> {
+        let Some(dir) = dir else {
+            return Task::none();
+        };
+        let include_hidden = self.config.show_hidden_files;
+
+        cosmic::task::future(async move {
+            let siblings = breadcrumb::load_siblings(dir.clone(), include_hidden).await;
+            Message::Breadcrumb(BreadcrumbMessage::SiblingsLoaded { dir, siblings })
+        })
+    }
+
+    fn complete_breadcrumb(&self) -> Task<Action<Message>> {
+        let input = self.breadcrumb.input.clone();
+        let include_hidden = self.config.show_hidden_files;
+
+        cosmic::task::future(async move {
+            let completions = breadcrumb::complete(input.clone(), include_hidden).await;
+            Message::Breadcrumb(BreadcrumbMessage::CompletionsLoaded { input, completions })
+        })
+    }
+
+    /// Count the images in each folder tree root
+    fn load_folder_counts(&self) -> Task<Action<Message>> {
+        let roots = self.folder_tree.root_paths();
+        let include_hidden = self.config.show_hidden_files;
+
+        cosmic::task::future(async move {
+            let counts = folder_tree::load_counts(roots, include_hidden).await;
+            Message::FolderTree(FolderTreeMessage::CountsLoaded(counts))
+        })
+    }
+
     fn update_title(&mut self) -> Task<Action<Message>> {
+        // There is no folder to name, so say the images were gathered ad hoc
+        let app_title = if self.nav.is_collection() {
+            format!(
+                "{} - {}",
+                fl!("collection-title", count = self.nav.total_unfiltered()),
+                fl!("app-title")
+            )
+        } else {
+            fl!("app-title")
+        };
+
         let title = if let Some(path) = self.nav.current()
             && let Some(name) = path.file_name().and_then(|name| name.to_str())
         {
             if self.edit_state.is_modified {
-                format!("{} * - {}", name, fl!("app-title"))
+                format!("{} * - {}", name, app_title)
             } else {
-                format!("{} - {}", name, fl!("app-title"))
+                format!("{} - {}", name, app_title)
             }
         } else {
-            fl!("app-title")
+            app_title
         };
 
         self.set_window_title(title, self.core.main_window_id().unwrap())
@@ -358,7 +841,7 @@ impl ImageViewer {
 
 impl Application for ImageViewer {
     type Executor = cosmic::executor::Default;
-    type Flags = Option<PathBuf>;
+    type Flags = cli::Args;
     type Message = Message;
 
     const APP_ID: &'static str = Self::APP_ID;
@@ -386,16 +869,25 @@ impl Application for ImageViewer {
             }
             Err(_) => (ViewerConfig::default(), None),
         };
+        let saved_config = config.clone();
+        let mut config = config;
+        flags.overrides.apply(&mut config);
+        let folder_tree = FolderTree::new(&config.pinned_folders);
 
         let mut app = Self {
             core,
             config,
+            saved_config,
+            overrides: flags.overrides,
             config_handler,
             key_binds: key_binds::init_key_binds(),
             nav: NavState::new(),
             cache: ImageCache::with_defaults(),
+            thumbnails: ThumbnailScheduler::new(),
             image_state: ImageViewState::new(),
             gallery_view: GalleryView::new(),
+            folder_tree,
+            breadcrumb: Breadcrumb::new(),
             context_page: None,
             is_loading: false,
             is_fullscreen: false,
@@ -403,11 +895,33 @@ impl Application for ImageViewer {
             wallpaper_dialog: None,
             available_outputs: Vec::new(),
             delete_dialog: None,
+            delete_progress: None,
+            delete_report: None,
+            transfer_dialog: None,
+            transfer_progress: None,
+            transform_dialog: None,
+            transform_progress: None,
+            transform_report: None,
+            toasts: Toasts::new(Message::CloseToast),
+            rename: None,
+            rename_report: None,
+            pending_renames: HashMap::new(),
             edit_state: EditState::new(),
             _save_dialog: None,
+            scan_id: 0,
+            scan_streamed: false,
+            scan_target_opened: false,
+            thumbnail_reach: 0,
+            zoom_unsaved_since: None,
+            start_slideshow: flags.slideshow,
+            bus: flags.bus,
+            status: watch::Sender::new(dbus::Status::default()),
         };
 
-        let startup_path = if let Some(path) = flags {
+        let mut paths = flags.paths;
+        let collection = (paths.len() > 1).then_some(paths.clone());
+
+        let startup_path = if let Some(path) = paths.pop().or_else(|| flags.start_at.clone()) {
             Some(path)
         } else if app.config.remember_last_dir {
             app.config.last_dir.as_ref().map(PathBuf::from)
@@ -423,30 +937,113 @@ impl Application for ImageViewer {
         ));
 
         tasks.push(app.set_window_title(fl!("app-title"), app.core.main_window_id().unwrap()));
-        if let Some(path) = startup_path {
-            tasks.push(app.scan_and_nav(path));
+        tasks.push(app.load_folder_counts());
+        if let Some(paths) = collection {
+            tasks.push(app.open_collection(paths, flags.start_at));
+        } else if let Some(path) = startup_path {
+            // The folder given is the one shown, with --start-at opened in it
+            let task = match flags.start_at {
+                Some(start_at) => app.scan_dir_at(nav::get_image_dir(&path), start_at),
+                None => app.scan_and_nav(path),
+            };
+            tasks.push(task);
+        }
+
+        if flags.fullscreen
+            && let Some(window_id) = app.core.main_window_id()
+        {
+            app.is_fullscreen = true;
+            tasks.push(
+                window::change_mode::<Message>(window_id, window::Mode::Fullscreen)
+                    .map(Action::from),
+            );
         }
 
         (app, Task::batch(tasks))
     }
 
     fn header_start(&self) -> Vec<Element<'_, Self::Message>> {
+        let is_pinned = self
+            .nav
+            .dir()
+            .and_then(|dir| dir.to_str())
+            .is_some_and(|dir| self.config.pinned_folders.iter().any(|pin| pin == dir));
+
         vec![menu_bar(
             &self.core,
             &self.key_binds,
             self.is_slideshow_active,
+            is_pinned,
             &self.config.recent_folders,
         )]
     }
 
+    fn header_end(&self) -> Vec<Element<'_, Self::Message>> {
+        let count = self.gallery_view.selected_paths(&self.nav).len();
+        if count == 0 {
+            return Vec::new();
+        }
+
+        vec![
+            cosmic::widget::row()
+                .push(text::body(fl!("status-selected", count = count)))
+                .push(
+                    button::icon(cosmic::widget::icon::from_name("edit-clear-symbolic"))
+                        .on_press(Message::View(ViewMessage::ClearSelection)),
+                )
+                .spacing(cosmic::theme::active().cosmic().spacing.space_xxs)
+                .align_y(cosmic::iced::Alignment::Center)
+                .into(),
+        ]
+    }
+
+    fn nav_model(&self) -> Option<&nav_bar::Model> {
+        Some(self.folder_tree.model())
+    }
+
+    fn on_nav_select(&mut self, id: nav_bar::Id) -> Task<Action<Self::Message>> {
+        let Some(path) = self.folder_tree.path(id) else {
+            return Task::none();
+        };
+
+        if self.core.is_condensed() {
+            self.core.nav_bar_set_toggled(false);
+        }
+
+        // Selecting a folder opens and expands it; selecting the open folder
+        // again expands or collapses it
+        let is_current = self.folder_tree.current() == Some(&path);
+        let to_load = self.folder_tree.select(&path);
+        let tree_task = self.load_folder_children(to_load);
+        if is_current {
+            return tree_task;
+        }
+
+        Task::batch([tree_task, self.scan_and_nav(path)])
+    }
+
     fn view(&self) -> Element<'_, Self::Message> {
-        let gallery = self.gallery_view.view(
-            &self.nav,
-            &self.cache,
-            self.config.thumbnail_size.pixels(),
-            &self.image_state,
-            &self.edit_state,
-        );
+        let gallery: Element<'_, Message> = column()
+            .push_maybe(self.nav.dir().map(|_| self.breadcrumb.view()))
+            .push_maybe(
+                self.rename
+                    .as_ref()
+                    .filter(|dialog| !dialog.is_batch())
+                    .map(RenameDialog::bar),
+            )
+            .push(self.gallery_view.view(
+                &self.nav,
+                &self.cache,
+                &self.thumbnails,
+                &self.config,
+                &self.image_state,
+                &self.edit_state,
+            ))
+            .push_maybe(self.delete_status())
+            .push_maybe(self.transfer_status())
+            .push_maybe(self.transform_status())
+            .into();
+        let gallery = toaster(&self.toasts, gallery);
 
         // Overlay crop dialog if active (takes priority over other dialogs)
         if self.edit_state.is_cropping {
@@ -487,8 +1084,8 @@ impl Application for ImageViewer {
             .on_press(Message::CloseWallpaperDialog);
 
             cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
-        } else if let Some(path) = &self.delete_dialog {
-            let dialog = self.delete_dialog_view(path);
+        } else if let Some(request) = &self.delete_dialog {
+            let dialog = self.delete_dialog_view(request);
 
             let backdrop = cosmic::widget::mouse_area(
                 cosmic::widget::container(cosmic::widget::Space::new(
@@ -502,49 +1099,210 @@ impl Application for ImageViewer {
             .on_press(Message::CloseDeleteDialog);
 
             cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
+        } else if let Some((paths, transform)) = &self.transform_dialog {
+            let dialog = self.transform_dialog_view(paths, *transform);
+
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::CloseTransformDialog);
+
+            cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
+        } else if let Some(request) = &self.transfer_dialog {
+            let dialog = self.transfer_dialog_view(request);
+
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::CloseTransferDialog);
+
+            cosmic::iced_widget::stack![gallery, backdrop, dialog].into()
+        } else if let Some(dialog) = self.rename.as_ref().filter(|dialog| dialog.is_batch()) {
+            let backdrop = cosmic::widget::mouse_area(
+                cosmic::widget::container(cosmic::widget::Space::new(
+                    cosmic::iced::Length::Fill,
+                    cosmic::iced::Length::Fill,
+                ))
+                .width(cosmic::iced::Length::Fill)
+                .height(cosmic::iced::Length::Fill)
+                .class(cosmic::theme::Container::Transparent),
+            )
+            .on_press(Message::Rename(RenameMessage::Cancel));
+
+            cosmic::iced_widget::stack![gallery, backdrop, dialog.dialog()].into()
         } else {
             gallery
         }
     }
 
     fn update(&mut self, message: Message) -> Task<Action<Self::Message>> {
-        let mut tasks = Vec::new();
+        let task = self.handle_message(message);
+        // Whichever way a handler returned, let the D-Bus interface know
+        // what changed
+        self.publish_status();
+        task
+    }
 
-        match message {
-            Message::Image(img_msg) => match img_msg {
-                ImageMessage::Loading(path) => {
-                    self.is_loading = true;
-                    tasks.push(self.load_image(path));
-                }
-                ImageMessage::Loaded {
-                    path,
-                    handle,
-                    width,
-                    height,
-                } => {
-                    self.is_loading = false;
-                    self.cache.insert_full(
-                        path.clone(),
-                        CachedImage {
-                            handle,
-                            width,
-                            height,
-                        },
-                    );
-                    // Update fit_zoom if this is the current image
-                    if self.nav.current() == Some(&path) {
-                        self.image_state.calculate_fit_zoom(width, height);
+    fn context_drawer(&self) -> Option<context_drawer::ContextDrawer<'_, Self::Message>> {
+        let page = self.context_page?;
+        let content = match page {
+            ContextPage::About => self.about_page(),
+            ContextPage::Settings => self.settings_page(),
+            ContextPage::ImageInfo => self.image_info_page(),
+        };
+
+        Some(context_drawer::context_drawer(
+            content,
+            Message::ToggleContextPage(page),
+        ))
+    }
+
+    fn subscription(&self) -> cosmic::iced::Subscription<Self::Message> {
+        // Setup the subscription to watch the current directory, as deep as it was scanned
+        let options = nav::ScanOptions::from_config(&self.config);
+        let watcher_sub = watcher::watch_directory(
+            self.nav.dir().cloned(),
+            options.depth,
+            options.include_hidden,
+        )
+        .map(Message::WatcherEvent);
+
+        // Slideshow timer
+        let slideshow_sub = if self.is_slideshow_active {
+            cosmic::iced::time::every(Duration::from_secs(self.config.slideshow_interval as u64))
+                .map(|_| Message::SlideshowTick)
+        } else {
+            cosmic::iced::Subscription::none()
+        };
+
+        // Checks whether a gallery zoom has settled enough to be saved
+        let zoom_save_sub = if self.zoom_unsaved_since.is_some() {
+            cosmic::iced::time::every(ZOOM_SAVE_DELAY)
+                .map(|_| Message::View(ViewMessage::SaveGalleryZoom))
+        } else {
+            cosmic::iced::Subscription::none()
+        };
+
+        // Later launches hand their paths to this instance, and scripts control it
+        let dbus_sub = if let Some(bus) = &self.bus {
+            dbus::serve(bus.clone(), self.status.subscribe())
+        } else {
+            cosmic::iced::Subscription::none()
+        };
+
+        cosmic::iced::Subscription::batch([
+            cosmic::iced::keyboard::on_key_press(key_press_handler),
+            cosmic::iced::window::events().map(|(_, event)| {
+                if let cosmic::iced::window::Event::Resized(size) = event {
+                    Message::WindowResized {
+                        width: size.width,
+                        height: size.height,
+                    }
+                } else {
+                    Message::Cancelled // Use existing no-op message for other window events
+                }
+            }),
+            watcher_sub,
+            slideshow_sub,
+            zoom_save_sub,
+            dbus_sub,
+        ])
+    }
+
+    fn on_app_exit(&mut self) -> Option<Self::Message> {
+        // Too late to show an error
+        let _ = self.write_config();
+
+        // Rotations can't be undone after quitting
+        if let Some(report) = self.transform_report.take() {
+            file_ops::discard_backups(&report);
+        }
+
+        None
+    }
+}
+
+impl ImageViewer {
+    fn handle_message(&mut self, message: Message) -> Task<Action<Message>> {
+        let mut tasks = Vec::new();
+
+        match message {
+            Message::Image(img_msg) => match img_msg {
+                ImageMessage::Loading(path) => {
+                    self.is_loading = true;
+                    tasks.push(self.load_image(path));
+                }
+                ImageMessage::Loaded {
+                    path,
+                    handle,
+                    width,
+                    height,
+                } => {
+                    self.is_loading = false;
+                    self.cache.insert_full(
+                        path.clone(),
+                        CachedImage {
+                            handle,
+                            width,
+                            height,
+                        },
+                    );
+                    // Update fit_zoom if this is the current image
+                    if self.nav.current() == Some(&path) {
+                        self.image_state.calculate_fit_zoom(width, height);
                     }
                     tasks.push(self.update_title());
                 }
                 ImageMessage::LoadFailed { path, error } => {
                     self.is_loading = false;
                     self.cache.clear_pending(&path);
-                    self.cache.clear_pending_thumbnail(&path);
                     tracing::error!("Failed to load {}: {error}", path.display());
+                    if self.nav.position(&path).is_some() {
+                        self.gallery_view.set_unreadable(&path, true);
+                    }
+                    // The modal shows the reason in place of the image
+                    self.cache.set_failed(path, error);
                 }
                 ImageMessage::ThumbnailReady { path, handle } => {
+                    let scheduled = self.thumbnails.finished(&path, None);
                     self.cache.insert_thumbnail(path, handle);
+
+                    if scheduled {
+                        tasks.push(self.load_thumbnails());
+                    }
+                }
+                ImageMessage::ThumbnailFailed { path, error } => {
+                    self.cache.clear_pending_thumbnail(&path);
+                    tracing::warn!("Thumbnail of {} failed to load: {error}", path.display());
+                    if self.nav.position(&path).is_some() {
+                        self.gallery_view.set_unreadable(&path, true);
+                    }
+
+                    if self.thumbnails.finished(&path, Some(error)) {
+                        tasks.push(self.load_thumbnails());
+                    }
+                }
+                ImageMessage::Retry(path) => {
+                    self.gallery_view.set_unreadable(&path, false);
+                    if self.thumbnails.failure(&path).is_some() {
+                        tasks.push(self.reload_thumbnail(path.clone()));
+                    }
+                    self.cache.clear_failed(&path);
+                    if self.nav.current() == Some(&path) {
+                        tasks.push(self.load_image(path));
+                    }
                 }
                 ImageMessage::Clear => {
                     self.nav = NavState::new();
@@ -559,7 +1317,7 @@ impl Application for ImageViewer {
                         // Modal open: navigate images
                         self.edit_state.reset();
                         self.nav.go_next();
-                        self.image_state.zoom_fit(); // Reset to fit mode for new image
+                        self.reset_zoom();
                         self.update_fit_zoom();
                         tasks.push(self.load_current_image());
                         tasks.push(self.preload_images());
@@ -583,7 +1341,7 @@ impl Application for ImageViewer {
                     if self.nav.is_selected() {
                         self.edit_state.reset();
                         self.nav.go_prev();
-                        self.image_state.zoom_fit(); // Reset to fit mode for new image
+                        self.reset_zoom();
                         self.update_fit_zoom();
                         tasks.push(self.load_current_image());
                         tasks.push(self.preload_images());
@@ -606,7 +1364,7 @@ impl Application for ImageViewer {
                     self.is_slideshow_active = false;
                     self.edit_state.reset();
                     self.nav.first();
-                    self.image_state.zoom_fit(); // Reset to fit mode for new image
+                    self.reset_zoom();
                     self.update_fit_zoom();
                     tasks.push(self.load_current_image());
                     tasks.push(self.update_title().map(Action::from));
@@ -615,7 +1373,7 @@ impl Application for ImageViewer {
                     self.is_slideshow_active = false;
                     self.edit_state.reset();
                     self.nav.last();
-                    self.image_state.zoom_fit(); // Reset to fit mode for new image
+                    self.reset_zoom();
                     self.update_fit_zoom();
                     tasks.push(self.load_current_image());
                     tasks.push(self.update_title().map(Action::from));
@@ -624,68 +1382,225 @@ impl Application for ImageViewer {
                     self.is_slideshow_active = false;
                     self.edit_state.reset();
                     self.nav.go_to(idx);
-                    self.image_state.zoom_fit(); // Reset to fit mode for new image
+                    self.reset_zoom();
                     self.update_fit_zoom();
                     tasks.push(self.load_current_image());
                     tasks.push(self.update_title().map(Action::from));
                 }
                 NavMessage::GalleryFocus(idx) => {
                     self.gallery_view.focused_index = Some(idx);
+                    self.gallery_view.focused_folder = None;
                     // Preload images around focused item for faster modal opening
                     tasks.push(self.preload_around(idx));
                 }
+                NavMessage::FolderFocus(idx) => {
+                    self.gallery_view.focused_folder = Some(idx);
+                }
+                NavMessage::CollectionScanned {
+                    scan_id,
+                    images,
+                    open,
+                } => {
+                    if scan_id != self.scan_id {
+                        return Task::none();
+                    }
+
+                    self.nav.set_images(images, open.as_deref());
+                    self.gallery_view
+                        .recount_unreadable(&self.nav, &self.cache, &self.thumbnails);
+                    self.gallery_view.focused_index =
+                        self.nav
+                            .index()
+                            .or(if self.nav.is_empty() { None } else { Some(0) });
+
+                    tasks.push(self.load_thumbnails());
+                    tasks.push(self.load_current_image());
+                    tasks.push(self.preload_images());
+                    tasks.push(self.update_title());
+
+                    if std::mem::take(&mut self.start_slideshow) {
+                        tasks.push(self.update(Message::View(ViewMessage::StartSlideshow)));
+                    }
+                }
+                NavMessage::FoldersScanned { dir, folders } => {
+                    // Ignore listings for a folder the user already left
+                    if self.nav.dir() == Some(&dir) {
+                        self.nav.set_folders(folders);
+                        tasks.push(self.load_thumbnails());
+                    }
+                }
+                NavMessage::GoUp => {
+                    // Backspace only navigates folders while the gallery is showing
+                    if !self.nav.is_selected()
+                        && let Some(parent) = self.nav.parent_dir()
+                    {
+                        tasks.push(self.scan_and_nav(parent.to_path_buf()));
+                    }
+                }
                 NavMessage::GallerySelect(idx) => {
+                    self.gallery_view.selection_anchor = self.nav.get(idx).cloned();
                     self.nav.select(idx);
-                    self.image_state.zoom_fit();
+                    self.reset_zoom();
                     self.update_fit_zoom();
                     tasks.push(self.load_current_image());
                     tasks.push(self.preload_images());
                 }
-                NavMessage::DirectoryScanned { images, target } => {
-                    self.nav.set_images(images, Some(&target));
-                    // Save last directory if enabled
-                    if self.config.remember_last_dir {
-                        // Get the directory
-                        let dir = if target.is_file() {
-                            target.parent().map(|parent| parent.to_path_buf())
-                        } else {
-                            Some(target.clone())
-                        };
+                NavMessage::FilterChanged(query) => {
+                    // Remember where the user was before narrowing the list
+                    if !self.nav.is_filtered() && self.gallery_view.filter_prev_focus.is_none() {
+                        self.gallery_view.filter_prev_focus = self.focused_path();
+                    }
 
-                        if let Some(dir) = dir {
-                            self.config.last_dir = Some(dir.to_string_lossy().to_string());
+                    self.gallery_view.filter_query = query;
+
+                    // An incomplete regex keeps the last valid filter applied
+                    match NavFilter::parse(&self.gallery_view.filter_query) {
+                        Ok(Some(filter)) => tasks.push(self.apply_nav_filter(Some(filter))),
+                        Ok(None) => {
+                            // Erased by hand, which clears it as much as ClearFilter does
+                            tasks.push(self.apply_nav_filter(None));
+                            self.restore_filter_focus();
                         }
+                        Err(err) => tracing::debug!("Invalid filter: {err}"),
+                    }
+                }
+                NavMessage::ClearFilter => {
+                    self.gallery_view.filter_query.clear();
+                    self.gallery_view.filter_visible = false;
+                    tasks.push(self.apply_nav_filter(None));
+                    self.restore_filter_focus();
+                }
+                NavMessage::DirectoryBatch {
+                    scan_id,
+                    images,
+                    target,
+                } => {
+                    if scan_id != self.scan_id {
+                        return Task::none();
                     }
 
-                    // Open modal only if a specific image file was requested
-                    if target.is_file() && self.nav.total() > 0 {
-                        self.nav.select(self.nav.index().unwrap_or(0));
-                    } else if self.nav.total() > 0 {
-                        // Focus first image in gallery - FlexGrid handles scrolling
+                    // Where the batch starts, among the images and among the tiles
+                    let streamed = self.scan_streamed;
+                    let first = if streamed { self.nav.total() } else { 0 };
+                    let first_tile = GalleryView::folder_tile_count(&self.nav) + first;
+
+                    if streamed {
+                        self.nav.extend_images(images);
+                    } else {
+                        self.scan_streamed = true;
+                        self.nav.set_images(images, None);
+                        self.gallery_view.focused_index = None;
+                    }
+
+                    // Open the requested image as soon as its batch arrives
+                    if !self.scan_target_opened
+                        && target.is_file()
+                        && let Some(idx) = self.nav.entries()[first..]
+                            .iter()
+                            .position(|entry| entry.path == target)
+                            .map(|offset| first + offset)
+                    {
+                        self.scan_target_opened = true;
+                        self.nav.select(idx);
+                        self.gallery_view.focused_index = Some(idx);
+                        tasks.push(self.load_current_image());
+                    }
+
+                    if self.gallery_view.focused_index.is_none() && !self.nav.is_empty() {
                         self.gallery_view.focused_index = Some(0);
                     }
 
+                    // Later batches mostly land below the tiles being loaded;
+                    // scrolling down to them schedules their thumbnails
+                    if !streamed || first_tile <= self.thumbnail_reach {
+                        tasks.push(self.load_thumbnails());
+                    }
+                }
+                NavMessage::DirectoryScanned {
+                    scan_id,
+                    images,
+                    target,
+                } => {
+                    if scan_id != self.scan_id {
+                        return Task::none();
+                    }
+
+                    // The final listing is sorted; keep whatever the user moved to meanwhile
+                    let streamed = self.scan_streamed;
+                    let focused = streamed.then(|| self.focused_path()).flatten();
+                    let current = if streamed {
+                        self.nav.current().cloned()
+                    } else {
+                        None
+                    };
+                    let select = current.or_else(|| {
+                        (target.is_file() && !self.scan_target_opened).then(|| target.clone())
+                    });
+                    self.nav.set_images(images, select.as_deref());
+                    self.gallery_view
+                        .recount_unreadable(&self.nav, &self.cache, &self.thumbnails);
+
+                    // Save last directory if enabled; the folder scanned, which
+                    // holds the target even when that is in a subfolder
+                    if self.config.remember_last_dir
+                        && let Some(dir) = self.nav.dir()
+                    {
+                        self.config.last_dir = Some(dir.to_string_lossy().to_string());
+                    }
+
+                    // Open modal only if a specific image file was requested
+                    if let Some(idx) = self.nav.index() {
+                        self.nav.select(idx);
+                    } else if target.is_file() && !self.scan_target_opened && self.nav.total() > 0 {
+                        self.nav.select(0);
+                    }
+
+                    // Focus first image in gallery unless the user already moved - FlexGrid handles scrolling
+                    self.gallery_view.focused_index = focused
+                        .and_then(|path| self.nav.position(&path))
+                        .or(self.nav.index())
+                        .or(if self.nav.is_empty() { None } else { Some(0) });
+
                     tasks.push(self.load_thumbnails());
                     tasks.push(self.load_current_image());
                     tasks.push(self.preload_images());
+
+                    if std::mem::take(&mut self.start_slideshow) {
+                        tasks.push(self.update(Message::View(ViewMessage::StartSlideshow)));
+                    }
                 }
                 NavMessage::DirectoryRefreshed { images } => {
                     let was_selected = self.nav.is_selected();
-                    let prev_path = self.nav.current().cloned();
+                    let prev_path = self
+                        .nav
+                        .current()
+                        .cloned()
+                        .map(|path| self.renamed_path(path));
                     let prev_idx = self.nav.index().unwrap_or(0);
+                    let focused = self.focused_path().map(|path| self.renamed_path(path));
 
                     // Update image list; clearing the selection
-                    self.nav.set_images(images.clone(), None);
+                    self.nav.set_images(images, None);
+                    self.gallery_view
+                        .recount_unreadable(&self.nav, &self.cache, &self.thumbnails);
+
+                    // Keep gallery focus on the same image, even if it was renamed
+                    // and sorted elsewhere, or near where it was if it went away
+                    let last = self.nav.total().checked_sub(1);
+                    self.gallery_view.focused_index = focused
+                        .and_then(|path| self.nav.position(&path))
+                        .or_else(|| {
+                            self.gallery_view
+                                .focused_index
+                                .zip(last)
+                                .map(|(idx, last)| idx.min(last))
+                        });
 
                     if was_selected {
                         if self.nav.total() > 0 {
                             // Try to restore selection to same image or nearest neighbor
-                            let new_idx = if let Some(ref path) = prev_path {
-                                // Find the image in the new list
-                                images.iter().position(|pos| pos == path)
-                            } else {
-                                None
-                            };
+                            let new_idx =
+                                prev_path.as_ref().and_then(|path| self.nav.position(path));
 
                             let idx = new_idx.unwrap_or_else(|| {
                                 // Image was deleted, use prev_idx clamped to valid range
@@ -696,7 +1611,7 @@ impl Application for ImageViewer {
 
                             // Reset zoom if showing different image
                             if new_idx.is_none() {
-                                self.image_state.zoom_fit();
+                                self.reset_zoom();
                             }
 
                             self.update_fit_zoom();
@@ -733,6 +1648,23 @@ impl Application for ImageViewer {
                     return window::change_mode::<Message>(window_id, mode).map(Action::from);
                 }
                 ViewMessage::CloseModal => {
+                    if self.breadcrumb.editing {
+                        self.breadcrumb.cancel_edit();
+                        return Task::none();
+                    }
+                    if self.rename.take().is_some() {
+                        return Task::none();
+                    }
+
+                    // Without a modal, Escape dismisses the filter bar, then the selection
+                    if !self.nav.is_selected() && self.gallery_view.filter_visible {
+                        return self.update(Message::Nav(NavMessage::ClearFilter));
+                    }
+                    if !self.nav.is_selected() && !self.gallery_view.selected.is_empty() {
+                        self.gallery_view.clear_selection();
+                        return Task::none();
+                    }
+
                     // Close the modal
                     self.nav.deselect();
                     // Reset zoom state
@@ -793,7 +1725,7 @@ impl Application for ImageViewer {
                 ViewMessage::SelectFocused => {
                     if let Some(idx) = self.gallery_view.focused_index {
                         self.nav.select(idx);
-                        self.image_state.zoom_fit();
+                        self.reset_zoom();
                         self.update_fit_zoom();
                         tasks.push(self.load_current_image());
                     }
@@ -817,6 +1749,42 @@ impl Application for ImageViewer {
                         tasks.push(self.update(Message::View(ViewMessage::StartSlideshow)));
                     }
                 }
+                ViewMessage::GalleryScrolled(viewport) => {
+                    self.gallery_view.set_viewport(viewport);
+                    tasks.push(self.load_thumbnails());
+                }
+                ViewMessage::SaveGalleryZoom => {
+                    if self
+                        .zoom_unsaved_since
+                        .is_some_and(|since| since.elapsed() >= ZOOM_SAVE_DELAY)
+                    {
+                        self.zoom_unsaved_since = None;
+                        tasks.push(self.write_config());
+                    }
+                }
+                ViewMessage::GalleryZoom(request) => {
+                    // The details list has fixed rows
+                    if self.config.gallery_layout == GalleryLayout::List {
+                        return Task::none();
+                    }
+
+                    let size = self.config.thumbnail_size.zoomed(request.steps);
+                    if size != self.config.thumbnail_size {
+                        // Saved once the wheel rests rather than on every step
+                        tasks.push(self.set_thumbnail_size(size));
+                        self.zoom_unsaved_since = Some(Instant::now());
+                        if let Some(offset) = self.gallery_view.zoom_offset(
+                            &self.nav,
+                            &self.cache,
+                            &self.config,
+                            request,
+                        ) {
+                            tasks.push(
+                                self.update(Message::View(ViewMessage::GalleryScrollTo(offset))),
+                            );
+                        }
+                    }
+                }
                 ViewMessage::GalleryScrollTo(offset_y) => {
                     return scrollable::scroll_to(
                         Id::new(GalleryView::SCROLL_ID),
@@ -829,10 +1797,37 @@ impl Application for ImageViewer {
                 ViewMessage::ImageEditEvent => {
                     // TODO: Add the image edit events
                 }
+                ViewMessage::ShowFilter => {
+                    self.gallery_view.filter_visible = true;
+                    return cosmic::widget::text_input::focus(Id::new(GalleryView::FILTER_ID));
+                }
+                ViewMessage::Select(change) => {
+                    self.gallery_view.change_selection(&self.nav, change);
+                }
+                ViewMessage::SelectAll => {
+                    // In the modal Ctrl+A has nothing to select
+                    if !self.nav.is_selected() {
+                        self.gallery_view.select_all(&self.nav);
+                    }
+                }
+                ViewMessage::ClearSelection => self.gallery_view.clear_selection(),
+                ViewMessage::DetailsLoaded(details) => self.gallery_view.set_details(details),
+                ViewMessage::ToggleGroup(index) => {
+                    self.gallery_view
+                        .toggle_group(&self.nav, &self.config, index);
+                    // Tiles of an expanded section come into view
+                    tasks.push(self.load_thumbnails());
+                }
+                ViewMessage::SelectGroup(index) => {
+                    self.gallery_view
+                        .select_group(&self.nav, &self.config, index);
+                }
             },
             Message::Edit(edit_msg) => match edit_msg {
                 EditMessage::Rotate90 => {
-                    if let Some(current_path) = self.nav.current() {
+                    if !self.nav.is_selected() && !self.gallery_view.selected.is_empty() {
+                        self.transform_selection(Transform::Rotate90);
+                    } else if let Some(current_path) = self.nav.current() {
                         if !self.edit_state.is_editing() {
                             self.edit_state.start_editing(current_path.clone());
                         }
@@ -844,7 +1839,9 @@ impl Application for ImageViewer {
                     }
                 }
                 EditMessage::Rotate180 => {
-                    if let Some(current_path) = self.nav.current() {
+                    if !self.nav.is_selected() && !self.gallery_view.selected.is_empty() {
+                        self.transform_selection(Transform::Rotate180);
+                    } else if let Some(current_path) = self.nav.current() {
                         if !self.edit_state.is_editing() {
                             self.edit_state.start_editing(current_path.clone());
                         }
@@ -856,7 +1853,9 @@ impl Application for ImageViewer {
                     }
                 }
                 EditMessage::FlipHorizontal => {
-                    if let Some(current_path) = self.nav.current() {
+                    if !self.nav.is_selected() && !self.gallery_view.selected.is_empty() {
+                        self.transform_selection(Transform::FlipHorizontal);
+                    } else if let Some(current_path) = self.nav.current() {
                         if !self.edit_state.is_editing() {
                             self.edit_state.start_editing(current_path.clone());
                         }
@@ -867,7 +1866,9 @@ impl Application for ImageViewer {
                     }
                 }
                 EditMessage::FlipVertical => {
-                    if let Some(current_path) = self.nav.current() {
+                    if !self.nav.is_selected() && !self.gallery_view.selected.is_empty() {
+                        self.transform_selection(Transform::FlipVertical);
+                    } else if let Some(current_path) = self.nav.current() {
                         if !self.edit_state.is_editing() {
                             self.edit_state.start_editing(current_path.clone());
                         }
@@ -931,6 +1932,7 @@ impl Application for ImageViewer {
 
                                 // Clear caches for saved image
                                 self.cache.remove_full(&path_clone);
+                                self.gallery_view.mark_edited(path_clone.clone());
 
                                 // Reload the thumbnail from the saved file
                                 tasks.push(
@@ -954,12 +1956,18 @@ impl Application for ImageViewer {
                                         }
                                     }
                                 }
+
+                                tasks.push(self.toast(fl!(
+                                    "toast-saved",
+                                    name = folder_tree::display_name(&path_clone)
+                                )));
                             }
                             self.edit_state.reset();
                             tasks.push(self.update_title().map(Action::from));
                         }
                         Err(err) => {
                             tracing::error!("Save failed: {err}");
+                            tasks.push(self.toast(fl!("toast-save-failed", error = err)));
                         }
                     }
                 }
@@ -967,6 +1975,22 @@ impl Application for ImageViewer {
                     if self.edit_state.undo() {
                         tasks.push(self.reload_with_edits().map(Action::from));
                         tasks.push(self.update_title().map(Action::from));
+                    } else if !self.nav.is_selected()
+                        && self
+                            .rename_report
+                            .as_ref()
+                            .is_some_and(|report| !report.renamed.is_empty())
+                    {
+                        // Nothing to undo in the gallery but the last rename
+                        tasks.push(self.update(Message::Rename(RenameMessage::Undo(None))));
+                    } else if !self.nav.is_selected()
+                        && self
+                            .delete_report
+                            .as_ref()
+                            .is_some_and(|report| report.can_undo())
+                    {
+                        // Nothing to undo in the gallery but the last trash delete
+                        tasks.push(self.update(Message::UndoTrash(None)));
                     }
                 }
                 EditMessage::StartCrop => {
@@ -1016,20 +2040,44 @@ impl Application for ImageViewer {
                     SettingsMessage::AppTheme(theme) => {
                         self.config.app_theme = theme;
                         // Save config and apply theme
-                        if let Some(ref handler) = self.config_handler {
-                            let _ = self.config.write_entry(handler);
-                        }
-                        return cosmic::command::set_theme(theme.to_cosmic_theme());
+                        return Task::batch([
+                            self.write_config(),
+                            cosmic::command::set_theme(theme.to_cosmic_theme()),
+                        ]);
                     }
                     SettingsMessage::DefaultZoom(zoom) => self.config.default_zoom = zoom,
                     SettingsMessage::FitToWindow(fit) => self.config.fit_to_window = fit,
                     SettingsMessage::SmoothScaling(smooth) => self.config.smooth_scaling = smooth,
                     SettingsMessage::ThumbnailSize(size) => {
-                        self.config.thumbnail_size = size;
-                        // Clear thumbnail cache and for regeneration
-                        self.cache.clear_thumbnails();
+                        tasks.push(self.set_thumbnail_size(size));
+                    }
+                    SettingsMessage::GalleryLayout(layout) => {
+                        let was_list = self.config.gallery_layout == GalleryLayout::List;
+                        let was_justified = self.config.gallery_layout == GalleryLayout::Justified;
+                        self.config.gallery_layout = layout;
+                        if self.config.date_grouping != DateGrouping::Off
+                            && was_list != (layout == GalleryLayout::List)
+                        {
+                            // The details list keeps its own sort order
+                            tasks.push(self.reload_image_list());
+                        } else if !was_justified && layout == GalleryLayout::Justified {
+                            // Justified rows need every image's dimensions
+                            tasks.push(self.reload_image_list());
+                        } else {
+                            // Other tiles come into view
+                            tasks.push(self.load_thumbnails());
+                        }
+                    }
+                    SettingsMessage::ThumbnailBadge(badge, show) => {
+                        self.config.thumbnail_badges.set(badge, show);
+                        // Ratings and animation come from the files' details
                         tasks.push(self.load_thumbnails());
                     }
+                    SettingsMessage::DateGrouping(grouping) => {
+                        self.config.date_grouping = grouping;
+                        // Grouping sorts by capture date
+                        tasks.push(self.reload_image_list());
+                    }
                     SettingsMessage::ShowHiddenFiles(show) => {
                         self.config.show_hidden_files = show;
                         // Reload the current directory with the setting
@@ -1058,12 +2106,29 @@ impl Application for ImageViewer {
                         // Reload the current directory with the new sort order
                         tasks.push(self.reload_image_list());
                     }
+                    SettingsMessage::IncludeSubfolders(include) => {
+                        self.config.include_subfolders = include;
+                        tasks.push(self.reload_image_list());
+                    }
+                    SettingsMessage::SubfolderDepth(depth) => {
+                        self.config.subfolder_depth = depth;
+                        if self.config.include_subfolders {
+                            tasks.push(self.reload_image_list());
+                        }
+                    }
+                    SettingsMessage::GroupByFolder(group) => {
+                        self.config.group_by_folder = group;
+                        if self.config.include_subfolders {
+                            tasks.push(self.reload_image_list());
+                        }
+                    }
+                    SettingsMessage::QuickSortFolder(slot, folder) => {
+                        self.config.set_quick_sort_folder(slot, folder)
+                    }
                 }
 
                 // Save config changes
-                if let Some(ref handler) = self.config_handler {
-                    let _ = self.config.write_entry(handler);
-                }
+                tasks.push(self.write_config());
             }
             Message::KeyBind(action) => tasks.push(self.update(action.message())),
             Message::Surface(action) => {
@@ -1112,56 +2177,245 @@ impl Application for ImageViewer {
             }
             Message::ClearRecentFolders => {
                 self.config.recent_folders.clear();
-                if let Some(ref handler) = self.config_handler {
-                    let _ = self.config.write_entry(handler);
-                }
-            }
-            Message::Cancelled => {}
-            Message::OpenError(why) => eprintln!("{why}"),
-            Message::FilesSelected(paths) => {
-                if let Some(path) = paths.first() {
-                    tasks.push(self.scan_and_nav(path.clone()));
-                }
-            }
-            Message::OpenPath(path) => tasks.push(self.scan_and_nav(path)),
-            Message::SystemThemeChanged => {
-                // TODO: Implement theme changing
+                tasks.push(self.write_config());
             }
-            Message::ConfigChanged => {
-                if let Some(ref handler) = self.config_handler {
-                    match ViewerConfig::get_entry(handler) {
-                        Ok(config) => self.config = config,
-                        Err((_, config)) => self.config = config,
+            Message::TogglePinFolder => {
+                if let Some(dir) = self.nav.dir().and_then(|dir| dir.to_str()) {
+                    let dir = dir.to_string();
+                    if self.config.pinned_folders.contains(&dir) {
+                        self.config.pinned_folders.retain(|pin| pin != &dir);
+                    } else {
+                        self.config.pinned_folders.push(dir);
                     }
+
+                    tasks.push(self.write_config());
+
+                    self.folder_tree.set_pinned(&self.config.pinned_folders);
+                    tasks.push(self.load_folder_counts());
                 }
             }
-            Message::WatcherEvent(evt) => {
-                match evt {
-                    watcher::WatcherEvent::Created(_) => {
-                        tasks.push(self.reload_image_list());
-                        // TODO: Do this more elegantly at some point
+            Message::Rename(rename_message) => match rename_message {
+                RenameMessage::Start => {
+                    let paths = self.target_paths();
+                    if paths.is_empty() || !self.pending_renames.is_empty() {
+                        return Task::none();
                     }
-                    watcher::WatcherEvent::Modified(path) => {
-                        // On some systems, external deletion reports as Modified
-                        if !path.exists() {
-                            self.cache.clear_pending(&path);
-                            if self.nav.current() == Some(&path) {
-                                self.nav.deselect();
-                            }
 
-                            tasks.push(self.reload_image_list());
+                    self.rename = Some(RenameDialog::new(paths));
+                    tasks.push(cosmic::widget::text_input::focus(Id::new(
+                        RenameDialog::INPUT_ID,
+                    )));
+                    // Typing replaces the whole name or pattern
+                    tasks.push(cosmic::widget::text_input::select_all(Id::new(
+                        RenameDialog::INPUT_ID,
+                    )));
+                }
+                RenameMessage::Input(input) => {
+                    if let Some(dialog) = &mut self.rename {
+                        dialog.set_input(input);
+
+                        if let Some(paths) = dialog.request_info() {
+                            return cosmic::task::future(async move {
+                                let info = rename::read_info(paths.clone()).await;
+                                Message::Rename(RenameMessage::InfoLoaded { paths, info })
+                            });
                         }
                     }
-                    watcher::WatcherEvent::Removed(path) => {
-                        self.cache.clear_pending(&path);
-                        // If the deleted image is the one in the modal, deselect it
-                        // so reload_image_list falls back to last_dir
-                        if self.nav.current() == Some(&path) {
-                            self.nav.deselect();
-                        }
-
-                        // Let DirectoryRefresh handle modal transition
-                        tasks.push(self.reload_image_list());
+                }
+                RenameMessage::InfoLoaded { paths, info } => {
+                    if let Some(dialog) = &mut self.rename {
+                        dialog.set_info(&paths, info);
+                    }
+                }
+                RenameMessage::Submit => {
+                    if let Some(renames) = self.rename.as_ref().and_then(RenameDialog::renames) {
+                        self.rename = None;
+                        return self.start_rename(renames, RenameMessage::Finished);
+                    }
+                }
+                RenameMessage::Cancel => {
+                    self.rename = None;
+                }
+                RenameMessage::Finished(report) => {
+                    for err in &report.errors {
+                        tracing::error!("Rename failed: {err}");
+                    }
+                    tasks.push(self.apply_renames(&report));
+
+                    let count = report.renamed.len();
+                    let mut summary = Vec::new();
+                    if count > 0 {
+                        summary.push(fl!("rename-done", count = count));
+                    }
+                    if !report.errors.is_empty() {
+                        summary.push(fl!("rename-failed", count = report.errors.len()));
+                    }
+                    let toast = Toast::new(summary.join(" · "));
+                    let toast = if count > 0 {
+                        toast.action(fl!("rename-undo"), |id| {
+                            Message::Rename(RenameMessage::Undo(Some(id)))
+                        })
+                    } else {
+                        toast
+                    };
+                    tasks.push(self.toasts.push(toast).map(Action::App));
+
+                    // Ctrl+Z now takes back the rename rather than an earlier delete
+                    self.delete_report = None;
+                    self.rename_report = Some(report);
+                }
+                RenameMessage::Undo(toast) => {
+                    if let Some(id) = toast {
+                        self.toasts.remove(id);
+                    }
+                    if self.pending_renames.is_empty()
+                        && let Some(report) = self.rename_report.take()
+                    {
+                        return self.start_rename(report.reversed(), RenameMessage::Undone);
+                    }
+                }
+                RenameMessage::Undone(report) => {
+                    for err in &report.errors {
+                        tracing::error!("Undoing the rename failed: {err}");
+                    }
+                    tasks.push(self.apply_renames(&report));
+                }
+            },
+            Message::Breadcrumb(crumb_message) => match crumb_message {
+                BreadcrumbMessage::SiblingsLoaded { dir, siblings } => {
+                    self.breadcrumb.set_siblings(&dir, siblings);
+                }
+                BreadcrumbMessage::SelectSibling(segment, idx) => {
+                    self.breadcrumb.close_siblings();
+                    if let Some(path) = self.breadcrumb.sibling(segment, idx).cloned() {
+                        tasks.push(self.scan_and_nav(path));
+                    }
+                }
+                BreadcrumbMessage::ToggleSiblings(segment) => {
+                    self.breadcrumb.toggle_siblings(segment);
+                }
+                BreadcrumbMessage::CloseSiblings => self.breadcrumb.close_siblings(),
+                BreadcrumbMessage::StartEdit => {
+                    self.breadcrumb.start_edit();
+                    tasks.push(cosmic::widget::text_input::focus(Id::new(
+                        Breadcrumb::INPUT_ID,
+                    )));
+                    tasks.push(self.complete_breadcrumb());
+                }
+                BreadcrumbMessage::Input(input) => {
+                    self.breadcrumb.input = input;
+                    tasks.push(self.complete_breadcrumb());
+                }
+                BreadcrumbMessage::CompletionsLoaded { input, completions } => {
+                    // Drop results for text the user has typed past
+                    if input == self.breadcrumb.input {
+                        self.breadcrumb.completions = completions;
+                    }
+                }
+                BreadcrumbMessage::Complete(path) => {
+                    self.breadcrumb.input = format!("{}/", path.to_string_lossy());
+                    tasks.push(cosmic::widget::text_input::focus(Id::new(
+                        Breadcrumb::INPUT_ID,
+                    )));
+                    tasks.push(self.complete_breadcrumb());
+                }
+                BreadcrumbMessage::Submit => match self.breadcrumb.submitted_dir() {
+                    Some(dir) => tasks.push(self.scan_and_nav(dir)),
+                    None => {
+                        let path = self.breadcrumb.input.trim().to_string();
+                        tasks.push(self.toast(fl!("breadcrumb-not-folder", path = path)));
+                    }
+                },
+                BreadcrumbMessage::CancelEdit => self.breadcrumb.cancel_edit(),
+            },
+            Message::FolderTree(tree_message) => match tree_message {
+                FolderTreeMessage::ChildrenLoaded { parent, folders } => {
+                    // Keep walking down towards the open directory
+                    let to_load = self.folder_tree.set_children(&parent, folders);
+                    tasks.push(self.load_folder_children(to_load));
+                }
+                FolderTreeMessage::CountsLoaded(counts) => self.folder_tree.set_counts(counts),
+            },
+            Message::Cancelled => {}
+            Message::OpenError(why) => {
+                tracing::error!("{why}");
+                tasks.push(self.toast(why.to_string()));
+            }
+            Message::CloseToast(id) => self.toasts.remove(id),
+            Message::FilesSelected(paths) => {
+                if paths.len() > 1 {
+                    tasks.push(self.open_collection(paths, None));
+                } else if let Some(path) = paths.first() {
+                    tasks.push(self.scan_and_nav(path.clone()));
+                }
+            }
+            Message::OpenPath(path) => tasks.push(self.scan_and_nav(path)),
+            Message::OpenWith(path) => {
+                tasks.push(cosmic::task::future(async move {
+                    match open_with(&path).await {
+                        Ok(()) => Message::Cancelled,
+                        Err(err) => Message::OpenError(Arc::new(format!(
+                            "Failed to open {}: {err}",
+                            path.display()
+                        ))),
+                    }
+                }));
+            }
+            Message::Activate(paths, token) => {
+                if !paths.is_empty() {
+                    tasks.push(self.update(Message::FilesSelected(paths)));
+                }
+
+                if let Some(window_id) = self.core.main_window_id() {
+                    tasks.push(raise_window(window_id, token));
+                }
+            }
+            Message::SystemThemeChanged => {
+                // TODO: Implement theme changing
+            }
+            Message::ConfigChanged => {
+                if let Some(ref handler) = self.config_handler {
+                    let config = match ViewerConfig::get_entry(handler) {
+                        Ok(config) => config,
+                        Err((_, config)) => config,
+                    };
+                    self.saved_config = config.clone();
+                    self.config = config;
+                    self.overrides.apply(&mut self.config);
+                }
+            }
+            Message::WatcherEvent(evt) => {
+                match evt {
+                    watcher::WatcherEvent::Created(_) => {
+                        tasks.push(self.reload_image_list());
+                        // TODO: Do this more elegantly at some point
+                    }
+                    watcher::WatcherEvent::Modified(path) => {
+                        // On some systems, external deletion reports as Modified
+                        if !path.exists() {
+                            self.cache.clear_pending(&path);
+                            // A rename of the open image keeps it open
+                            if self.nav.current() == Some(&path)
+                                && !self.pending_renames.contains_key(&path)
+                            {
+                                self.nav.deselect();
+                            }
+
+                            tasks.push(self.reload_image_list());
+                        }
+                    }
+                    watcher::WatcherEvent::Removed(path) => {
+                        self.cache.clear_pending(&path);
+                        // If the deleted image is the one in the modal, deselect it
+                        // so reload_image_list falls back to last_dir
+                        if self.nav.current() == Some(&path)
+                            && !self.pending_renames.contains_key(&path)
+                        {
+                            self.nav.deselect();
+                        }
+
+                        // Let DirectoryRefresh handle modal transition
+                        tasks.push(self.reload_image_list());
                     }
                     watcher::WatcherEvent::Error(err) => tracing::warn!("watcher error: {err}"),
                 }
@@ -1186,12 +2440,12 @@ impl Application for ImageViewer {
                 }
             }
             Message::SetWallpaper => {
-                // Try current selected image (modal view), then focused gallery thumbnail
-                let path = self.nav.current().cloned().or_else(|| {
-                    self.gallery_view
-                        .focused_index
-                        .and_then(|idx| self.nav.images().get(idx).cloned())
-                });
+                // Only one image can be the wallpaper: the open one, a single
+                // selected one, or else the focused thumbnail
+                let path = match self.target_paths().as_slice() {
+                    [path] => Some(path.clone()),
+                    _ => self.focused_path(),
+                };
 
                 if let Some(path) = path {
                     // On COSMIC, check the wallpaper behavior setting
@@ -1248,113 +2502,328 @@ impl Application for ImageViewer {
                 self.wallpaper_dialog = None;
             }
             Message::WallpaperResult(result) => {
-                if let Err(err) = result {
-                    tracing::error!("Failed to set wallpaper: {}", err);
-                }
+                let message = match result {
+                    Ok(()) => fl!("toast-wallpaper-set"),
+                    Err(err) => {
+                        tracing::error!("Failed to set wallpaper: {}", err);
+                        fl!("toast-wallpaper-failed", error = err)
+                    }
+                };
+                tasks.push(self.toast(message));
             }
             Message::DeleteImage => {
-                // Get current image path (modal view or focused gallery thumbnail)
-                let path = self.nav.current().cloned().or_else(|| {
-                    self.gallery_view
-                        .focused_index
-                        .and_then(|idx| self.nav.images().get(idx).cloned())
-                });
+                // The open image, the selection or the focused gallery thumbnail
+                let paths = self.target_paths();
 
-                if let Some(path) = path {
-                    self.delete_dialog = Some(path);
+                // One delete at a time so progress and undo stay unambiguous
+                if !paths.is_empty() && self.delete_progress.is_none() {
+                    self.delete_dialog = Some(file_ops::DeleteRequest::new(paths, &self.nav));
                 }
             }
             Message::ShowDeleteDialog(path) => {
-                self.delete_dialog = Some(path);
+                // Same as DeleteImage: one delete at a time
+                if self.delete_progress.is_none() {
+                    self.delete_dialog = Some(file_ops::DeleteRequest::new(vec![path], &self.nav));
+                }
             }
-            Message::ConfirmDelete(path, action) => {
+            Message::ConfirmDelete(paths, action) => {
                 self.delete_dialog = None;
-                return cosmic::task::future(async move {
-                    let result = match action {
-                        DeleteAction::Trash => trash::delete(&path)
-                            .map_err(|e| format!("Failed to move to trash: {}", e)),
-                        DeleteAction::Permanent => std::fs::remove_file(&path)
-                            .map_err(|e| format!("Failed to delete file: {}", e)),
-                    };
-                    Message::DeleteResult(result)
+                self.delete_report = None;
+                self.delete_progress = Some((0, paths.len()));
+                self.gallery_view.clear_selection();
+
+                return Task::run(file_ops::delete(paths, action), |event| {
+                    Action::from(match event {
+                        file_ops::DeleteEvent::Progress { done, total } => {
+                            Message::DeleteProgress { done, total }
+                        }
+                        file_ops::DeleteEvent::Done(report) => Message::DeleteFinished(report),
+                    })
                 });
             }
             Message::CloseDeleteDialog => {
                 self.delete_dialog = None;
             }
-            Message::DeleteResult(result) => {
-                if let Err(err) = result {
-                    tracing::error!("Delete failed: {}", err);
+            Message::DeleteProgress { done, total } => {
+                self.delete_progress = Some((done, total));
+            }
+            Message::DeleteFinished(report) => {
+                self.delete_progress = None;
+                for err in &report.errors {
+                    tracing::error!("Delete failed: {err}");
+                }
+
+                let count = report.deleted.len();
+                let mut summary = Vec::new();
+                if count > 0 {
+                    summary.push(match report.action {
+                        DeleteAction::Trash => fl!("delete-done-trash", count = count),
+                        DeleteAction::Permanent => fl!("delete-done-permanent", count = count),
+                    });
+                }
+                if !report.errors.is_empty() {
+                    summary.push(fl!("delete-failed", count = report.errors.len()));
                 }
+                let toast = Toast::new(summary.join(" · "));
+                let toast = if report.can_undo() {
+                    toast.action(fl!("delete-undo"), |id| Message::UndoTrash(Some(id)))
+                } else {
+                    toast
+                };
+                tasks.push(self.toasts.push(toast).map(Action::App));
+
+                self.delete_report = Some(report);
+                self.rename_report = None;
                 // The file watcher will handle updating the gallery
             }
-            Message::Quit => {
-                std::process::exit(0);
+            Message::UndoTrash(toast) => {
+                if let Some(id) = toast {
+                    self.toasts.remove(id);
+                }
+                if let Some(report) = self.delete_report.take_if(|report| report.can_undo()) {
+                    return cosmic::task::future(async move {
+                        Message::TrashRestored(file_ops::restore(report).await)
+                    });
+                }
             }
-        }
+            Message::TrashRestored(result) => {
+                let message = match result {
+                    Ok(count) => {
+                        tracing::info!("Restored {count} files from the trash");
+                        fl!("toast-restored", count = count)
+                    }
+                    Err(err) => {
+                        tracing::error!("Failed to restore from the trash: {err}");
+                        fl!("toast-restore-failed", error = err)
+                    }
+                };
+                tasks.push(self.toast(message));
+            }
+            Message::ShowTransferDialog(kind) => {
+                let title = match kind {
+                    TransferKind::Copy => fl!("menu-copy-to"),
+                    TransferKind::Move => fl!("menu-move-to"),
+                };
 
-        if tasks.is_empty() {
-            Task::none()
-        } else {
-            Task::batch(tasks)
-        }
-    }
+                return future(async move {
+                    let dialog = AsyncFileDialog::new().set_title(title);
 
-    fn context_drawer(&self) -> Option<context_drawer::ContextDrawer<'_, Self::Message>> {
-        let page = self.context_page?;
-        let content = match page {
-            ContextPage::About => self.about_page(),
-            ContextPage::Settings => self.settings_page(),
-            ContextPage::ImageInfo => self.image_info_page(),
-        };
+                    match dialog.pick_folder().await {
+                        Some(handle) => Message::TransferTo(kind, handle.path().to_path_buf()),
+                        None => Message::Cancelled,
+                    }
+                });
+            }
+            Message::TransferTo(kind, dest) => {
+                let paths = self.target_paths();
+
+                // One copy or move at a time, like deletes
+                if !paths.is_empty() && self.transfer_progress.is_none() {
+                    let request = file_ops::TransferRequest::new(kind, paths, dest);
+                    if request.collisions > 0 {
+                        self.transfer_dialog = Some(request);
+                    } else if !request.paths.is_empty() {
+                        return self.start_transfer(request, Collision::Skip);
+                    }
+                }
+            }
+            Message::QuickSort(slot) => {
+                let folder = self.config.quick_sort_folder(slot).map(PathBuf::from);
+                match folder {
+                    Some(folder) => {
+                        tasks.push(self.update(Message::TransferTo(TransferKind::Move, folder)))
+                    }
+                    // Nothing set up for this key yet, so ask where it should go
+                    None => tasks.push(self.update(Message::PickQuickSortFolder(slot))),
+                }
+            }
+            Message::PickQuickSortFolder(slot) => {
+                return future(async move {
+                    let dialog = AsyncFileDialog::new()
+                        .set_title(fl!("settings-quick-sort-slot", slot = slot + 1));
 
-        Some(context_drawer::context_drawer(
-            content,
-            Message::ToggleContextPage(page),
-        ))
-    }
+                    match dialog.pick_folder().await {
+                        Some(handle) => {
+                            let folder = handle.path().to_string_lossy().to_string();
+                            Message::Settings(SettingsMessage::QuickSortFolder(slot, Some(folder)))
+                        }
+                        None => Message::Cancelled,
+                    }
+                });
+            }
+            Message::ConfirmTransfer(collision) => {
+                if let Some(request) = self.transfer_dialog.take() {
+                    return self.start_transfer(request, collision);
+                }
+            }
+            Message::CloseTransferDialog => {
+                self.transfer_dialog = None;
+            }
+            Message::ConfirmTransform => {
+                if let Some((paths, transform)) = self.transform_dialog.take() {
+                    self.transform_progress = Some((0, paths.len()));
+                    self.gallery_view.clear_selection();
+
+                    return Task::run(file_ops::transform(paths, transform), |event| {
+                        Action::from(match event {
+                            file_ops::TransformEvent::Progress { done, total } => {
+                                Message::TransformProgress { done, total }
+                            }
+                            file_ops::TransformEvent::Done(report) => {
+                                Message::TransformFinished(report)
+                            }
+                        })
+                    });
+                }
+            }
+            Message::CloseTransformDialog => {
+                self.transform_dialog = None;
+            }
+            Message::TransformProgress { done, total } => {
+                self.transform_progress = Some((done, total));
+            }
+            Message::TransformFinished(report) => {
+                self.transform_progress = None;
+                for err in &report.errors {
+                    tracing::error!("Rotate or flip failed: {err}");
+                }
 
-    fn subscription(&self) -> cosmic::iced::Subscription<Self::Message> {
-        // Setup the subscription to watch the current directory
-        let watcher_sub =
-            watcher::watch_directory(self.config.last_dir.as_ref().map(|dir| PathBuf::from(dir)))
-                .map(Message::WatcherEvent);
+                for (path, _) in &report.saved {
+                    self.gallery_view.mark_edited(path.clone());
+                    self.cache.remove_full(path);
+                    tasks.push(self.reload_thumbnail(path.clone()));
+                }
 
-        // Slideshow timer
-        let slideshow_sub = if self.is_slideshow_active {
-            cosmic::iced::time::every(Duration::from_secs(self.config.slideshow_interval as u64))
-                .map(|_| Message::SlideshowTick)
-        } else {
-            cosmic::iced::Subscription::none()
-        };
+                let count = report.saved.len();
+                let mut summary = Vec::new();
+                if count > 0 {
+                    summary.push(fl!("transform-done", count = count));
+                }
+                if !report.errors.is_empty() {
+                    summary.push(fl!("transform-failed", count = report.errors.len()));
+                }
+                let toast = Toast::new(summary.join(" · "));
+                let toast = if count > 0 {
+                    toast.action(fl!("transform-undo"), |id| Message::UndoTransform(Some(id)))
+                } else {
+                    toast
+                };
+                tasks.push(self.toasts.push(toast).map(Action::App));
 
-        cosmic::iced::Subscription::batch([
-            cosmic::iced::keyboard::on_key_press(key_press_handler),
-            cosmic::iced::window::events().map(|(_, event)| {
-                if let cosmic::iced::window::Event::Resized(size) = event {
-                    Message::WindowResized {
-                        width: size.width,
-                        height: size.height,
+                if let Some(old) = self.transform_report.replace(report) {
+                    file_ops::discard_backups(&old);
+                }
+            }
+            Message::UndoTransform(toast) => {
+                if let Some(id) = toast {
+                    self.toasts.remove(id);
+                }
+                if let Some(report) = self.transform_report.take() {
+                    return cosmic::task::future(async move {
+                        Message::TransformUndone(file_ops::restore_originals(report).await)
+                    });
+                }
+            }
+            Message::TransformUndone(result) => {
+                let message = match result {
+                    Ok(restored) => {
+                        for path in &restored {
+                            self.cache.remove_full(path);
+                            tasks.push(self.reload_thumbnail(path.clone()));
+                        }
+                        fl!("toast-transform-restored", count = restored.len())
                     }
-                } else {
-                    Message::Cancelled // Use existing no-op message for other window events
+                    Err(err) => {
+                        tracing::error!("Failed to restore rotated images: {err}");
+                        fl!("toast-transform-restore-failed", error = err)
+                    }
+                };
+                tasks.push(self.toast(message));
+            }
+            Message::TransferProgress { done, total } => {
+                if let Some((kind, _, _)) = self.transfer_progress {
+                    self.transfer_progress = Some((kind, done, total));
+                }
+            }
+            Message::TransferFinished(report) => {
+                self.transfer_progress = None;
+                for err in &report.errors {
+                    tracing::error!("Transfer failed: {err}");
                 }
-            }),
-            watcher_sub,
-            slideshow_sub,
-        ])
-    }
 
-    fn on_app_exit(&mut self) -> Option<Self::Message> {
-        if let Some(ref handler) = self.config_handler {
-            let _ = self.config.write_entry(handler);
+                if report.kind == TransferKind::Move && !report.transferred.is_empty() {
+                    let moved: HashSet<PathBuf> = report
+                        .transferred
+                        .iter()
+                        .map(|(source, _)| source.clone())
+                        .collect();
+                    let focused = self.focused_path();
+                    let current = self.nav.current().cloned();
+
+                    self.nav.remove_images(&moved);
+                    self.gallery_view
+                        .recount_unreadable(&self.nav, &self.cache, &self.thumbnails);
+                    for path in &moved {
+                        self.cache.remove_full(path);
+                        self.cache.remove_thumbnail(path);
+                    }
+
+                    // Keep gallery focus on the same image, or near where it was
+                    let last = self.nav.total().checked_sub(1);
+                    self.gallery_view.focused_index = match focused {
+                        Some(path) if !moved.contains(&path) => self.nav.position(&path),
+                        _ => self
+                            .gallery_view
+                            .focused_index
+                            .zip(last)
+                            .map(|(idx, last)| idx.min(last)),
+                    };
+
+                    // The open image was moved away, so show the one in its place
+                    if self.nav.current() != current.as_ref() {
+                        self.edit_state.reset();
+                        self.reset_zoom();
+                        self.update_fit_zoom();
+                        tasks.push(self.load_current_image());
+                        tasks.push(self.preload_images());
+                        tasks.push(self.update_title().map(Action::from));
+                    }
+                    tasks.push(self.load_thumbnails());
+                }
+
+                let count = report.transferred.len();
+                let folder = folder_tree::display_name(&report.dest);
+                let mut summary = Vec::new();
+                if count > 0 {
+                    summary.push(match report.kind {
+                        TransferKind::Copy => {
+                            fl!("transfer-done-copy", count = count, folder = folder)
+                        }
+                        TransferKind::Move => {
+                            fl!("transfer-done-move", count = count, folder = folder)
+                        }
+                    });
+                }
+                if report.skipped > 0 {
+                    summary.push(fl!("transfer-skipped", count = report.skipped));
+                }
+                if !report.errors.is_empty() {
+                    summary.push(fl!("transfer-failed", count = report.errors.len()));
+                }
+                tasks.push(self.toast(summary.join(" · ")));
+            }
+            Message::Quit => {
+                std::process::exit(0);
+            }
         }
 
-        None
+        if tasks.is_empty() {
+            Task::none()
+        } else {
+            Task::batch(tasks)
+        }
     }
-}
 
-impl ImageViewer {
     fn about_page(&self) -> Element<'_, Message> {
         column()
             .push(text::title3(fl!("app-title")))
@@ -1418,25 +2887,83 @@ impl ImageViewer {
         .into()
     }
 
-    fn delete_dialog_view(&self, path: &PathBuf) -> Element<'_, Message> {
+    /// Progress of the running delete
+    fn delete_status(&self) -> Option<Element<'_, Message>> {
+        use cosmic::iced::{Alignment, Length};
+        use cosmic::widget::{container, row};
+
+        let spacing = cosmic::theme::active().cosmic().spacing;
+        let (done, total) = self.delete_progress?;
+
+        let content = row()
+            .push(text::body(fl!(
+                "delete-progress",
+                done = done,
+                total = total
+            )))
+            .push(
+                cosmic::iced_widget::progress_bar(0.0..=total as f32, done as f32)
+                    .width(Length::Fixed(200.0))
+                    .height(Length::Fixed(6.0)),
+            );
+
+        Some(
+            container(
+                content
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .width(Length::Fill),
+            )
+            .padding([spacing.space_xxs, spacing.space_s])
+            .into(),
+        )
+    }
+
+    fn delete_dialog_view(&self, request: &file_ops::DeleteRequest) -> Element<'_, Message> {
         use cosmic::iced::Length;
         use cosmic::widget::{Space, container};
 
+        /// File names listed before the rest are summed up as "and N more"
+        const LISTED: usize = 5;
+
         let spacing = cosmic::theme::active().cosmic().spacing;
+        let paths = &request.paths;
 
-        // Get filename for display
-        let filename = path
-            .file_name()
-            .map(|n| n.to_string_lossy().to_string())
-            .unwrap_or_else(|| path.to_string_lossy().to_string());
+        let title = if paths.len() == 1 {
+            fl!("delete-dialog-title")
+        } else {
+            fl!("delete-dialog-title-many", count = paths.len())
+        };
+
+        // Name the first few files
+        let mut names = column().spacing(spacing.space_xxxs);
+        for path in paths.iter().take(LISTED) {
+            let filename = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_else(|| path.to_string_lossy().to_string());
+            names = names.push(text::body(filename));
+        }
+        if paths.len() > LISTED {
+            names = names.push(text::body(fl!(
+                "delete-dialog-more",
+                count = paths.len() - LISTED
+            )));
+        }
+
+        let summary = fl!(
+            "delete-dialog-summary",
+            count = paths.len(),
+            size = format_size(request.total_size)
+        );
 
         // Trash button
         let trash_btn = button::suggested(fl!("delete-trash"))
-            .on_press(Message::ConfirmDelete(path.clone(), DeleteAction::Trash));
+            .on_press(Message::ConfirmDelete(paths.to_vec(), DeleteAction::Trash));
 
         // Delete permanently button
         let delete_btn = button::destructive(fl!("delete-permanent")).on_press(
-            Message::ConfirmDelete(path.clone(), DeleteAction::Permanent),
+            Message::ConfirmDelete(paths.to_vec(), DeleteAction::Permanent),
         );
 
         // Cancel button
@@ -1448,9 +2975,10 @@ impl ImageViewer {
             .spacing(spacing.space_s);
 
         let content = column()
-            .push(text::title4(fl!("delete-dialog-title")))
+            .push(text::title4(title))
             .push(Space::with_height(Length::Fixed(spacing.space_xs as f32)))
-            .push(text::body(filename))
+            .push(names.align_x(cosmic::iced::Alignment::Center))
+            .push(text::caption(summary))
             .push(Space::with_height(Length::Fixed(spacing.space_m as f32)))
             .push(button_row)
             .push(Space::with_height(Length::Fixed(spacing.space_s as f32)))
@@ -1475,6 +3003,181 @@ impl ImageViewer {
         .into()
     }
 
+    /// Progress of the running copy or move
+    fn transfer_status(&self) -> Option<Element<'_, Message>> {
+        use cosmic::iced::{Alignment, Length};
+        use cosmic::widget::{container, row};
+
+        let spacing = cosmic::theme::active().cosmic().spacing;
+        let (kind, done, total) = self.transfer_progress?;
+
+        let progress = match kind {
+            TransferKind::Copy => fl!("transfer-progress-copy", done = done, total = total),
+            TransferKind::Move => fl!("transfer-progress-move", done = done, total = total),
+        };
+        let content = row().push(text::body(progress)).push(
+            cosmic::iced_widget::progress_bar(0.0..=total as f32, done as f32)
+                .width(Length::Fixed(200.0))
+                .height(Length::Fixed(6.0)),
+        );
+
+        Some(
+            container(
+                content
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .width(Length::Fill),
+            )
+            .padding([spacing.space_xxs, spacing.space_s])
+            .into(),
+        )
+    }
+
+    /// Asks what to do with files whose names are taken in the destination
+    fn transfer_dialog_view(&self, request: &file_ops::TransferRequest) -> Element<'_, Message> {
+        use cosmic::iced::Length;
+        use cosmic::widget::{Space, container};
+
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let folder = request.dest.to_string_lossy().to_string();
+        let body = fl!(
+            "transfer-collision-body",
+            count = request.collisions,
+            folder = folder
+        );
+
+        let button_row = cosmic::widget::row()
+            .push(
+                button::text(fl!("transfer-skip"))
+                    .on_press(Message::ConfirmTransfer(Collision::Skip)),
+            )
+            .push(
+                button::suggested(fl!("transfer-rename"))
+                    .on_press(Message::ConfirmTransfer(Collision::Rename)),
+            )
+            .push(
+                button::destructive(fl!("transfer-overwrite"))
+                    .on_press(Message::ConfirmTransfer(Collision::Overwrite)),
+            )
+            .spacing(spacing.space_s);
+
+        let cancel_btn =
+            button::text(fl!("transfer-cancel")).on_press(Message::CloseTransferDialog);
+
+        let content = column()
+            .push(text::title4(fl!("transfer-collision-title")))
+            .push(Space::with_height(Length::Fixed(spacing.space_xs as f32)))
+            .push(text::body(body))
+            .push(Space::with_height(Length::Fixed(spacing.space_m as f32)))
+            .push(button_row)
+            .push(Space::with_height(Length::Fixed(spacing.space_s as f32)))
+            .push(cancel_btn)
+            .spacing(spacing.space_xxs)
+            .align_x(cosmic::iced::Alignment::Center);
+
+        let dialog_container = container(content)
+            .padding(spacing.space_m)
+            .max_width(480)
+            .class(cosmic::theme::Container::Dialog);
+
+        // Center the dialog on screen
+        container(
+            container(dialog_container)
+                .width(Length::Shrink)
+                .height(Length::Shrink),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(cosmic::iced::alignment::Horizontal::Center)
+        .align_y(cosmic::iced::alignment::Vertical::Center)
+        .into()
+    }
+
+    /// Progress of the running rotate or flip
+    fn transform_status(&self) -> Option<Element<'_, Message>> {
+        use cosmic::iced::{Alignment, Length};
+        use cosmic::widget::{container, row};
+
+        let spacing = cosmic::theme::active().cosmic().spacing;
+        let (done, total) = self.transform_progress?;
+
+        let content = row()
+            .push(text::body(fl!(
+                "transform-progress",
+                done = done,
+                total = total
+            )))
+            .push(
+                cosmic::iced_widget::progress_bar(0.0..=total as f32, done as f32)
+                    .width(Length::Fixed(200.0))
+                    .height(Length::Fixed(6.0)),
+            );
+
+        Some(
+            container(
+                content
+                    .spacing(spacing.space_s)
+                    .align_y(Alignment::Center)
+                    .width(Length::Fill),
+            )
+            .padding([spacing.space_xxs, spacing.space_s])
+            .into(),
+        )
+    }
+
+    /// Asks before rotating or flipping the selected images on disk
+    fn transform_dialog_view(
+        &self,
+        paths: &[PathBuf],
+        transform: Transform,
+    ) -> Element<'_, Message> {
+        use cosmic::iced::Length;
+        use cosmic::widget::{Space, container};
+
+        let spacing = cosmic::theme::active().cosmic().spacing;
+
+        let title = match transform {
+            Transform::Rotate90 => fl!("transform-dialog-rotate-90", count = paths.len()),
+            Transform::Rotate180 => fl!("transform-dialog-rotate-180", count = paths.len()),
+            Transform::FlipHorizontal => {
+                fl!("transform-dialog-flip-horizontal", count = paths.len())
+            }
+            Transform::FlipVertical => fl!("transform-dialog-flip-vertical", count = paths.len()),
+        };
+
+        let button_row = cosmic::widget::row()
+            .push(button::text(fl!("transform-cancel")).on_press(Message::CloseTransformDialog))
+            .push(button::suggested(fl!("transform-confirm")).on_press(Message::ConfirmTransform))
+            .spacing(spacing.space_s);
+
+        let content = column()
+            .push(text::title4(title))
+            .push(Space::with_height(Length::Fixed(spacing.space_xs as f32)))
+            .push(text::body(fl!("transform-dialog-body")))
+            .push(Space::with_height(Length::Fixed(spacing.space_m as f32)))
+            .push(button_row)
+            .spacing(spacing.space_xxs)
+            .align_x(cosmic::iced::Alignment::Center);
+
+        let dialog_container = container(content)
+            .padding(spacing.space_m)
+            .max_width(480)
+            .class(cosmic::theme::Container::Dialog);
+
+        // Center the dialog on screen
+        container(
+            container(dialog_container)
+                .width(Length::Shrink)
+                .height(Length::Shrink),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .align_x(cosmic::iced::alignment::Horizontal::Center)
+        .align_y(cosmic::iced::alignment::Vertical::Center)
+        .into()
+    }
+
     fn crop_dialog_view(&self, cached: &crate::image::CachedImage) -> Element<'_, Message> {
         use crate::widgets::crop_widget;
         use cosmic::iced::Length;
@@ -1538,6 +3241,21 @@ impl ImageViewer {
 
     fn settings_page(&self) -> Element<'_, Message> {
         let spacing = cosmic::theme::active().cosmic().spacing;
+        let badge_item = |badge: Badge| {
+            let label = match badge {
+                Badge::Format => fl!("settings-badge-format"),
+                Badge::Animation => fl!("settings-badge-animation"),
+                Badge::Rating => fl!("settings-badge-rating"),
+                Badge::Edited => fl!("settings-badge-edited"),
+                Badge::Failed => fl!("settings-badge-failed"),
+            };
+            settings::item(
+                label,
+                toggler(self.config.thumbnail_badges.shows(badge)).on_toggle(move |show| {
+                    Message::Settings(SettingsMessage::ThumbnailBadge(badge, show))
+                }),
+            )
+        };
 
         let mut sections = vec![
             // Appearance section
@@ -1584,39 +3302,139 @@ impl ImageViewer {
                 .title(fl!("settings-gallery"))
                 .add(settings::item(
                     fl!("settings-thumbnail-size"),
+                    cosmic::widget::row()
+                        .push(
+                            slider(
+                                ThumbnailSize::MIN..=ThumbnailSize::MAX,
+                                self.config.thumbnail_size.pixels(),
+                                |pixels| {
+                                    Message::Settings(SettingsMessage::ThumbnailSize(
+                                        ThumbnailSize::new(pixels),
+                                    ))
+                                },
+                            )
+                            .step(8u32),
+                        )
+                        .push(text::body(fl!(
+                            "settings-thumbnail-pixels",
+                            pixels = self.config.thumbnail_size.pixels()
+                        )))
+                        .spacing(spacing.space_xs)
+                        .align_y(cosmic::iced::Alignment::Center),
+                ))
+                .add(settings::item(
+                    fl!("settings-gallery-layout"),
+                    column()
+                        .push(radio(
+                            text::body(fl!("settings-gallery-layout-grid")),
+                            GalleryLayout::Grid,
+                            Some(self.config.gallery_layout),
+                            |layout| Message::Settings(SettingsMessage::GalleryLayout(layout)),
+                        ))
+                        .push(radio(
+                            text::body(fl!("settings-gallery-layout-justified")),
+                            GalleryLayout::Justified,
+                            Some(self.config.gallery_layout),
+                            |layout| Message::Settings(SettingsMessage::GalleryLayout(layout)),
+                        ))
+                        .push(radio(
+                            text::body(fl!("settings-gallery-layout-list")),
+                            GalleryLayout::List,
+                            Some(self.config.gallery_layout),
+                            |layout| Message::Settings(SettingsMessage::GalleryLayout(layout)),
+                        ))
+                        .spacing(spacing.space_xxs),
+                ))
+                .add(settings::item(
+                    fl!("settings-date-grouping"),
                     column()
                         .push(radio(
-                            text::body(fl!("settings-thumbnail-small")),
-                            ThumbnailSize::Small,
-                            Some(self.config.thumbnail_size),
-                            |size| Message::Settings(SettingsMessage::ThumbnailSize(size)),
+                            text::body(fl!("settings-date-grouping-off")),
+                            DateGrouping::Off,
+                            Some(self.config.date_grouping),
+                            |grouping| Message::Settings(SettingsMessage::DateGrouping(grouping)),
                         ))
                         .push(radio(
-                            text::body(fl!("settings-thumbnail-medium")),
-                            ThumbnailSize::Medium,
-                            Some(self.config.thumbnail_size),
-                            |size| Message::Settings(SettingsMessage::ThumbnailSize(size)),
+                            text::body(fl!("settings-date-grouping-day")),
+                            DateGrouping::Day,
+                            Some(self.config.date_grouping),
+                            |grouping| Message::Settings(SettingsMessage::DateGrouping(grouping)),
                         ))
                         .push(radio(
-                            text::body(fl!("settings-thumbnail-large")),
-                            ThumbnailSize::Large,
-                            Some(self.config.thumbnail_size),
-                            |size| Message::Settings(SettingsMessage::ThumbnailSize(size)),
+                            text::body(fl!("settings-date-grouping-month")),
+                            DateGrouping::Month,
+                            Some(self.config.date_grouping),
+                            |grouping| Message::Settings(SettingsMessage::DateGrouping(grouping)),
                         ))
                         .push(radio(
-                            text::body(fl!("settings-thumbnail-xlarge")),
-                            ThumbnailSize::XLarge,
-                            Some(self.config.thumbnail_size),
-                            |size| Message::Settings(SettingsMessage::ThumbnailSize(size)),
+                            text::body(fl!("settings-date-grouping-year")),
+                            DateGrouping::Year,
+                            Some(self.config.date_grouping),
+                            |grouping| Message::Settings(SettingsMessage::DateGrouping(grouping)),
                         ))
                         .spacing(spacing.space_xxs),
                 ))
+                .add(badge_item(Badge::Format))
+                .add(badge_item(Badge::Animation))
+                .add(badge_item(Badge::Rating))
+                .add(badge_item(Badge::Edited))
+                .add(badge_item(Badge::Failed))
                 .add(settings::item(
                     fl!("settings-show-hidden"),
                     toggler(self.config.show_hidden_files).on_toggle(|show| {
                         Message::Settings(SettingsMessage::ShowHiddenFiles(show))
                     }),
                 ))
+                .add(settings::item(
+                    fl!("settings-sort-mode"),
+                    dropdown(
+                        SortMode::ALL
+                            .iter()
+                            .map(|m| m.to_string())
+                            .collect::<Vec<_>>(),
+                        SortMode::ALL
+                            .iter()
+                            .position(|m| *m == self.config.sort_mode),
+                        |idx| Message::Settings(SettingsMessage::SortMode(SortMode::ALL[idx])),
+                    ),
+                ))
+                .add(settings::item(
+                    fl!("settings-sort-order"),
+                    dropdown(
+                        SortOrder::ALL
+                            .iter()
+                            .map(|o| o.to_string())
+                            .collect::<Vec<_>>(),
+                        SortOrder::ALL
+                            .iter()
+                            .position(|o| *o == self.config.sort_order),
+                        |idx| Message::Settings(SettingsMessage::SortOrder(SortOrder::ALL[idx])),
+                    ),
+                ))
+                .add(settings::item(
+                    fl!("settings-include-subfolders"),
+                    toggler(self.config.include_subfolders).on_toggle(|include| {
+                        Message::Settings(SettingsMessage::IncludeSubfolders(include))
+                    }),
+                ))
+                .add(settings::item(
+                    fl!("settings-subfolder-depth"),
+                    spin_button(
+                        format!("{}", self.config.subfolder_depth),
+                        fl!("settings-subfolder-depth"),
+                        self.config.subfolder_depth,
+                        1,
+                        1,
+                        32,
+                        |depth| Message::Settings(SettingsMessage::SubfolderDepth(depth)),
+                    ),
+                ))
+                .add(settings::item(
+                    fl!("settings-group-by-folder"),
+                    toggler(self.config.group_by_folder).on_toggle(|group| {
+                        Message::Settings(SettingsMessage::GroupByFolder(group))
+                    }),
+                ))
                 .into(),
             // Slideshow settings section
             settings::section()
@@ -1662,6 +3480,34 @@ impl ImageViewer {
                 .into(),
         ];
 
+        // Quick-sort destinations, one per Alt+number key
+        let mut quick_sort = settings::section().title(fl!("settings-quick-sort"));
+        for slot in 0..QUICK_SORT_SLOTS {
+            let folder = self.config.quick_sort_folder(slot);
+            let controls = cosmic::widget::row()
+                .push(text::body(folder.map_or_else(
+                    || fl!("settings-quick-sort-unset"),
+                    str::to_string,
+                )))
+                .push(
+                    button::text(fl!("settings-quick-sort-choose"))
+                        .on_press(Message::PickQuickSortFolder(slot)),
+                )
+                .push_maybe(folder.map(|_| {
+                    button::icon(cosmic::widget::icon::from_name("edit-clear-symbolic")).on_press(
+                        Message::Settings(SettingsMessage::QuickSortFolder(slot, None)),
+                    )
+                }))
+                .spacing(spacing.space_xs)
+                .align_y(cosmic::iced::Alignment::Center);
+
+            quick_sort = quick_sort.add(settings::item(
+                fl!("settings-quick-sort-slot", slot = slot + 1),
+                controls,
+            ));
+        }
+        sections.push(quick_sort.into());
+
         // Add COSMIC-specific wallpaper settings if on COSMIC desktop
         if is_cosmic_desktop() {
             sections.push(
@@ -1709,6 +3555,23 @@ impl ImageViewer {
                     cached.width, cached.height
                 )));
             }
+
+            // Size and mtime were gathered by the directory scan
+            if let Some(entry) = self.nav.current_entry() {
+                content = content.push(text::body(format!("Size: {}", format_size(entry.size))));
+
+                if let Some(modified) = entry.modified {
+                    content = content.push(text::body(format!(
+                        "Modified: {}",
+                        format_system_time(modified)
+                    )));
+                }
+
+                if let Some(taken) = entry.taken {
+                    content =
+                        content.push(text::body(format!("Taken: {}", format_system_time(taken))));
+                }
+            }
         } else {
             content = content.push(text::body("No image loaded"));
         }
@@ -1717,6 +3580,18 @@ impl ImageViewer {
     }
 }
 
+/// Ask the desktop which application to open `path` with
+async fn open_with(path: &std::path::Path) -> Result<(), String> {
+    let file = std::fs::File::open(path).map_err(|e| e.to_string())?;
+
+    OpenFileRequest::default()
+        .ask(true)
+        .send_file(&file)
+        .await
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
 async fn set_wallpaper(path: &std::path::Path) -> Result<(), String> {
     // Try XDG portal first (works on GNOME, KDE, and eventually COSMIC)
     let uri = Url::from_file_path(path).map_err(|()| "Invalid file path".to_string())?;
@@ -1781,6 +3656,20 @@ async fn set_wallpaper_cosmic(path: &std::path::Path) -> Result<(), String> {
     Ok(())
 }
 
+/// Bring the window to the front. On Wayland only the activation token of
+/// the launch asking for it lets the compositor do that.
+fn raise_window(window_id: window::Id, token: Option<String>) -> Task<Action<Message>> {
+    #[cfg(feature = "wayland")]
+    if let Some(token) = token {
+        use cosmic::iced::platform_specific::shell::commands::activation;
+        return activation::activate::<Message>(window_id, token).map(Action::from);
+    }
+    #[cfg(not(feature = "wayland"))]
+    let _ = token;
+
+    window::gain_focus::<Message>(window_id).map(Action::from)
+}
+
 fn is_cosmic_desktop() -> bool {
     std::env::var("XDG_CURRENT_DESKTOP")
         .map(|d| d.to_uppercase().contains("COSMIC"))
@@ -0,0 +1,503 @@
+//! Headless batch processing
+//!
+//! `cupola convert|resize|rotate|strip` runs the viewer's edit and resize code
+//! over a list of files without opening a window, so scripts get the same
+//! results as the GUI.
+
+use crate::{
+    edit::{self, EditError, Transform},
+    image::loader::{self, LoadError},
+    nav,
+    widgets::CropRegion,
+};
+use image::{
+    DynamicImage, ImageDecoder, ImageFormat, ImageReader, RgbaImage, metadata::Orientation,
+};
+use regex::RegexBuilder;
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use thiserror::Error;
+use tokio::{sync::Semaphore, task::JoinSet};
+
+pub const SUBCOMMANDS: &[&str] = &["convert", "resize", "rotate", "strip"];
+
+pub const USAGE: &str = "\
+Usage: cupola <convert|resize|rotate|strip> [OPTIONS] <FILE|FOLDER|GLOB>...
+
+Processes images without opening a window. Every command writes a new file
+without metadata; the options below can be combined with any of them.
+
+Commands:
+  convert                 Change the format, needs --format
+  resize                  Shrink to fit a size, needs --size
+  rotate                  Rotate or flip, needs --angle or --flip
+  strip                   Remove metadata only; JPEG and PNG aren't re-encoded
+
+Options:
+  -o, --output DIR        Write results to DIR instead of next to the originals
+  -f, --format FMT        Output format, e.g. png, jpg, webp, avif
+  -q, --quality N         Encoder quality from 1 to 100 (JPEG and AVIF)
+  -s, --size PX           Longest side in pixels; smaller images are left as they are
+  --angle 90|180|270      Rotate clockwise
+  --flip h|v              Flip horizontally or vertically
+  --crop X,Y,W,H          Crop to a region, after rotating
+  -j, --jobs N            Images processed at once (default: one per CPU)
+  --in-place              Allow replacing the original files
+  -h, --help              Print this help
+";
+
+#[derive(Debug, Clone)]
+pub struct Job {
+    pub inputs: Vec<String>,
+    pub output: Option<PathBuf>,
+    pub format: Option<ImageFormat>,
+    pub quality: Option<u8>,
+    pub max_size: Option<u32>,
+    pub transforms: Vec<Transform>,
+    pub crop: Option<CropRegion>,
+    pub jobs: usize,
+    pub in_place: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum BatchError {
+    #[error(transparent)]
+    Edit(#[from] EditError),
+    #[error(transparent)]
+    Load(#[from] LoadError),
+    #[error("Failed to decode image: {0}")]
+    Decode(#[from] image::ImageError),
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("would replace the original, pass --in-place to allow it")]
+    WouldOverwrite,
+    #[error("Task cancelled")]
+    Cancelled,
+}
+
+/// Parse the options following a subcommand; `None` asks for the usage text
+pub fn parse(command: &str, args: impl IntoIterator<Item = String>) -> Result<Option<Job>, String> {
+    let mut job = Job {
+        inputs: Vec::new(),
+        output: None,
+        format: None,
+        quality: None,
+        max_size: None,
+        transforms: Vec::new(),
+        crop: None,
+        jobs: std::thread::available_parallelism().map_or(1, |jobs| jobs.get()),
+        in_place: false,
+    };
+    let mut args = args.into_iter();
+    let mut only_inputs = false;
+
+    while let Some(arg) = args.next() {
+        if only_inputs || !arg.starts_with('-') {
+            job.inputs.push(arg);
+            continue;
+        }
+
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = || {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{name} needs a value"))
+        };
+
+        match name.as_str() {
+            "--" => only_inputs = true,
+            "-h" | "--help" => return Ok(None),
+            "-o" | "--output" => job.output = Some(PathBuf::from(value()?)),
+            "-f" | "--format" => {
+                let format = value()?;
+                job.format = Some(
+                    ImageFormat::from_extension(format.to_lowercase())
+                        .filter(|format| format.writing_enabled())
+                        .ok_or_else(|| format!("can't write '{format}' images"))?,
+                );
+            }
+            "-q" | "--quality" => {
+                let quality = value()?;
+                job.quality = Some(
+                    quality
+                        .parse::<u8>()
+                        .ok()
+                        .filter(|quality| (1..=100).contains(quality))
+                        .ok_or_else(|| format!("quality must be 1 to 100, got '{quality}'"))?,
+                );
+            }
+            "-s" | "--size" => {
+                let size = value()?;
+                job.max_size = Some(
+                    size.parse::<u32>()
+                        .ok()
+                        .filter(|size| *size > 0)
+                        .ok_or_else(|| format!("invalid size '{size}'"))?,
+                );
+            }
+            "--angle" => match value()?.trim_end_matches('°') {
+                "90" => job.transforms.push(Transform::Rotate90),
+                "180" => job.transforms.push(Transform::Rotate180),
+                "270" => job
+                    .transforms
+                    .extend([Transform::Rotate180, Transform::Rotate90]),
+                angle => return Err(format!("angle must be 90, 180 or 270, got '{angle}'")),
+            },
+            "--flip" => match value()?.to_lowercase().as_str() {
+                "h" | "horizontal" => job.transforms.push(Transform::FlipHorizontal),
+                "v" | "vertical" => job.transforms.push(Transform::FlipVertical),
+                flip => return Err(format!("flip must be h or v, got '{flip}'")),
+            },
+            "--crop" => {
+                let crop = value()?;
+                job.crop = Some(parse_crop(&crop).ok_or_else(|| format!("invalid crop '{crop}'"))?);
+            }
+            "-j" | "--jobs" => {
+                let jobs = value()?;
+                job.jobs = jobs
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|jobs| *jobs > 0)
+                    .ok_or_else(|| format!("invalid job count '{jobs}'"))?;
+            }
+            "--in-place" => job.in_place = true,
+            _ => return Err(format!("unknown option '{name}'")),
+        }
+    }
+
+    match command {
+        "convert" if job.format.is_none() => return Err("convert needs --format".into()),
+        "resize" if job.max_size.is_none() => return Err("resize needs --size".into()),
+        "rotate" if job.transforms.is_empty() => {
+            return Err("rotate needs --angle or --flip".into());
+        }
+        _ => {}
+    }
+
+    if job.inputs.is_empty() {
+        return Err("no images given".into());
+    }
+
+    Ok(Some(job))
+}
+
+/// Process every input, returning the process exit code
+pub fn run(job: Job) -> i32 {
+    let (files, mut failed) = collect_files(&job.inputs);
+    if files.is_empty() {
+        eprintln!("cupola: no images to process");
+        return 1;
+    }
+
+    if let Some(output) = &job.output
+        && let Err(why) = fs::create_dir_all(output)
+    {
+        eprintln!("cupola: {}: {why}", output.display());
+        return 1;
+    }
+
+    // Two inputs writing the same output would replace each other's result
+    let mut written: HashMap<PathBuf, PathBuf> = HashMap::new();
+    let mut planned = Vec::with_capacity(files.len());
+    for path in files {
+        match output_path(&job, &path) {
+            Ok(output) => {
+                if let Some(first) = written.get(&output) {
+                    eprintln!(
+                        "cupola: {}: {} is already written from {}, skipped",
+                        path.display(),
+                        output.display(),
+                        first.display()
+                    );
+                    failed += 1;
+                } else {
+                    written.insert(output.clone(), path.clone());
+                    planned.push((path, output));
+                }
+            }
+            Err(why) => {
+                eprintln!("cupola: {}: {why}", path.display());
+                failed += 1;
+            }
+        }
+    }
+
+    let runtime = match tokio::runtime::Builder::new_multi_thread()
+        .worker_threads(job.jobs)
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(why) => {
+            eprintln!("cupola: {why}");
+            return 1;
+        }
+    };
+
+    let job = Arc::new(job);
+    failed += runtime.block_on(async move {
+        let permits = Arc::new(Semaphore::new(job.jobs));
+        let mut tasks = JoinSet::new();
+
+        for (path, output) in planned {
+            let job = job.clone();
+            let permits = permits.clone();
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                let result = process(job, &path, output).await;
+                (path, result)
+            });
+        }
+
+        let mut failed = 0;
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((path, Ok(output))) => println!("{} -> {}", path.display(), output.display()),
+                Ok((path, Err(why))) => {
+                    eprintln!("cupola: {}: {why}", path.display());
+                    failed += 1;
+                }
+                Err(why) => {
+                    eprintln!("cupola: {why}");
+                    failed += 1;
+                }
+            }
+        }
+        failed
+    });
+
+    if failed > 0 { 1 } else { 0 }
+}
+
+async fn process(job: Arc<Job>, path: &Path, output: PathBuf) -> Result<PathBuf, BatchError> {
+    let format = job
+        .format
+        .or_else(|| ImageFormat::from_path(path).ok())
+        .unwrap_or(ImageFormat::Png);
+    // `output` is canonical, so `./x.png` and `x.png` compare equal
+    if !job.in_place && fs::canonicalize(path)? == output {
+        return Err(BatchError::WouldOverwrite);
+    }
+
+    // Written beside the output and renamed over it, so a failed encode never
+    // leaves a truncated file behind, least of all in place of the original
+    let temp = edit::temp_sibling(&output);
+    if let Err(err) = write_output(job, path, &temp, format).await {
+        let _ = fs::remove_file(&temp);
+        return Err(err);
+    }
+    edit::replace_with(&temp, &output)?;
+
+    Ok(output)
+}
+
+async fn write_output(
+    job: Arc<Job>,
+    path: &Path,
+    output: &Path,
+    format: ImageFormat,
+) -> Result<(), BatchError> {
+    let stripped = {
+        let job = job.clone();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || strip_losslessly(&job, &path, format))
+            .await
+            .map_err(|_| BatchError::Cancelled)??
+    };
+    if let Some(stripped) = stripped {
+        fs::write(output, stripped)?;
+        return Ok(());
+    }
+
+    let img = {
+        let job = job.clone();
+        let path = path.to_path_buf();
+        tokio::task::spawn_blocking(move || edit_image(&job, &path, format))
+            .await
+            .map_err(|_| BatchError::Cancelled)??
+    };
+
+    match job.quality {
+        Some(quality) => edit::save_image_with_quality(img, output, quality).await?,
+        None => edit::save_image(img, output).await?,
+    }
+
+    Ok(())
+}
+
+/// The file with its metadata dropped but the pixels left encoded as they
+/// are, when the job changes nothing else and the image is already upright
+fn strip_losslessly(
+    job: &Job,
+    path: &Path,
+    format: ImageFormat,
+) -> Result<Option<Vec<u8>>, BatchError> {
+    let unchanged = job.transforms.is_empty()
+        && job.crop.is_none()
+        && job.max_size.is_none()
+        && job.quality.is_none()
+        && ImageFormat::from_path(path).ok() == Some(format);
+    if !unchanged {
+        return Ok(None);
+    }
+
+    // The EXIF orientation goes with the metadata, so it has to be baked in
+    let mut decoder = ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()?;
+    if decoder.orientation()? != Orientation::NoTransforms {
+        return Ok(None);
+    }
+
+    Ok(edit::strip_metadata(&fs::read(path)?, format))
+}
+
+/// Decode, apply the job's edits and get the pixels ready for `format`
+fn edit_image(job: &Job, path: &Path, format: ImageFormat) -> Result<DynamicImage, BatchError> {
+    // Metadata isn't carried over, so bake the EXIF orientation into the pixels
+    let mut decoder = ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut img = DynamicImage::from_decoder(decoder)?;
+    img.apply_orientation(orientation);
+
+    img = edit::apply_transforms(img, &job.transforms);
+
+    if let Some(region) = job.crop {
+        img = edit::crop_image(&img, region)?;
+    }
+
+    if let Some(max_size) = job.max_size
+        && (img.width() > max_size || img.height() > max_size)
+    {
+        let rgba = img.to_rgba8();
+        let (width, height) = rgba.dimensions();
+        let (width, height, pixels) =
+            loader::fast_resize_rgba(rgba.as_raw(), width, height, max_size)?;
+        img = RgbaImage::from_raw(width, height, pixels)
+            .map(DynamicImage::ImageRgba8)
+            .ok_or_else(|| LoadError::UnsupportedFormat("resized buffer size".into()))?;
+    }
+
+    // JPEG has no alpha channel
+    if format == ImageFormat::Jpeg && img.color().has_alpha() {
+        img = DynamicImage::ImageRgb8(img.to_rgb8());
+    }
+
+    Ok(img)
+}
+
+/// Output keeps the file name, with the extension changed when converting.
+/// Its folder is canonicalized so outputs compare equal to the inputs and to
+/// each other however the paths were written.
+fn output_path(job: &Job, path: &Path) -> io::Result<PathBuf> {
+    let dir = match &job.output {
+        Some(output) => output.as_path(),
+        None => path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or(Path::new(".")),
+    };
+
+    let name = match job.format {
+        Some(format) => Path::new(path.file_stem().unwrap_or_default())
+            .with_extension(format.extensions_str().first().copied().unwrap_or_default()),
+        None => PathBuf::from(path.file_name().unwrap_or_default()),
+    };
+    Ok(fs::canonicalize(dir)?.join(name))
+}
+
+/// Expand folders and glob patterns into image files, in the order given.
+/// Returns the files and how many inputs matched nothing.
+fn collect_files(inputs: &[String]) -> (Vec<PathBuf>, usize) {
+    let mut seen = HashSet::new();
+    let mut files = Vec::new();
+    let mut failed = 0;
+
+    for input in inputs {
+        let path = PathBuf::from(input);
+
+        let found = if path.is_dir() {
+            images_in(&path, |_| true)
+        } else if path.exists() {
+            vec![path]
+        } else if input.contains(['*', '?', '[']) {
+            // A quoted glob the shell didn't expand; only the file name may have wildcards
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+                _ => PathBuf::from("."),
+            };
+            let pattern = path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+
+            match RegexBuilder::new(&nav::glob_to_regex(&pattern))
+                .case_insensitive(true)
+                .build()
+            {
+                Ok(re) => images_in(&dir, |name| re.is_match(name)),
+                Err(_) => Vec::new(),
+            }
+        } else {
+            Vec::new()
+        };
+
+        if found.is_empty() {
+            eprintln!("cupola: {input}: no images found");
+            failed += 1;
+        }
+
+        files.extend(found.into_iter().filter(|file| seen.insert(file.clone())));
+    }
+
+    (files, failed)
+}
+
+fn images_in(dir: &Path, matches: impl Fn(&str) -> bool) -> Vec<PathBuf> {
+    let mut images: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && nav::is_supported_image(path))
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| matches(&name.to_string_lossy()))
+        })
+        .collect();
+
+    images.sort_by(|a, b| {
+        nav::human_sort(
+            &a.file_name().unwrap_or_default().to_string_lossy(),
+            &b.file_name().unwrap_or_default().to_string_lossy(),
+        )
+    });
+    images
+}
+
+fn parse_crop(crop: &str) -> Option<CropRegion> {
+    let values: Vec<u32> = crop
+        .split(',')
+        .map(|value| value.trim().parse().ok())
+        .collect::<Option<_>>()?;
+
+    match values.as_slice() {
+        [x, y, width, height] => Some(CropRegion {
+            x: *x,
+            y: *y,
+            width: *width,
+            height: *height,
+        }),
+        _ => None,
+    }
+}
@@ -0,0 +1,259 @@
+//! Command line options
+//!
+//! Options override the matching settings for the running session only; the
+//! values saved in the config are left as they were.
+
+use crate::{
+    batch,
+    config::{AppTheme, SortMode, SortOrder, ViewerConfig},
+    dbus,
+};
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+Usage: cupola [OPTIONS] [PATH]...
+       cupola <convert|resize|rotate|strip> [OPTIONS] <FILE|FOLDER|GLOB>...
+
+Opens the given images and folders. Several paths are shown together as a collection.
+
+Options:
+  --fullscreen            Start in fullscreen
+  --slideshow [SECS]      Start a slideshow, optionally changing image every SECS seconds
+  --sort MODE             Sort by name, date, size, taken, dimensions, type or random
+  --reverse               Sort in descending order
+  --recursive             Include images from subfolders
+  --hidden                Show hidden files
+  --theme THEME           Use a theme, e.g. system, dracula, nord, tokyo-night
+  --zoom fit|100          Open images fitted to the window or at 100%
+  --start-at FILE         Open FILE first
+  --new-instance          Start a separate window instead of using a running one
+  -h, --help              Print this help
+  -V, --version           Print the version
+
+Run 'cupola convert --help' for the batch processing options.
+";
+
+/// What the command line asked for
+#[derive(Debug)]
+pub enum Command {
+    Run(Args),
+    /// Process files without opening a window
+    Batch(batch::Job),
+    Help,
+    BatchHelp,
+    Version,
+}
+
+/// Options for starting the viewer
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    pub paths: Vec<PathBuf>,
+    pub overrides: Overrides,
+    pub fullscreen: bool,
+    pub slideshow: bool,
+    pub start_at: Option<PathBuf>,
+    pub new_instance: bool,
+    /// Bus name `main` claimed before starting the app; not a command line
+    /// option
+    pub bus: Option<dbus::Bus>,
+}
+
+impl Args {
+    /// Whether this launch can be handed to a running instance. Launches with
+    /// session options get their own instance so the options take effect.
+    pub fn forwardable(&self) -> bool {
+        !self.new_instance
+            && !self.fullscreen
+            && !self.slideshow
+            && self.start_at.is_none()
+            && self.overrides == Overrides::default()
+    }
+}
+
+/// Settings replaced for this session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Overrides {
+    pub slideshow_interval: Option<u32>,
+    pub sort_mode: Option<SortMode>,
+    pub sort_order: Option<SortOrder>,
+    pub include_subfolders: Option<bool>,
+    pub show_hidden_files: Option<bool>,
+    pub app_theme: Option<AppTheme>,
+    pub fit_to_window: Option<bool>,
+    pub default_zoom: Option<f32>,
+}
+
+impl Overrides {
+    pub fn apply(&self, config: &mut ViewerConfig) {
+        macro_rules! apply {
+            ($($field:ident),*) => {
+                $(if let Some(value) = self.$field {
+                    config.$field = value;
+                })*
+            };
+        }
+
+        apply!(
+            slideshow_interval,
+            sort_mode,
+            sort_order,
+            include_subfolders,
+            show_hidden_files,
+            app_theme,
+            fit_to_window,
+            default_zoom
+        );
+    }
+
+    /// The config to save: overridden values go back to what `saved` had,
+    /// unless they were changed during the session. Changed ones stop being
+    /// overrides so the user's choice sticks.
+    pub fn persisted(&mut self, config: &ViewerConfig, saved: &ViewerConfig) -> ViewerConfig {
+        let mut persisted = config.clone();
+
+        macro_rules! restore {
+            ($($field:ident),*) => {
+                $(if let Some(value) = self.$field {
+                    if config.$field == value {
+                        persisted.$field = saved.$field;
+                    } else {
+                        self.$field = None;
+                    }
+                })*
+            };
+        }
+
+        restore!(
+            slideshow_interval,
+            sort_mode,
+            sort_order,
+            include_subfolders,
+            show_hidden_files,
+            app_theme,
+            fit_to_window,
+            default_zoom
+        );
+
+        persisted
+    }
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Command, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter().peekable();
+
+    if let Some(command) = args.next_if(|arg| batch::SUBCOMMANDS.contains(&arg.as_str())) {
+        return Ok(match batch::parse(&command, args)? {
+            Some(job) => Command::Batch(job),
+            None => Command::BatchHelp,
+        });
+    }
+    let mut only_paths = false;
+
+    while let Some(arg) = args.next() {
+        if only_paths || !arg.starts_with('-') {
+            parsed.paths.push(PathBuf::from(arg));
+            continue;
+        }
+
+        // Accept both "--sort date" and "--sort=date"
+        let (name, inline) = match arg.split_once('=') {
+            Some((name, value)) => (name.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = |what: &str| {
+            inline
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("{name} needs {what}"))
+        };
+
+        match name.as_str() {
+            "--" => only_paths = true,
+            "-h" | "--help" => return Ok(Command::Help),
+            "-V" | "--version" => return Ok(Command::Version),
+            "--fullscreen" => parsed.fullscreen = true,
+            "--slideshow" => {
+                parsed.slideshow = true;
+
+                // The interval is optional, so only take the next argument if it's a number
+                let secs = match inline {
+                    Some(secs) => Some(secs),
+                    None => args.next_if(|next| next.parse::<u32>().is_ok()),
+                };
+                if let Some(secs) = secs {
+                    let secs = secs
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|secs| *secs > 0)
+                        .ok_or_else(|| format!("invalid slideshow interval '{secs}'"))?;
+                    parsed.overrides.slideshow_interval = Some(secs);
+                }
+            }
+            "--sort" => {
+                let mode = value("a sort mode")?;
+                parsed.overrides.sort_mode = Some(
+                    parse_sort_mode(&mode).ok_or_else(|| format!("unknown sort mode '{mode}'"))?,
+                );
+            }
+            "--reverse" => parsed.overrides.sort_order = Some(SortOrder::Descending),
+            "--recursive" => parsed.overrides.include_subfolders = Some(true),
+            "--hidden" => parsed.overrides.show_hidden_files = Some(true),
+            "--theme" => {
+                let theme = value("a theme")?;
+                parsed.overrides.app_theme =
+                    Some(parse_theme(&theme).ok_or_else(|| format!("unknown theme '{theme}'"))?);
+            }
+            "--zoom" => match value("fit or 100")?.trim_end_matches('%') {
+                "fit" => parsed.overrides.fit_to_window = Some(true),
+                "100" => {
+                    parsed.overrides.fit_to_window = Some(false);
+                    parsed.overrides.default_zoom = Some(1.0);
+                }
+                zoom => return Err(format!("unknown zoom '{zoom}', expected fit or 100")),
+            },
+            "--new-instance" => parsed.new_instance = true,
+            "--start-at" => parsed.start_at = Some(PathBuf::from(value("a file")?)),
+            _ => return Err(format!("unknown option '{name}'")),
+        }
+    }
+
+    Ok(Command::Run(parsed))
+}
+
+fn parse_sort_mode(mode: &str) -> Option<SortMode> {
+    match normalize(mode).as_str() {
+        "name" => Some(SortMode::Name),
+        "date" | "modified" | "datemodified" => Some(SortMode::Date),
+        "size" => Some(SortMode::Size),
+        "taken" | "datetaken" => Some(SortMode::DateTaken),
+        "dimensions" => Some(SortMode::Dimensions),
+        "type" => Some(SortMode::Type),
+        "random" => Some(SortMode::Random),
+        _ => None,
+    }
+}
+
+/// Themes are matched by their display name, ignoring case and punctuation
+fn parse_theme(theme: &str) -> Option<AppTheme> {
+    let theme = normalize(theme);
+
+    AppTheme::ALL
+        .iter()
+        .copied()
+        .find(|candidate| normalize(&candidate.to_string()) == theme)
+}
+
+fn normalize(input: &str) -> String {
+    input
+        .chars()
+        .map(|c| {
+            if c == 'é' {
+                'e'
+            } else {
+                c.to_ascii_lowercase()
+            }
+        })
+        .filter(|c| c.is_ascii_alphanumeric())
+        .collect()
+}
@@ -33,18 +33,38 @@ pub enum SortMode {
     Name,
     Date,
     Size,
+    /// EXIF DateTimeOriginal, falling back to the modification time
+    DateTaken,
+    /// Pixel count
+    Dimensions,
+    /// File extension, then name
+    Type,
+    /// Shuffled with a seed that stays fixed for the session
+    Random,
 }
 
 impl SortMode {
-    pub const ALL: &'static [Self] = &[Self::Name, Self::Date, Self::Size];
+    pub const ALL: &'static [Self] = &[
+        Self::Name,
+        Self::Date,
+        Self::Size,
+        Self::DateTaken,
+        Self::Dimensions,
+        Self::Type,
+        Self::Random,
+    ];
 }
 
 impl fmt::Display for SortMode {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             SortMode::Name => write!(f, "Name"),
-            SortMode::Date => write!(f, "Date"),
+            SortMode::Date => write!(f, "Date Modified"),
             SortMode::Size => write!(f, "Size"),
+            SortMode::DateTaken => write!(f, "Date Taken"),
+            SortMode::Dimensions => write!(f, "Dimensions"),
+            SortMode::Type => write!(f, "Type"),
+            SortMode::Random => write!(f, "Random"),
         }
     }
 }
@@ -76,26 +96,169 @@ impl fmt::Display for SortOrder {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
-pub enum ThumbnailSize {
+/// Edge of a gallery thumbnail in pixels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ThumbnailSize(u32);
+
+impl ThumbnailSize {
+    pub const MIN: u32 = 48;
+    pub const MAX: u32 = 512;
+    /// Resolutions thumbnails are decoded at. Each size uses the smallest
+    /// tier that covers it, so resizing within a tier only rescales.
+    const TIERS: [u32; 4] = [64, 128, 256, 512];
+    /// Growth per wheel step
+    const ZOOM_STEP: f32 = 1.1;
+
+    pub fn new(pixels: u32) -> Self {
+        Self(pixels.clamp(Self::MIN, Self::MAX))
+    }
+
+    /// Read the stored size, taking a named size from an older version as
+    /// the pixels it stood for
+    fn get(config: &Config) -> Result<Self, cosmic_config::Error> {
+        config.get::<Self>("thumbnail_size").or_else(|err| {
+            config
+                .get::<LegacyThumbnailSize>("thumbnail_size")
+                .map(Self::from)
+                .map_err(|_| err)
+        })
+    }
+
+    pub fn pixels(self) -> u32 {
+        // A hand-edited config may hold anything
+        self.0.clamp(Self::MIN, Self::MAX)
+    }
+
+    /// Resolution to decode thumbnails at for this size
+    pub fn tier(self) -> u32 {
+        Self::TIERS
+            .into_iter()
+            .find(|&tier| tier >= self.pixels())
+            .unwrap_or(Self::MAX)
+    }
+
+    /// The size after `steps` wheel steps; even a small touchpad step moves
+    /// it by a pixel
+    pub fn zoomed(self, steps: f32) -> Self {
+        let current = self.pixels();
+        let scaled = (current as f32 * Self::ZOOM_STEP.powf(steps)).round() as u32;
+
+        if steps > 0.0 {
+            Self::new(scaled.max(current + 1))
+        } else if steps < 0.0 {
+            Self::new(scaled.min(current.saturating_sub(1)))
+        } else {
+            self
+        }
+    }
+}
+
+impl Default for ThumbnailSize {
+    fn default() -> Self {
+        Self(128)
+    }
+}
+
+/// Named sizes stored by versions before the size became a pixel count
+#[derive(Debug, Clone, Copy, Deserialize)]
+enum LegacyThumbnailSize {
     Small,
-    #[default]
     Medium,
     Large,
     XLarge,
 }
 
-impl ThumbnailSize {
-    pub fn pixels(self) -> u32 {
-        match self {
-            ThumbnailSize::Small => 64,
-            ThumbnailSize::Medium => 128,
-            ThumbnailSize::Large => 192,
-            ThumbnailSize::XLarge => 256,
+impl From<LegacyThumbnailSize> for ThumbnailSize {
+    fn from(size: LegacyThumbnailSize) -> Self {
+        Self::new(match size {
+            LegacyThumbnailSize::Small => 64,
+            LegacyThumbnailSize::Medium => 128,
+            LegacyThumbnailSize::Large => 192,
+            LegacyThumbnailSize::XLarge => 256,
+        })
+    }
+}
+
+/// How the gallery arranges thumbnails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GalleryLayout {
+    /// Square cells in columns
+    #[default]
+    Grid,
+    /// Rows that keep each image's aspect ratio and fill the width
+    Justified,
+    /// One row per image with its details in sortable columns
+    List,
+}
+
+/// A mark the gallery can draw over thumbnails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    /// File format, and the size of very large files
+    Format,
+    Animation,
+    Rating,
+    /// Edited in this session, saved or not
+    Edited,
+    /// The thumbnail couldn't be loaded
+    Failed,
+}
+
+/// Which badges the gallery draws over thumbnails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThumbnailBadges {
+    pub format: bool,
+    pub animation: bool,
+    pub rating: bool,
+    pub edited: bool,
+    pub failed: bool,
+}
+
+impl ThumbnailBadges {
+    pub fn shows(&self, badge: Badge) -> bool {
+        match badge {
+            Badge::Format => self.format,
+            Badge::Animation => self.animation,
+            Badge::Rating => self.rating,
+            Badge::Edited => self.edited,
+            Badge::Failed => self.failed,
+        }
+    }
+
+    pub fn set(&mut self, badge: Badge, show: bool) {
+        match badge {
+            Badge::Format => self.format = show,
+            Badge::Animation => self.animation = show,
+            Badge::Rating => self.rating = show,
+            Badge::Edited => self.edited = show,
+            Badge::Failed => self.failed = show,
+        }
+    }
+}
+
+impl Default for ThumbnailBadges {
+    fn default() -> Self {
+        Self {
+            format: true,
+            animation: true,
+            rating: true,
+            edited: true,
+            failed: true,
         }
     }
 }
 
+/// How the gallery groups images under date headers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum DateGrouping {
+    #[default]
+    Off,
+    Day,
+    Month,
+    Year,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum AppTheme {
     #[default]
@@ -227,6 +390,9 @@ impl fmt::Display for AppTheme {
 /// Maximum number of recent folders to remember
 pub const MAX_RECENT_FOLDERS: usize = 10;
 
+/// Quick-sort destinations, bound to Alt+1 to Alt+9
+pub const QUICK_SORT_SLOTS: usize = 9;
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ViewerConfig {
     pub app_theme: AppTheme,
@@ -237,12 +403,24 @@ pub struct ViewerConfig {
     pub slideshow_interval: u32,
     pub smooth_scaling: bool,
     pub thumbnail_size: ThumbnailSize,
+    pub gallery_layout: GalleryLayout,
+    /// Group the gallery by when the photos were taken
+    pub date_grouping: DateGrouping,
+    pub thumbnail_badges: ThumbnailBadges,
     pub cache_size: usize,
     pub show_hidden_files: bool,
     pub wallpaper_behavior: WallpaperBehavior,
     pub sort_mode: SortMode,
     pub sort_order: SortOrder,
     pub recent_folders: Vec<String>,
+    /// Folders listed as roots of the folder tree
+    pub pinned_folders: Vec<String>,
+    pub include_subfolders: bool,
+    /// How many levels below the opened folder are scanned with `include_subfolders`
+    pub subfolder_depth: u32,
+    pub group_by_folder: bool,
+    /// Folders images are moved to with Alt+1 to Alt+9, by slot
+    pub quick_sort_folders: Vec<Option<String>>,
 }
 
 impl Default for ViewerConfig {
@@ -256,12 +434,20 @@ impl Default for ViewerConfig {
             slideshow_interval: 5,
             smooth_scaling: true,
             thumbnail_size: ThumbnailSize::default(),
+            gallery_layout: GalleryLayout::default(),
+            date_grouping: DateGrouping::default(),
+            thumbnail_badges: ThumbnailBadges::default(),
             cache_size: 20,
             show_hidden_files: false,
             wallpaper_behavior: WallpaperBehavior::default(),
             sort_mode: SortMode::default(),
             sort_order: SortOrder::default(),
             recent_folders: Vec::new(),
+            pinned_folders: Vec::new(),
+            include_subfolders: false,
+            subfolder_depth: 3,
+            group_by_folder: true,
+            quick_sort_folders: vec![None; QUICK_SORT_SLOTS],
         }
     }
 }
@@ -278,6 +464,20 @@ impl ViewerConfig {
         // Trim to max size
         self.recent_folders.truncate(MAX_RECENT_FOLDERS);
     }
+
+    pub fn quick_sort_folder(&self, slot: usize) -> Option<&str> {
+        self.quick_sort_folders.get(slot)?.as_deref()
+    }
+
+    pub fn set_quick_sort_folder(&mut self, slot: usize, folder: Option<String>) {
+        if slot >= QUICK_SORT_SLOTS {
+            return;
+        }
+        if self.quick_sort_folders.len() <= slot {
+            self.quick_sort_folders.resize(slot + 1, None);
+        }
+        self.quick_sort_folders[slot] = folder;
+    }
 }
 
 impl CosmicConfigEntry for ViewerConfig {
@@ -292,12 +492,20 @@ impl CosmicConfigEntry for ViewerConfig {
         config.set("slideshow_interval", self.slideshow_interval)?;
         config.set("smooth_scaling", self.smooth_scaling)?;
         config.set("thumbnail_size", self.thumbnail_size)?;
+        config.set("gallery_layout", self.gallery_layout)?;
+        config.set("date_grouping", self.date_grouping)?;
+        config.set("thumbnail_badges", self.thumbnail_badges)?;
         config.set("cache_size", self.cache_size)?;
         config.set("show_hidden_files", self.show_hidden_files)?;
         config.set("wallpaper_behavior", self.wallpaper_behavior)?;
         config.set("sort_mode", self.sort_mode)?;
         config.set("sort_order", self.sort_order)?;
         config.set("recent_folders", self.recent_folders.clone())?;
+        config.set("pinned_folders", self.pinned_folders.clone())?;
+        config.set("include_subfolders", self.include_subfolders)?;
+        config.set("subfolder_depth", self.subfolder_depth)?;
+        config.set("group_by_folder", self.group_by_folder)?;
+        config.set("quick_sort_folders", self.quick_sort_folders.clone())?;
         Ok(())
     }
 
@@ -322,13 +530,28 @@ impl CosmicConfigEntry for ViewerConfig {
         get_field!("remember_last_dir", remember_last_dir, bool);
         get_field!("last_dir", last_dir, Option<String>);
         get_field!("slideshow_interval", slideshow_interval, u32);
-        get_field!("thumbnail_size", thumbnail_size, ThumbnailSize);
+        match ThumbnailSize::get(config) {
+            Ok(val) => cfg.thumbnail_size = val,
+            Err(e) => errors.push(e),
+        }
+        get_field!("gallery_layout", gallery_layout, GalleryLayout);
+        get_field!("date_grouping", date_grouping, DateGrouping);
+        get_field!("thumbnail_badges", thumbnail_badges, ThumbnailBadges);
         get_field!("cache_size", cache_size, usize);
         get_field!("show_hidden_files", show_hidden_files, bool);
         get_field!("wallpaper_behavior", wallpaper_behavior, WallpaperBehavior);
         get_field!("sort_mode", sort_mode, SortMode);
         get_field!("sort_order", sort_order, SortOrder);
         get_field!("recent_folders", recent_folders, Vec<String>);
+        get_field!("pinned_folders", pinned_folders, Vec<String>);
+        get_field!("include_subfolders", include_subfolders, bool);
+        get_field!("subfolder_depth", subfolder_depth, u32);
+        get_field!("group_by_folder", group_by_folder, bool);
+        get_field!(
+            "quick_sort_folders",
+            quick_sort_folders,
+            Vec<Option<String>>
+        );
 
         if errors.is_empty() {
             Ok(cfg)
@@ -382,6 +605,27 @@ impl CosmicConfigEntry for ViewerConfig {
                     }
                     Err(e) => errors.push(e),
                 },
+                "include_subfolders" => match config.get::<bool>("include_subfolders") {
+                    Ok(val) => {
+                        self.include_subfolders = val;
+                        updated.push("include_subfolders");
+                    }
+                    Err(e) => errors.push(e),
+                },
+                "subfolder_depth" => match config.get::<u32>("subfolder_depth") {
+                    Ok(val) => {
+                        self.subfolder_depth = val;
+                        updated.push("subfolder_depth");
+                    }
+                    Err(e) => errors.push(e),
+                },
+                "group_by_folder" => match config.get::<bool>("group_by_folder") {
+                    Ok(val) => {
+                        self.group_by_folder = val;
+                        updated.push("group_by_folder");
+                    }
+                    Err(e) => errors.push(e),
+                },
                 _ => {}
             }
         }
@@ -0,0 +1,378 @@
+//! Session bus integration
+//!
+//! The first instance claims the app's well-known name before opening its
+//! window and implements `org.freedesktop.Application`; later launches hand
+//! their paths to it through `Open` instead of starting another process.
+//!
+//! The same object also serves `org.codeberg.bhh32.Cupola.Viewer`, which lets
+//! scripts navigate and run the slideshow:
+//!
+//! ```sh
+//! busctl --user call org.codeberg.bhh32.Cupola /org/codeberg/bhh32/Cupola \
+//!     org.codeberg.bhh32.Cupola.Viewer GoTo u 3
+//! ```
+
+use crate::{
+    app::ImageViewer,
+    message::{Message, NavMessage, ViewMessage},
+};
+use ashpd::url::Url;
+use cosmic::{
+    iced::Subscription,
+    iced_futures::futures::{
+        SinkExt, StreamExt,
+        channel::mpsc::{self, Receiver, Sender},
+        future,
+    },
+};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+use tokio::sync::watch;
+use zbus::{
+    fdo::{RequestNameFlags, RequestNameReply},
+    zvariant::{OwnedValue, Value},
+};
+
+pub const BUS_NAME: &str = ImageViewer::APP_ID;
+pub const OBJECT_PATH: &str = "/org/codeberg/bhh32/Cupola";
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Application",
+    default_service = "org.codeberg.bhh32.Cupola",
+    default_path = "/org/codeberg/bhh32/Cupola"
+)]
+trait Application {
+    fn activate(&self, platform_data: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+
+    fn open(&self, uris: &[&str], platform_data: HashMap<&str, Value<'_>>) -> zbus::Result<()>;
+}
+
+/// This process's hold on the bus name. Activations that arrive before the
+/// window is up wait in `requests` until [`serve`] passes them on.
+#[derive(Debug, Clone)]
+pub struct Bus {
+    connection: zbus::Connection,
+    requests: Arc<Mutex<Option<Receiver<Message>>>>,
+}
+
+/// Who got the bus name
+#[derive(Debug)]
+pub enum Claim {
+    /// This process has it, so later launches open here
+    Owned(Bus),
+    /// A running instance has it already
+    Taken,
+}
+
+/// Request the bus name and start taking activations, before any window
+/// opens so that of two launches at once only one becomes the instance
+pub async fn claim() -> zbus::Result<Claim> {
+    let (sender, receiver) = mpsc::channel(10);
+    let connection = zbus::connection::Builder::session()?
+        .serve_at(OBJECT_PATH, Activation { output: sender })?
+        .build()
+        .await?;
+
+    let reply = connection
+        .request_name_with_flags(BUS_NAME, RequestNameFlags::DoNotQueue.into())
+        .await;
+
+    match reply {
+        Ok(RequestNameReply::PrimaryOwner | RequestNameReply::AlreadyOwner) => {
+            Ok(Claim::Owned(Bus {
+                connection,
+                requests: Arc::new(Mutex::new(Some(receiver))),
+            }))
+        }
+        Ok(_) | Err(zbus::Error::NameTaken) => Ok(Claim::Taken),
+        Err(err) => Err(err),
+    }
+}
+
+/// Hand `paths` to the running instance, or just raise it when there are
+/// none. Returns false when it couldn't be reached.
+pub async fn forward(paths: &[PathBuf]) -> bool {
+    match try_forward(paths).await {
+        Ok(forwarded) => forwarded,
+        Err(why) => {
+            tracing::warn!("Couldn't reach the running instance: {why}");
+            false
+        }
+    }
+}
+
+async fn try_forward(paths: &[PathBuf]) -> zbus::Result<bool> {
+    let connection = zbus::Connection::session().await?;
+
+    // Only talk to a live instance; D-Bus activation would start a new one
+    let dbus = zbus::fdo::DBusProxy::new(&connection).await?;
+    if !dbus.name_has_owner(BUS_NAME.try_into()?).await? {
+        return Ok(false);
+    }
+
+    // Lets the running window take focus from the launcher
+    let mut platform_data = HashMap::new();
+    if let Ok(token) = std::env::var("XDG_ACTIVATION_TOKEN") {
+        platform_data.insert("activation-token", Value::from(token));
+    }
+    if let Ok(id) = std::env::var("DESKTOP_STARTUP_ID") {
+        platform_data.insert("desktop-startup-id", Value::from(id));
+    }
+
+    let proxy = ApplicationProxy::new(&connection).await?;
+    if paths.is_empty() {
+        proxy.activate(platform_data).await?;
+    } else {
+        let uris: Vec<String> = paths
+            .iter()
+            .map(|path| match Url::from_file_path(path) {
+                Ok(url) => url.to_string(),
+                Err(()) => path.to_string_lossy().into_owned(),
+            })
+            .collect();
+        let uris: Vec<&str> = uris.iter().map(String::as_str).collect();
+        proxy.open(&uris, platform_data).await?;
+    }
+
+    Ok(true)
+}
+
+/// The token the launcher was started with, which lets the window take focus
+fn activation_token(platform_data: &HashMap<String, OwnedValue>) -> Option<String> {
+    let value = platform_data.get("activation-token")?;
+    <&str>::try_from(&**value).ok().map(str::to_owned)
+}
+
+/// `org.freedesktop.Application` served by the running instance
+struct Activation {
+    output: Sender<Message>,
+}
+
+#[zbus::interface(name = "org.freedesktop.Application")]
+impl Activation {
+    async fn activate(&self, platform_data: HashMap<String, OwnedValue>) {
+        let token = activation_token(&platform_data);
+        let _ = self
+            .output
+            .clone()
+            .send(Message::Activate(Vec::new(), token))
+            .await;
+    }
+
+    async fn open(&self, uris: Vec<String>, platform_data: HashMap<String, OwnedValue>) {
+        let paths = uris
+            .iter()
+            .map(|uri| {
+                Url::parse(uri)
+                    .ok()
+                    .and_then(|url| url.to_file_path().ok())
+                    .unwrap_or_else(|| PathBuf::from(uri))
+            })
+            .collect();
+
+        let token = activation_token(&platform_data);
+        let _ = self
+            .output
+            .clone()
+            .send(Message::Activate(paths, token))
+            .await;
+    }
+
+    async fn activate_action(
+        &self,
+        _action_name: String,
+        _parameter: Vec<OwnedValue>,
+        platform_data: HashMap<String, OwnedValue>,
+    ) {
+        // No actions are exported yet, so treat it as a plain activation
+        let token = activation_token(&platform_data);
+        let _ = self
+            .output
+            .clone()
+            .send(Message::Activate(Vec::new(), token))
+            .await;
+    }
+}
+
+/// What the viewer is showing, as reported by the control interface
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Status {
+    pub current_image: Option<PathBuf>,
+    pub current_index: Option<usize>,
+    pub image_count: usize,
+    pub slideshow_active: bool,
+    pub fullscreen: bool,
+}
+
+/// Remote control for navigation and the slideshow; every method maps onto
+/// the message the matching menu item or key sends
+struct Viewer {
+    output: Sender<Message>,
+    status: watch::Receiver<Status>,
+}
+
+impl Viewer {
+    async fn send(&self, message: Message) {
+        let _ = self.output.clone().send(message).await;
+    }
+}
+
+#[zbus::interface(name = "org.codeberg.bhh32.Cupola.Viewer")]
+impl Viewer {
+    async fn next(&self) {
+        self.send(Message::Nav(NavMessage::Next)).await;
+    }
+
+    async fn previous(&self) {
+        self.send(Message::Nav(NavMessage::Prev)).await;
+    }
+
+    /// Open the image at `index` in the current list
+    async fn go_to(&self, index: u32) -> zbus::fdo::Result<()> {
+        if index as usize >= self.status.borrow().image_count {
+            return Err(zbus::fdo::Error::InvalidArgs(format!(
+                "no image at index {index}"
+            )));
+        }
+
+        self.send(Message::Nav(NavMessage::GoTo(index as usize)))
+            .await;
+        Ok(())
+    }
+
+    /// Open an image or folder
+    async fn open_path(&self, path: String) {
+        self.send(Message::OpenPath(PathBuf::from(path))).await;
+    }
+
+    async fn start_slideshow(&self) {
+        self.send(Message::View(ViewMessage::StartSlideshow)).await;
+    }
+
+    async fn stop_slideshow(&self) {
+        self.send(Message::View(ViewMessage::StopSlideshow)).await;
+    }
+
+    async fn set_fullscreen(&self, fullscreen: bool) {
+        if self.status.borrow().fullscreen != fullscreen {
+            self.send(Message::View(ViewMessage::ToggleFullScreen))
+                .await;
+        }
+    }
+
+    /// Path of the open image, empty when none is open
+    #[zbus(property)]
+    fn current_image(&self) -> String {
+        self.status
+            .borrow()
+            .current_image
+            .as_ref()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    }
+
+    /// Index of the open image, -1 when none is open
+    #[zbus(property)]
+    fn current_index(&self) -> i64 {
+        self.status
+            .borrow()
+            .current_index
+            .map_or(-1, |index| index as i64)
+    }
+
+    #[zbus(property)]
+    fn image_count(&self) -> u32 {
+        self.status.borrow().image_count as u32
+    }
+
+    #[zbus(property)]
+    fn slideshow_active(&self) -> bool {
+        self.status.borrow().slideshow_active
+    }
+
+    #[zbus(property)]
+    fn fullscreen(&self) -> bool {
+        self.status.borrow().fullscreen
+    }
+}
+
+/// Pass on the activations arriving on `bus` and serve the control
+/// interface, announcing every change to `status`
+pub fn serve(bus: Bus, status: watch::Receiver<Status>) -> Subscription<Message> {
+    Subscription::run_with_id(
+        BUS_NAME,
+        cosmic::iced::stream::channel(10, |mut output| async move {
+            let viewer = Viewer {
+                output: output.clone(),
+                status: status.clone(),
+            };
+            if let Err(why) = bus.connection.object_server().at(OBJECT_PATH, viewer).await {
+                tracing::error!("Couldn't serve the control interface: {why}");
+            }
+
+            // Including any that came in while the window was opening
+            let requests = bus
+                .requests
+                .lock()
+                .ok()
+                .and_then(|mut requests| requests.take());
+            let relay = async {
+                if let Some(mut requests) = requests {
+                    while let Some(message) = requests.next().await {
+                        let _ = output.send(message).await;
+                    }
+                }
+            };
+            let announce = async {
+                if let Err(why) = announce_changes(&bus.connection, status).await {
+                    tracing::warn!("D-Bus property updates stopped: {why}");
+                }
+            };
+
+            // Keeps the connection open for as long as the subscription runs
+            future::join(relay, announce).await;
+            std::future::pending::<()>().await;
+        }),
+    )
+}
+
+/// Emit `PropertiesChanged` for whatever differs each time the status changes
+async fn announce_changes(
+    connection: &zbus::Connection,
+    mut status: watch::Receiver<Status>,
+) -> zbus::Result<()> {
+    let viewer = connection
+        .object_server()
+        .interface::<_, Viewer>(OBJECT_PATH)
+        .await?;
+    let emitter = viewer.signal_emitter();
+    let mut announced = status.borrow_and_update().clone();
+
+    while status.changed().await.is_ok() {
+        let current = status.borrow_and_update().clone();
+        let viewer = viewer.get().await;
+
+        if current.current_image != announced.current_image {
+            viewer.current_image_changed(emitter).await?;
+        }
+        if current.current_index != announced.current_index {
+            viewer.current_index_changed(emitter).await?;
+        }
+        if current.image_count != announced.image_count {
+            viewer.image_count_changed(emitter).await?;
+        }
+        if current.slideshow_active != announced.slideshow_active {
+            viewer.slideshow_active_changed(emitter).await?;
+        }
+        if current.fullscreen != announced.fullscreen {
+            viewer.fullscreen_changed(emitter).await?;
+        }
+
+        announced = current;
+    }
+
+    Ok(())
+}
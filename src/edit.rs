@@ -3,4 +3,4 @@ pub mod operations;
 
 pub use state::{EditState, Transform};
 pub use crate::widgets::CropRegion;
-pub use operations::{apply_transform, apply_transforms, crop_image, save_image, apply_edits_to_image, EditError};
+pub use operations::{apply_transform, apply_transforms, crop_image, save_image, save_image_with_quality, apply_edits_to_image, transform_in_place, temp_sibling, replace_with, strip_metadata, EditError};
@@ -1,6 +1,13 @@
 use cosmic::widget::image::Handle;
-use image::{DynamicImage, GenericImageView};
-use std::path::{Path, PathBuf};
+use image::{
+    DynamicImage, GenericImageView, ImageDecoder, ImageFormat, ImageReader,
+    codecs::{avif::AvifEncoder, jpeg::JpegEncoder},
+};
+use std::{
+    fs::{self, File},
+    io::{BufWriter, Cursor},
+    path::{Path, PathBuf},
+};
 use thiserror::Error;
 
 use crate::widgets::CropRegion;
@@ -71,6 +78,35 @@ pub async fn save_image(
     .map_err(|err| EditError::SaveError(err.to_string()))?
 }
 
+/// Save with an encoder quality from 1 to 100. Only JPEG and AVIF have one;
+/// other formats are saved as by [`save_image`].
+pub async fn save_image_with_quality(
+    img: DynamicImage,
+    path: &Path,
+    quality: u8,
+) -> Result<(), EditError> {
+    let path = path.to_path_buf();
+    let quality = quality.clamp(1, 100);
+
+    tokio::task::spawn_blocking(move || {
+        let result = match ImageFormat::from_path(&path) {
+            Ok(ImageFormat::Jpeg) => {
+                let writer = BufWriter::new(File::create(&path)?);
+                img.write_with_encoder(JpegEncoder::new_with_quality(writer, quality))
+            }
+            Ok(ImageFormat::Avif) => {
+                let writer = BufWriter::new(File::create(&path)?);
+                img.write_with_encoder(AvifEncoder::new_with_speed_quality(writer, 4, quality))
+            }
+            _ => img.save(&path),
+        };
+
+        result.map_err(|err| EditError::SaveError(err.to_string()))
+    })
+    .await
+    .map_err(|err| EditError::SaveError(err.to_string()))?
+}
+
 pub async fn apply_edits_to_image(
     original_path: &Path,
     transforms: &[Transform],
@@ -106,3 +142,297 @@ pub async fn apply_edits_to_image(
 
     rx.await.map_err(|_| EditError::SaveError("Task cancelled".into()))?
 }
+
+/// JPEG quality used when rotating or flipping a photo in place
+const TRANSFORM_QUALITY: u8 = 95;
+
+/// Rotate or flip the image at `path` and save it over itself.
+///
+/// The EXIF orientation is baked into the pixels first and the EXIF data is
+/// written back with the orientation reset to upright. Only JPEG and PNG get
+/// their EXIF back; other formats are saved with the pixels alone. JPEGs are
+/// re-encoded at [`TRANSFORM_QUALITY`] rather than the encoder's default.
+pub fn transform_in_place(path: &Path, transform: Transform) -> Result<(), EditError> {
+    let mut decoder = ImageReader::open(path)?
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let exif = decoder.exif_metadata()?;
+    let mut img = DynamicImage::from_decoder(decoder)?;
+    img.apply_orientation(orientation);
+    let img = apply_transform(&img, transform);
+
+    let format = ImageFormat::from_path(path)?;
+    let mut encoded = Vec::new();
+    match format {
+        ImageFormat::Jpeg => img.write_with_encoder(JpegEncoder::new_with_quality(
+            &mut encoded,
+            TRANSFORM_QUALITY,
+        ))?,
+        _ => img.write_to(&mut Cursor::new(&mut encoded), format)?,
+    }
+
+    if let Some(mut exif) = exif {
+        // Some decoders hand back the APP1 identifier along with the TIFF data
+        if exif.starts_with(EXIF_ID) {
+            exif.drain(..EXIF_ID.len());
+        }
+        reset_orientation(&mut exif);
+
+        encoded = match format {
+            ImageFormat::Jpeg => insert_jpeg_exif(encoded, &exif),
+            ImageFormat::Png => insert_png_exif(encoded, &exif),
+            _ => encoded,
+        };
+    }
+
+    write_over(path, &encoded)?;
+    Ok(())
+}
+
+/// A path beside `path` to write to before renaming over it. The extension
+/// is kept so the format can still be told from the name.
+pub fn temp_sibling(path: &Path) -> PathBuf {
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let name = match path.extension() {
+        Some(ext) => format!(
+            ".{stem}.cupola-{}.{}",
+            std::process::id(),
+            ext.to_string_lossy()
+        ),
+        None => format!(".{stem}.cupola-{}", std::process::id()),
+    };
+    path.with_file_name(name)
+}
+
+/// Move a finished `temp` file over `path`, keeping the permissions `path`
+/// had. `temp` is removed if that fails.
+pub fn replace_with(temp: &Path, path: &Path) -> std::io::Result<()> {
+    let result = fs::metadata(path)
+        .and_then(|metadata| fs::set_permissions(temp, metadata.permissions()))
+        .or_else(|err| match err.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(err),
+        })
+        .and_then(|()| fs::rename(temp, path));
+
+    if result.is_err() {
+        let _ = fs::remove_file(temp);
+    }
+    result
+}
+
+/// Write `bytes` over `path` without ever leaving it half written
+fn write_over(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let temp = temp_sibling(path);
+    if let Err(err) = fs::write(&temp, bytes) {
+        let _ = fs::remove_file(&temp);
+        return Err(err);
+    }
+    replace_with(&temp, path)
+}
+
+/// What starts the EXIF APP1 segment of a JPEG, ahead of the TIFF data
+const EXIF_ID: &[u8] = b"Exif\0\0";
+
+/// Set the orientation tag of raw EXIF (TIFF) data to upright, if it has one
+fn reset_orientation(exif: &mut [u8]) {
+    const ORIENTATION: u16 = 0x0112;
+
+    let big_endian = match exif.get(..2) {
+        Some(b"MM") => true,
+        Some(b"II") => false,
+        _ => return,
+    };
+    let read_u16 = |bytes: &[u8], at: usize| {
+        let bytes: [u8; 2] = bytes.get(at..at + 2)?.try_into().ok()?;
+        Some(if big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    };
+    let read_u32 = |bytes: &[u8], at: usize| {
+        let bytes: [u8; 4] = bytes.get(at..at + 4)?.try_into().ok()?;
+        Some(if big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    };
+
+    let Some(ifd) = read_u32(exif, 4).map(|offset| offset as usize) else {
+        return;
+    };
+    let Some(count) = read_u16(exif, ifd) else {
+        return;
+    };
+
+    // IFD0 entries are 12 bytes: tag, type, count, then the value itself
+    for entry in (0..count as usize).map(|i| ifd + 2 + i * 12) {
+        if read_u16(exif, entry) == Some(ORIENTATION) {
+            let upright = if big_endian {
+                1u16.to_be_bytes()
+            } else {
+                1u16.to_le_bytes()
+            };
+            if let Some(value) = exif.get_mut(entry + 8..entry + 10) {
+                value.copy_from_slice(&upright);
+            }
+            return;
+        }
+    }
+}
+
+/// Put `exif` into an encoded JPEG as an APP1 segment, after the JFIF header
+fn insert_jpeg_exif(jpeg: Vec<u8>, exif: &[u8]) -> Vec<u8> {
+    let length = EXIF_ID.len() + exif.len() + 2;
+    let Ok(length) = u16::try_from(length) else {
+        // Too big for one segment
+        return jpeg;
+    };
+
+    // Right after the start of image marker, or after APP0 when there is one
+    let mut at = 2;
+    if jpeg.get(2..4) == Some(&[0xFF, 0xE0]) {
+        at = jpeg
+            .get(4..6)
+            .map_or(2, |len| 4 + u16::from_be_bytes([len[0], len[1]]) as usize);
+    }
+    if at > jpeg.len() {
+        return jpeg;
+    }
+
+    let mut out = Vec::with_capacity(jpeg.len() + length as usize + 2);
+    out.extend_from_slice(&jpeg[..at]);
+    out.extend_from_slice(&[0xFF, 0xE1]);
+    out.extend_from_slice(&length.to_be_bytes());
+    out.extend_from_slice(EXIF_ID);
+    out.extend_from_slice(exif);
+    out.extend_from_slice(&jpeg[at..]);
+    out
+}
+
+/// Put `exif` into an encoded PNG as an eXIf chunk, right after IHDR
+fn insert_png_exif(png: Vec<u8>, exif: &[u8]) -> Vec<u8> {
+    /// Signature, then the IHDR chunk: length, type, 13 bytes of data, CRC
+    const AFTER_IHDR: usize = 8 + 4 + 4 + 13 + 4;
+
+    let Ok(length) = u32::try_from(exif.len()) else {
+        return png;
+    };
+    if png.len() < AFTER_IHDR {
+        return png;
+    }
+
+    let mut chunk = Vec::with_capacity(exif.len() + 4);
+    chunk.extend_from_slice(b"eXIf");
+    chunk.extend_from_slice(exif);
+
+    let mut out = Vec::with_capacity(png.len() + chunk.len() + 8);
+    out.extend_from_slice(&png[..AFTER_IHDR]);
+    out.extend_from_slice(&length.to_be_bytes());
+    out.extend_from_slice(&chunk);
+    out.extend_from_slice(&crc32(&chunk).to_be_bytes());
+    out.extend_from_slice(&png[AFTER_IHDR..]);
+    out
+}
+
+/// Drop the metadata of an encoded JPEG or PNG without decoding it: EXIF,
+/// XMP, IPTC and comments. Color profiles are kept. `None` for other formats
+/// or data that doesn't parse.
+pub fn strip_metadata(encoded: &[u8], format: ImageFormat) -> Option<Vec<u8>> {
+    match format {
+        ImageFormat::Jpeg => strip_jpeg(encoded),
+        ImageFormat::Png => strip_png(encoded),
+        _ => None,
+    }
+}
+
+fn strip_jpeg(jpeg: &[u8]) -> Option<Vec<u8>> {
+    /// APP1 holds EXIF and XMP, APP13 IPTC, COM comments
+    const DROPPED: &[u8] = &[0xE1, 0xED, 0xFE];
+
+    if jpeg.get(..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(jpeg.len());
+    out.extend_from_slice(&jpeg[..2]);
+    let mut at = 2;
+
+    loop {
+        if *jpeg.get(at)? != 0xFF {
+            return None;
+        }
+        let marker = *jpeg.get(at + 1)?;
+        match marker {
+            // Fill byte ahead of the marker
+            0xFF => at += 1,
+            // Start of scan: the compressed data runs to the end
+            0xDA => {
+                out.extend_from_slice(&jpeg[at..]);
+                return Some(out);
+            }
+            0x01 | 0xD0..=0xD7 => {
+                out.extend_from_slice(&jpeg[at..at + 2]);
+                at += 2;
+            }
+            _ => {
+                let length = u16::from_be_bytes(jpeg.get(at + 2..at + 4)?.try_into().ok()?);
+                let end = at + 2 + length as usize;
+                let segment = jpeg.get(at..end)?;
+                if !DROPPED.contains(&marker) {
+                    out.extend_from_slice(segment);
+                }
+                at = end;
+            }
+        }
+    }
+}
+
+fn strip_png(png: &[u8]) -> Option<Vec<u8>> {
+    const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+    const DROPPED: &[&[u8]] = &[b"eXIf", b"tEXt", b"zTXt", b"iTXt", b"tIME"];
+
+    if png.get(..8)? != SIGNATURE {
+        return None;
+    }
+
+    let mut out = Vec::with_capacity(png.len());
+    out.extend_from_slice(SIGNATURE);
+    let mut at = 8;
+
+    while at < png.len() {
+        let length = u32::from_be_bytes(png.get(at..at + 4)?.try_into().ok()?) as usize;
+        // Length, type, data, CRC
+        let end = at + 12 + length;
+        let chunk = png.get(at..end)?;
+        let kind = &chunk[4..8];
+        if !DROPPED.contains(&kind) {
+            out.extend_from_slice(chunk);
+        }
+        if kind == b"IEND" {
+            break;
+        }
+        at = end;
+    }
+
+    Some(out)
+}
+
+/// The CRC-32 PNG chunks end with
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
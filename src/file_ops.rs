@@ -0,0 +1,504 @@
+//! File operations on many images at once
+//!
+//! Deletes, copies, moves and rotations run in chunks on a blocking thread
+//! and report progress between them. Files moved to the trash can be put back
+//! from the system trash, rotated files from the copies kept of them.
+
+use crate::{edit::Transform, message::DeleteAction, nav::NavState};
+use cosmic::iced_futures::futures::Stream;
+use rayon::prelude::*;
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::task::spawn_blocking;
+
+/// Files handed to the trash per call; progress is reported between chunks
+const DELETE_CHUNK: usize = 32;
+/// Files copied or moved between progress reports
+const TRANSFER_CHUNK: usize = 8;
+/// Images decoded at once when rotating or flipping; progress is reported
+/// between chunks
+const TRANSFORM_CHUNK: usize = 8;
+
+/// Files waiting for the user to confirm deleting them
+#[derive(Debug, Clone)]
+pub struct DeleteRequest {
+    pub paths: Vec<PathBuf>,
+    pub total_size: u64,
+}
+
+impl DeleteRequest {
+    /// Sizes come from the scan where possible so the dialog opens without
+    /// touching the disk
+    pub fn new(paths: Vec<PathBuf>, nav: &NavState) -> Self {
+        let mut unlisted: HashSet<&PathBuf> = paths.iter().collect();
+        let mut total_size = 0;
+
+        for entry in nav.entries() {
+            if unlisted.remove(&entry.path) {
+                total_size += entry.size;
+            }
+        }
+
+        total_size += unlisted
+            .iter()
+            .filter_map(|path| std::fs::metadata(path).ok())
+            .map(|meta| meta.len())
+            .sum::<u64>();
+
+        Self { paths, total_size }
+    }
+}
+
+/// Progress of a running delete
+#[derive(Debug, Clone)]
+pub enum DeleteEvent {
+    Progress { done: usize, total: usize },
+    Done(DeleteReport),
+}
+
+/// What a finished delete did
+#[derive(Debug, Clone)]
+pub struct DeleteReport {
+    pub action: DeleteAction,
+    pub deleted: Vec<PathBuf>,
+    pub errors: Vec<String>,
+    /// When the delete started, in seconds since the epoch; trash entries
+    /// older than this weren't made by it
+    pub started: i64,
+}
+
+impl DeleteReport {
+    /// Whether the files can be put back with [`restore`]
+    pub fn can_undo(&self) -> bool {
+        self.action == DeleteAction::Trash && !self.deleted.is_empty()
+    }
+}
+
+/// Delete `paths` in chunks, yielding progress after each one
+pub fn delete(paths: Vec<PathBuf>, action: DeleteAction) -> impl Stream<Item = DeleteEvent> {
+    cosmic::iced::stream::channel(4, move |mut output| async move {
+        use cosmic::iced_futures::futures::SinkExt;
+
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_secs() as i64);
+        let total = paths.len();
+        let mut deleted = Vec::with_capacity(total);
+        let mut errors = Vec::new();
+
+        for chunk in paths.chunks(DELETE_CHUNK) {
+            let chunk = chunk.to_vec();
+            match spawn_blocking(move || delete_chunk(chunk, action)).await {
+                Ok((done, failed)) => {
+                    deleted.extend(done);
+                    errors.extend(failed);
+                }
+                Err(err) => errors.push(err.to_string()),
+            }
+
+            let done = deleted.len() + errors.len();
+            let _ = output.send(DeleteEvent::Progress { done, total }).await;
+        }
+
+        let _ = output
+            .send(DeleteEvent::Done(DeleteReport {
+                action,
+                deleted,
+                errors,
+                started,
+            }))
+            .await;
+    })
+}
+
+/// Returns the paths that were deleted and an error for each that wasn't
+fn delete_chunk(chunk: Vec<PathBuf>, action: DeleteAction) -> (Vec<PathBuf>, Vec<String>) {
+    // One call for the whole chunk; only go file by file to find out which failed
+    if action == DeleteAction::Trash && trash::delete_all(&chunk).is_ok() {
+        return (chunk, Vec::new());
+    }
+
+    let mut deleted = Vec::with_capacity(chunk.len());
+    let mut errors = Vec::new();
+
+    for path in chunk {
+        let result = match action {
+            DeleteAction::Trash => trash::delete(&path).map_err(|err| err.to_string()),
+            DeleteAction::Permanent => std::fs::remove_file(&path).map_err(|err| err.to_string()),
+        };
+
+        match result {
+            Ok(()) => deleted.push(path),
+            // Already gone, e.g. trashed by the failed batch call
+            Err(_) if !path.exists() => deleted.push(path),
+            Err(err) => errors.push(format!("{}: {err}", path.display())),
+        }
+    }
+
+    (deleted, errors)
+}
+
+/// Put the files of a trash delete back where they were, returning how many
+/// were restored
+pub async fn restore(report: DeleteReport) -> Result<usize, String> {
+    spawn_blocking(move || -> Result<usize, String> {
+        let trashed: HashSet<&PathBuf> = report.deleted.iter().collect();
+        let items: Vec<_> = trash::os_limited::list()
+            .map_err(|err| err.to_string())?
+            .into_iter()
+            .filter(|item| item.time_deleted >= report.started)
+            .filter(|item| trashed.contains(&item.original_path()))
+            .collect();
+
+        let count = items.len();
+        trash::os_limited::restore_all(items).map_err(|err| err.to_string())?;
+        Ok(count)
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferKind {
+    Copy,
+    Move,
+}
+
+/// What to do when the destination already has a file of the same name
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Collision {
+    Skip,
+    /// Keep both, numbering the new one: "photo (2).jpg"
+    Rename,
+    Overwrite,
+}
+
+/// Files waiting to be copied or moved into `dest`
+#[derive(Debug, Clone)]
+pub struct TransferRequest {
+    pub kind: TransferKind,
+    pub paths: Vec<PathBuf>,
+    pub dest: PathBuf,
+    /// How many of `paths` have a namesake in `dest` already
+    pub collisions: usize,
+}
+
+impl TransferRequest {
+    /// Files already in `dest` are left out; there is nothing to do for them.
+    /// `dest` is canonicalized so a symlink to the files' own folder counts
+    /// as that folder.
+    pub fn new(kind: TransferKind, paths: Vec<PathBuf>, dest: PathBuf) -> Self {
+        let dest = fs::canonicalize(&dest).unwrap_or(dest);
+        let paths: Vec<PathBuf> = paths
+            .into_iter()
+            .filter(|path| {
+                path.file_name()
+                    .is_none_or(|name| !same_file(path, &dest.join(name)))
+            })
+            .collect();
+        let collisions = paths
+            .iter()
+            .filter_map(|path| path.file_name())
+            .filter(|name| dest.join(name).exists())
+            .count();
+
+        Self {
+            kind,
+            paths,
+            dest,
+            collisions,
+        }
+    }
+}
+
+/// Progress of a running copy or move
+#[derive(Debug, Clone)]
+pub enum TransferEvent {
+    Progress { done: usize, total: usize },
+    Done(TransferReport),
+}
+
+/// What a finished copy or move did
+#[derive(Debug, Clone)]
+pub struct TransferReport {
+    pub kind: TransferKind,
+    pub dest: PathBuf,
+    /// Source and destination of every file copied or moved
+    pub transferred: Vec<(PathBuf, PathBuf)>,
+    pub skipped: usize,
+    pub errors: Vec<String>,
+}
+
+/// Copy or move the files of `request`, yielding progress as it goes
+pub fn transfer(
+    request: TransferRequest,
+    collision: Collision,
+) -> impl Stream<Item = TransferEvent> {
+    cosmic::iced::stream::channel(4, move |mut output| async move {
+        use cosmic::iced_futures::futures::SinkExt;
+
+        let TransferRequest {
+            kind, paths, dest, ..
+        } = request;
+        let total = paths.len();
+        let mut transferred = Vec::with_capacity(total);
+        let mut skipped = 0;
+        let mut errors = Vec::new();
+
+        for chunk in paths.chunks(TRANSFER_CHUNK) {
+            let chunk = chunk.to_vec();
+            let chunk_dest = dest.clone();
+            let results = spawn_blocking(move || {
+                chunk
+                    .into_iter()
+                    .map(|path| {
+                        let result = transfer_one(&path, &chunk_dest, kind, collision);
+                        (path, result)
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .await;
+
+            match results {
+                Ok(results) => {
+                    for (path, result) in results {
+                        match result {
+                            Ok(Some(target)) => transferred.push((path, target)),
+                            Ok(None) => skipped += 1,
+                            Err(err) => errors.push(format!("{}: {err}", path.display())),
+                        }
+                    }
+                }
+                Err(err) => errors.push(err.to_string()),
+            }
+
+            let done = transferred.len() + skipped + errors.len();
+            let _ = output.send(TransferEvent::Progress { done, total }).await;
+        }
+
+        let _ = output
+            .send(TransferEvent::Done(TransferReport {
+                kind,
+                dest,
+                transferred,
+                skipped,
+                errors,
+            }))
+            .await;
+    })
+}
+
+/// Copy or move one file, returning where it ended up, or `None` if it was
+/// skipped because of a name collision
+fn transfer_one(
+    path: &Path,
+    dest: &Path,
+    kind: TransferKind,
+    collision: Collision,
+) -> io::Result<Option<PathBuf>> {
+    let Some(name) = path.file_name() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "not a file"));
+    };
+
+    let mut target = dest.join(name);
+    // Overwriting would truncate the file being copied; it's where it should be
+    if same_file(path, &target) {
+        return Ok(None);
+    }
+    if target.exists() {
+        match collision {
+            Collision::Skip => return Ok(None),
+            Collision::Rename => target = free_name(&target),
+            Collision::Overwrite => {}
+        }
+    }
+
+    match kind {
+        TransferKind::Copy => {
+            fs::copy(path, &target)?;
+        }
+        TransferKind::Move => match fs::rename(path, &target) {
+            Ok(()) => {}
+            // Another filesystem: copy, then remove the original
+            Err(err) if err.kind() == io::ErrorKind::CrossesDevices => {
+                fs::copy(path, &target)?;
+                fs::remove_file(path)?;
+            }
+            Err(err) => return Err(err),
+        },
+    }
+
+    Ok(Some(target))
+}
+
+/// Whether `a` and `b` are the same file on disk, however they are reached
+fn same_file(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+/// `target` with " (2)", " (3)", ... added to the name until nothing has it
+fn free_name(target: &Path) -> PathBuf {
+    let stem = target
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let extension = target
+        .extension()
+        .map(|ext| format!(".{}", ext.to_string_lossy()))
+        .unwrap_or_default();
+
+    (2..)
+        .map(|n| target.with_file_name(format!("{stem} ({n}){extension}")))
+        .find(|candidate| !candidate.exists())
+        .expect("some numbered name is free")
+}
+
+/// Progress of a running rotate or flip
+#[derive(Debug, Clone)]
+pub enum TransformEvent {
+    Progress { done: usize, total: usize },
+    Done(TransformReport),
+}
+
+/// What a finished rotate or flip did
+#[derive(Debug, Clone)]
+pub struct TransformReport {
+    /// Every image saved, with the copy of it from before
+    pub saved: Vec<(PathBuf, PathBuf)>,
+    pub errors: Vec<String>,
+    /// Folder holding the copies, removed by [`restore_originals`] or
+    /// [`discard_backups`]
+    pub backup_dir: PathBuf,
+}
+
+/// Rotate or flip `paths` in place, keeping a copy of each original so
+/// [`restore_originals`] can take it back
+pub fn transform(paths: Vec<PathBuf>, transform: Transform) -> impl Stream<Item = TransformEvent> {
+    cosmic::iced::stream::channel(4, move |mut output| async move {
+        use cosmic::iced_futures::futures::SinkExt;
+
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |since| since.as_nanos());
+        // Under the cache dir rather than /tmp, which is often a small tmpfs
+        let backup_dir = dirs::cache_dir()
+            .unwrap_or_else(std::env::temp_dir)
+            .join("cupola/undo")
+            .join(format!("{}-{started}", std::process::id()));
+        let total = paths.len();
+        let mut saved = Vec::with_capacity(total);
+        let mut errors = Vec::new();
+
+        if let Err(err) = fs::create_dir_all(&backup_dir) {
+            errors.push(format!("{}: {err}", backup_dir.display()));
+        } else {
+            for (index, chunk) in paths.chunks(TRANSFORM_CHUNK).enumerate() {
+                let chunk = chunk.to_vec();
+                let chunk_dir = backup_dir.clone();
+                let first = index * TRANSFORM_CHUNK;
+                let results = spawn_blocking(move || {
+                    chunk
+                        .into_par_iter()
+                        .enumerate()
+                        .map(|(i, path)| {
+                            let result = transform_one(&path, &chunk_dir, first + i, transform);
+                            (path, result)
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .await;
+
+                match results {
+                    Ok(results) => {
+                        for (path, result) in results {
+                            match result {
+                                Ok(backup) => saved.push((path, backup)),
+                                Err(err) => errors.push(format!("{}: {err}", path.display())),
+                            }
+                        }
+                    }
+                    Err(err) => errors.push(err.to_string()),
+                }
+
+                let done = saved.len() + errors.len();
+                let _ = output.send(TransformEvent::Progress { done, total }).await;
+            }
+        }
+
+        let _ = output
+            .send(TransformEvent::Done(TransformReport {
+                saved,
+                errors,
+                backup_dir,
+            }))
+            .await;
+    })
+}
+
+/// Copy `path` into `backup_dir`, then rotate or flip it, returning the copy
+fn transform_one(
+    path: &Path,
+    backup_dir: &Path,
+    index: usize,
+    transform: Transform,
+) -> Result<PathBuf, String> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| "not a file".to_string())?
+        .to_string_lossy();
+    // Numbered so namesakes from different folders don't meet
+    let backup = backup_dir.join(format!("{index}-{name}"));
+    fs::copy(path, &backup).map_err(|err| err.to_string())?;
+
+    if let Err(err) = crate::edit::transform_in_place(path, transform) {
+        // The original is left untouched, so there's nothing to undo
+        let _ = fs::remove_file(&backup);
+        return Err(err.to_string());
+    }
+
+    Ok(backup)
+}
+
+/// Remove the copies kept of a rotate or flip once it can't be undone
+pub fn discard_backups(report: &TransformReport) {
+    if let Err(err) = fs::remove_dir_all(&report.backup_dir)
+        && err.kind() != io::ErrorKind::NotFound
+    {
+        tracing::warn!("Failed to remove {}: {err}", report.backup_dir.display());
+    }
+}
+
+/// Put the images of a rotate or flip back as they were, returning the
+/// paths that were restored
+pub async fn restore_originals(report: TransformReport) -> Result<Vec<PathBuf>, String> {
+    spawn_blocking(move || -> Result<Vec<PathBuf>, String> {
+        let mut restored = Vec::with_capacity(report.saved.len());
+        let mut errors = Vec::new();
+
+        for (path, backup) in report.saved {
+            match fs::copy(&backup, &path) {
+                Ok(_) => restored.push(path),
+                Err(err) => errors.push(format!("{}: {err}", path.display())),
+            }
+        }
+
+        if errors.is_empty() {
+            let _ = fs::remove_dir_all(&report.backup_dir);
+            Ok(restored)
+        } else {
+            // Keep the copies around so nothing is lost
+            Err(errors.join("; "))
+        }
+    })
+    .await
+    .map_err(|err| err.to_string())?
+}
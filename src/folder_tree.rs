@@ -0,0 +1,402 @@
+//! Folder tree shown in the navigation panel
+//!
+//! Roots are the home folder, pinned folders and mounted volumes. Subfolders
+//! are read lazily the first time a node is expanded. Each node keeps its
+//! own expanded state; the branch leading to the open directory is expanded
+//! once when the user moves to another folder.
+
+use crate::nav;
+use cosmic::widget::{icon, nav_bar};
+use rayon::prelude::*;
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+use tokio::task::spawn_blocking;
+
+/// Mount points below these prefixes are listed as volumes
+const VOLUME_PREFIXES: &[&str] = &["/media/", "/run/media/", "/mnt/"];
+
+/// Images kept per folder for the gallery's folder tiles
+const PREVIEW_COUNT: usize = 4;
+
+/// A folder as read by a background scan
+#[derive(Debug, Clone)]
+pub struct FolderInfo {
+    pub path: PathBuf,
+    /// Images directly inside the folder
+    pub image_count: usize,
+    /// The first few of those images in name order
+    pub previews: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+struct FolderNode {
+    path: PathBuf,
+    name: String,
+    icon: &'static str,
+    image_count: Option<usize>,
+    expanded: bool,
+    /// `None` until the subfolders have been read
+    children: Option<Vec<FolderNode>>,
+}
+
+impl FolderNode {
+    fn new(path: PathBuf, name: String, icon: &'static str) -> Self {
+        Self {
+            path,
+            name,
+            icon,
+            image_count: None,
+            expanded: false,
+            children: None,
+        }
+    }
+
+    fn find_mut(&mut self, path: &Path) -> Option<&mut FolderNode> {
+        if self.path == path {
+            return Some(self);
+        }
+        if !path.starts_with(&self.path) {
+            return None;
+        }
+        self.children
+            .as_mut()?
+            .iter_mut()
+            .find_map(|child| child.find_mut(path))
+    }
+}
+
+pub struct FolderTree {
+    roots: Vec<FolderNode>,
+    /// Directory shown in the gallery
+    current: Option<PathBuf>,
+    /// Directory whose branch is still being expanded
+    revealing: Option<PathBuf>,
+    /// Folders whose subfolders are being read
+    loading: HashSet<PathBuf>,
+    model: nav_bar::Model,
+}
+
+impl FolderTree {
+    pub fn new(pinned: &[String]) -> Self {
+        let mut tree = Self {
+            roots: Vec::new(),
+            current: None,
+            revealing: None,
+            loading: HashSet::new(),
+            model: nav_bar::Model::default(),
+        };
+        tree.set_pinned(pinned);
+        tree
+    }
+
+    pub fn model(&self) -> &nav_bar::Model {
+        &self.model
+    }
+
+    pub fn path(&self, id: nav_bar::Id) -> Option<PathBuf> {
+        self.model.data::<PathBuf>(id).cloned()
+    }
+
+    pub fn current(&self) -> Option<&PathBuf> {
+        self.current.as_ref()
+    }
+
+    pub fn root_paths(&self) -> Vec<PathBuf> {
+        self.roots.iter().map(|root| root.path.clone()).collect()
+    }
+
+    /// Rebuild the list of roots, keeping already loaded branches
+    pub fn set_pinned(&mut self, pinned: &[String]) {
+        let mut old_roots = std::mem::take(&mut self.roots);
+        let mut roots: Vec<FolderNode> = Vec::new();
+
+        let home = dirs::home_dir();
+        let candidates = home
+            .iter()
+            .map(|path| (path.clone(), "user-home-symbolic"))
+            .chain(
+                pinned
+                    .iter()
+                    .map(|path| (PathBuf::from(path), "folder-symbolic")),
+            )
+            .chain(
+                mounted_volumes()
+                    .into_iter()
+                    .map(|path| (path, "drive-removable-media-symbolic")),
+            );
+
+        for (path, icon) in candidates {
+            if roots.iter().any(|root| root.path == path) || !path.is_dir() {
+                continue;
+            }
+
+            let node = match old_roots.iter().position(|root| root.path == path) {
+                Some(idx) => old_roots.swap_remove(idx),
+                None => {
+                    let name = if Some(&path) == home.as_ref() {
+                        crate::fl!("folder-tree-home")
+                    } else {
+                        display_name(&path)
+                    };
+                    FolderNode::new(path, name, icon)
+                }
+            };
+            roots.push(node);
+        }
+
+        self.roots = roots;
+        self.rebuild();
+    }
+
+    /// Follow the directory shown in the gallery, expanding the branch that
+    /// leads to it when it changes. Returns a folder whose subfolders must be
+    /// read to go deeper.
+    pub fn set_current(&mut self, dir: Option<PathBuf>) -> Option<PathBuf> {
+        if self.current != dir {
+            self.revealing = dir.clone();
+        }
+        self.current = dir;
+        let to_load = self.reveal();
+        self.rebuild();
+        to_load
+    }
+
+    /// Expand or collapse a folder, or only expand it with `expand_only`.
+    /// Returns the folder if its subfolders must be read.
+    fn toggle(&mut self, path: &Path, expand_only: bool) -> Option<PathBuf> {
+        let node = self.roots.iter_mut().find_map(|root| root.find_mut(path))?;
+        node.expanded = !node.expanded || expand_only;
+
+        let to_load = (node.expanded && node.children.is_none()).then(|| node.path.clone());
+        self.rebuild();
+        to_load.filter(|path| self.loading.insert(path.clone()))
+    }
+
+    /// Make a folder picked in the tree the current one and expand it, or
+    /// collapse it when it already was current. The rest of the tree stays
+    /// as the user left it.
+    pub fn select(&mut self, path: &Path) -> Option<PathBuf> {
+        let was_current = self.current.as_deref() == Some(path);
+        self.current = Some(path.to_path_buf());
+        self.revealing = None;
+        self.toggle(path, !was_current)
+    }
+
+    /// Store the subfolders of `parent`, then keep expanding towards the
+    /// current directory if it was just opened
+    pub fn set_children(&mut self, parent: &Path, folders: Vec<FolderInfo>) -> Option<PathBuf> {
+        self.loading.remove(parent);
+
+        for root in &mut self.roots {
+            if let Some(node) = root.find_mut(parent) {
+                let children = folders
+                    .into_iter()
+                    .map(|info| {
+                        let mut child = FolderNode::new(
+                            info.path.clone(),
+                            display_name(&info.path),
+                            "folder-symbolic",
+                        );
+                        child.image_count = Some(info.image_count);
+                        child
+                    })
+                    .collect();
+                node.children = Some(children);
+                break;
+            }
+        }
+
+        let to_load = self.reveal();
+        self.rebuild();
+        to_load
+    }
+
+    pub fn set_counts(&mut self, counts: Vec<FolderInfo>) {
+        for info in counts {
+            if let Some(node) = self
+                .roots
+                .iter_mut()
+                .find_map(|root| root.find_mut(&info.path))
+            {
+                node.image_count = Some(info.image_count);
+            }
+        }
+        self.rebuild();
+    }
+
+    /// Expand the branch towards `revealing`, stopping at the first folder
+    /// whose subfolders are still unread
+    fn reveal(&mut self) -> Option<PathBuf> {
+        let target = self.revealing.take()?;
+
+        // The most specific root wins, so a volume under home expands on its own
+        let mut node = self
+            .roots
+            .iter_mut()
+            .filter(|root| target.starts_with(&root.path))
+            .max_by_key(|root| root.path.components().count())?;
+
+        loop {
+            node.expanded = true;
+
+            if node.children.is_none() {
+                let path = node.path.clone();
+                self.revealing = Some(target);
+                return self.loading.insert(path.clone()).then_some(path);
+            }
+            if node.path == target {
+                return None;
+            }
+
+            // Stops at hidden or otherwise unlisted folders
+            node = node
+                .children
+                .as_mut()?
+                .iter_mut()
+                .find(|child| target.starts_with(&child.path))?;
+        }
+    }
+
+    /// Recreate the nav bar entries from the expanded nodes
+    fn rebuild(&mut self) {
+        self.model.clear();
+
+        let mut stack: Vec<(&FolderNode, u16)> =
+            self.roots.iter().rev().map(|root| (root, 0)).collect();
+
+        while let Some((node, depth)) = stack.pop() {
+            let text = match node.image_count {
+                Some(count) if count > 0 => format!("{} ({count})", node.name),
+                _ => node.name.clone(),
+            };
+            let icon_name = if node.expanded && node.icon == "folder-symbolic" {
+                "folder-open-symbolic"
+            } else {
+                node.icon
+            };
+
+            let id = self
+                .model
+                .insert()
+                .text(text)
+                .icon(icon::from_name(icon_name).icon())
+                .indent(depth)
+                .data(node.path.clone())
+                .id();
+
+            if self.current.as_ref() == Some(&node.path) {
+                self.model.activate(id);
+            }
+
+            if node.expanded
+                && let Some(children) = &node.children
+            {
+                stack.extend(children.iter().rev().map(|child| (child, depth + 1)));
+            }
+        }
+    }
+}
+
+/// Read the subfolders of `dir` along with how many images each holds
+pub async fn load_children(dir: PathBuf, include_hidden: bool) -> Vec<FolderInfo> {
+    spawn_blocking(move || {
+        let mut folders: Vec<PathBuf> = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .filter(|entry| include_hidden || !entry.file_name().to_string_lossy().starts_with('.'))
+            .map(|entry| entry.path())
+            .collect();
+
+        folders.sort_by(|a, b| nav::human_sort(&display_name(a), &display_name(b)));
+        count_folders(folders, include_hidden)
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Count the images in each of `folders`
+pub async fn load_counts(folders: Vec<PathBuf>, include_hidden: bool) -> Vec<FolderInfo> {
+    spawn_blocking(move || count_folders(folders, include_hidden))
+        .await
+        .unwrap_or_default()
+}
+
+fn count_folders(folders: Vec<PathBuf>, include_hidden: bool) -> Vec<FolderInfo> {
+    folders
+        .into_par_iter()
+        .map(|path| read_folder(path, include_hidden))
+        .collect()
+}
+
+fn read_folder(path: PathBuf, include_hidden: bool) -> FolderInfo {
+    let mut images: Vec<PathBuf> = fs::read_dir(&path)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| include_hidden || !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .filter(|path| nav::is_supported_image(path))
+        .collect();
+
+    let image_count = images.len();
+    images.sort_by(|a, b| nav::human_sort(&display_name(a), &display_name(b)));
+    images.truncate(PREVIEW_COUNT);
+
+    FolderInfo {
+        path,
+        image_count,
+        previews: images,
+    }
+}
+
+pub fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+/// Removable and manually mounted volumes, read from `/proc/mounts`
+fn mounted_volumes() -> Vec<PathBuf> {
+    let Ok(mounts) = fs::read_to_string("/proc/mounts") else {
+        return Vec::new();
+    };
+
+    mounts
+        .lines()
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(unescape_mount_path)
+        .filter(|path| {
+            VOLUME_PREFIXES
+                .iter()
+                .any(|prefix| path.starts_with(prefix))
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// `/proc/mounts` escapes whitespace and backslashes as octal, e.g. `\040`
+fn unescape_mount_path(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+
+    while idx < bytes.len() {
+        if bytes[idx] == b'\\'
+            && let Some(octal) = field.get(idx + 1..idx + 4)
+            && let Ok(byte) = u8::from_str_radix(octal, 8)
+        {
+            out.push(byte);
+            idx += 4;
+        } else {
+            out.push(bytes[idx]);
+            idx += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&out).into_owned()
+}
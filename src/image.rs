@@ -1,8 +1,11 @@
 pub mod cache;
 pub mod loader;
+pub mod metadata;
+pub mod scheduler;
 
 pub use cache::{CachedImage, ImageCache};
 pub use loader::{LoadedImage, load_image, load_thumbnail};
+pub use scheduler::ThumbnailScheduler;
 
 pub fn register_format_hooks() {
     #[cfg(feature = "heif")]
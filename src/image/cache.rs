@@ -1,7 +1,7 @@
 use cosmic::widget::image::Handle;
 use lru::LruCache;
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     num::NonZeroUsize,
     path::PathBuf,
     sync::{Arc, Mutex},
@@ -20,6 +20,10 @@ pub struct ImageCache {
     thumbnails: Arc<Mutex<LruCache<PathBuf, Handle>>>,
     pending: Arc<Mutex<HashSet<PathBuf>>>,
     pending_thumbnails: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Thumbnails decoded for another size, shown scaled until replaced
+    stale_thumbnails: Arc<Mutex<HashSet<PathBuf>>>,
+    /// Why full images failed to load, kept until they are retried
+    failed: Arc<Mutex<HashMap<PathBuf, String>>>,
 }
 
 impl ImageCache {
@@ -33,6 +37,8 @@ impl ImageCache {
             ))),
             pending: Arc::new(Mutex::new(HashSet::new())),
             pending_thumbnails: Arc::new(Mutex::new(HashSet::new())),
+            stale_thumbnails: Arc::new(Mutex::new(HashSet::new())),
+            failed: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -75,6 +81,9 @@ impl ImageCache {
         if let Ok(mut cache) = self.thumbnails.lock() {
             cache.put(path.clone(), handle);
         }
+        if let Ok(mut set) = self.stale_thumbnails.lock() {
+            set.remove(&path);
+        }
         self.clear_pending_thumbnail(&path);
     }
 
@@ -82,6 +91,33 @@ impl ImageCache {
         if let Ok(mut cache) = self.thumbnails.lock() {
             cache.pop(path);
         }
+        if let Ok(mut set) = self.stale_thumbnails.lock() {
+            set.remove(path);
+        }
+    }
+
+    /// Keep showing the thumbnails there are, but have each decoded again,
+    /// e.g. at a new size
+    pub fn mark_thumbnails_stale(&self) {
+        if let (Ok(cache), Ok(mut stale)) = (self.thumbnails.lock(), self.stale_thumbnails.lock()) {
+            stale.extend(cache.iter().map(|(path, _)| path.clone()));
+        }
+        if let Ok(mut set) = self.pending_thumbnails.lock() {
+            set.clear();
+        }
+    }
+
+    /// Whether the thumbnail of `path` is missing or waits to be replaced
+    pub fn needs_thumbnail(&self, path: &PathBuf) -> bool {
+        let cached = self
+            .thumbnails
+            .lock()
+            .is_ok_and(|cache| cache.contains(path));
+        let stale = self
+            .stale_thumbnails
+            .lock()
+            .is_ok_and(|set| set.contains(path));
+        !cached || stale
     }
 
     pub fn is_thumbnail_pending(&self, path: &PathBuf) -> bool {
@@ -122,6 +158,30 @@ impl ImageCache {
         }
     }
 
+    pub fn failure(&self, path: &PathBuf) -> Option<String> {
+        self.failed.lock().ok()?.get(path).cloned()
+    }
+
+    pub fn set_failed(&self, path: PathBuf, error: String) {
+        if let Ok(mut failed) = self.failed.lock() {
+            failed.insert(path, error);
+        }
+    }
+
+    /// Every image that failed to load, taken under one lock
+    pub fn failed_paths(&self) -> HashSet<PathBuf> {
+        self.failed
+            .lock()
+            .map(|failed| failed.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    pub fn clear_failed(&self, path: &PathBuf) {
+        if let Ok(mut failed) = self.failed.lock() {
+            failed.remove(path);
+        }
+    }
+
     pub fn clear_thumbnails(&self) {
         if let Ok(mut cache) = self.thumbnails.lock() {
             cache.clear();
@@ -129,6 +189,9 @@ impl ImageCache {
         if let Ok(mut set) = self.pending_thumbnails.lock() {
             set.clear();
         }
+        if let Ok(mut set) = self.stale_thumbnails.lock() {
+            set.clear();
+        }
     }
 
     pub fn clear(&self) {
@@ -147,5 +210,13 @@ impl ImageCache {
         if let Ok(mut set) = self.pending_thumbnails.lock() {
             set.clear();
         }
+
+        if let Ok(mut set) = self.stale_thumbnails.lock() {
+            set.clear();
+        }
+
+        if let Ok(mut failed) = self.failed.lock() {
+            failed.clear();
+        }
     }
 }
@@ -579,7 +579,7 @@ fn decode_and_resize_image(path: &Path, max_size: u32) -> Result<(u32, u32, Vec<
 }
 
 /// Fast RGBA image resize using SIMD-optimized fast_image_resize crate
-fn fast_resize_rgba(
+pub fn fast_resize_rgba(
     pixels: &[u8],
     src_width: u32,
     src_height: u32,
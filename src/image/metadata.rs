@@ -0,0 +1,254 @@
+//! Lightweight per-file metadata reads used for sorting, renaming and the
+//! gallery's badges
+
+use chrono::NaiveDateTime;
+use std::{
+    fs::File,
+    io::{self, BufReader, Read},
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// EXIF Rating, as written by Windows and many photo managers
+const EXIF_RATING: exif::Tag = exif::Tag(exif::Context::Tiff, 0x4746);
+/// How far into a file to look for an XMP rating
+const XMP_SEARCH_LIMIT: u64 = 256 * 1024;
+
+/// Parse the EXIF of a file once, for the readers below that take it
+pub fn read_exif(path: &Path) -> Option<exif::Exif> {
+    let file = File::open(path).ok()?;
+    let mut reader = BufReader::new(file);
+    exif::Reader::new().read_from_container(&mut reader).ok()
+}
+
+/// Read the capture date from EXIF DateTimeOriginal, falling back to DateTime
+pub fn capture_datetime(path: &Path) -> Option<NaiveDateTime> {
+    exif_capture_datetime(&read_exif(path)?)
+}
+
+/// [`capture_datetime`] from already parsed EXIF
+pub fn exif_capture_datetime(exif: &exif::Exif) -> Option<NaiveDateTime> {
+    let field = exif
+        .get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)
+        .or_else(|| exif.get_field(exif::Tag::DateTime, exif::In::PRIMARY))?;
+
+    let exif::Value::Ascii(ref values) = field.value else {
+        return None;
+    };
+
+    let dt = exif::DateTime::from_ascii(values.first()?).ok()?;
+
+    chrono::NaiveDate::from_ymd_opt(dt.year.into(), dt.month.into(), dt.day.into())?.and_hms_opt(
+        dt.hour.into(),
+        dt.minute.into(),
+        dt.second.into(),
+    )
+}
+
+/// Camera model from EXIF, prefixed with the brand unless the model already
+/// names it ("NIKON CORPORATION" + "NIKON D750" gives "NIKON D750")
+pub fn camera(path: &Path) -> Option<String> {
+    let file = File::open(path).ok()?;
+    let mut reader = BufReader::new(file);
+    let exif = exif::Reader::new().read_from_container(&mut reader).ok()?;
+
+    let ascii = |tag| {
+        let field = exif.get_field(tag, exif::In::PRIMARY)?;
+        let exif::Value::Ascii(ref values) = field.value else {
+            return None;
+        };
+        let value = String::from_utf8_lossy(values.first()?)
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_string();
+        (!value.is_empty()).then_some(value)
+    };
+
+    let model = ascii(exif::Tag::Model)?;
+    let brand =
+        ascii(exif::Tag::Make).and_then(|make| make.split_whitespace().next().map(str::to_string));
+    match brand {
+        Some(brand) if !model.to_lowercase().starts_with(&brand.to_lowercase()) => {
+            Some(format!("{brand} {model}"))
+        }
+        _ => Some(model),
+    }
+}
+
+/// Capture date as a `SystemTime` so it can be compared with file mtimes.
+/// EXIF has no time zone, so the wall-clock value is treated as UTC.
+pub fn capture_time(path: &Path) -> Option<SystemTime> {
+    exif_capture_time(&read_exif(path)?)
+}
+
+/// [`capture_time`] from already parsed EXIF
+pub fn exif_capture_time(exif: &exif::Exif) -> Option<SystemTime> {
+    let secs = exif_capture_datetime(exif)?.and_utc().timestamp();
+    u64::try_from(secs)
+        .ok()
+        .map(|secs| UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Image dimensions read from the file header without decoding pixels
+pub fn dimensions(path: &Path) -> Option<(u32, u32)> {
+    image::image_dimensions(path).ok()
+}
+
+/// Star rating from 1 to 5, from the already parsed EXIF or else the
+/// embedded XMP packet. Unrated and rejected (-1) images have none.
+pub fn rating(path: &Path, exif: Option<&exif::Exif>) -> Option<u8> {
+    exif.and_then(exif_rating)
+        .or_else(|| xmp_rating(path))
+        .filter(|rating| (1..=5).contains(rating))
+}
+
+fn exif_rating(exif: &exif::Exif) -> Option<u8> {
+    let rating = exif
+        .get_field(EXIF_RATING, exif::In::PRIMARY)?
+        .value
+        .get_uint(0)?;
+    u8::try_from(rating).ok()
+}
+
+/// `xmp:Rating="4"` or `<xmp:Rating>4</xmp:Rating>`
+fn xmp_rating(path: &Path) -> Option<u8> {
+    let mut head = Vec::new();
+    File::open(path)
+        .ok()?
+        .take(XMP_SEARCH_LIMIT)
+        .read_to_end(&mut head)
+        .ok()?;
+
+    let key = b"xmp:Rating";
+    let start = head.windows(key.len()).position(|window| window == key)? + key.len();
+    let value = head[start..]
+        .iter()
+        .skip_while(|byte| matches!(byte, b'=' | b'"' | b'\'' | b'>' | b' '))
+        .take_while(|byte| byte.is_ascii_digit() || **byte == b'-')
+        .map(|&byte| byte as char)
+        .collect::<String>();
+
+    value
+        .parse::<i8>()
+        .ok()
+        .and_then(|rating| u8::try_from(rating).ok())
+}
+
+/// Whether a GIF, PNG or WebP has more than one frame, read from its headers
+pub fn is_animated(path: &Path) -> bool {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+    let Ok(file) = File::open(path) else {
+        return false;
+    };
+    let mut reader = BufReader::new(file);
+
+    let animated = match extension.as_str() {
+        "gif" => gif_is_animated(&mut reader),
+        "png" => png_is_animated(&mut reader),
+        "webp" => webp_is_animated(&mut reader),
+        _ => Ok(false),
+    };
+    animated.unwrap_or(false)
+}
+
+/// Counts image descriptors until the second one
+fn gif_is_animated(reader: &mut impl Read) -> io::Result<bool> {
+    let mut header = [0u8; 13];
+    reader.read_exact(&mut header)?;
+    if &header[..3] != b"GIF" {
+        return Ok(false);
+    }
+    if header[10] & 0x80 != 0 {
+        skip(reader, color_table_len(header[10]))?;
+    }
+
+    let mut frames = 0;
+    loop {
+        let mut block = [0u8; 1];
+        reader.read_exact(&mut block)?;
+        match block[0] {
+            // Image descriptor
+            0x2C => {
+                frames += 1;
+                if frames > 1 {
+                    return Ok(true);
+                }
+                let mut descriptor = [0u8; 9];
+                reader.read_exact(&mut descriptor)?;
+                if descriptor[8] & 0x80 != 0 {
+                    skip(reader, color_table_len(descriptor[8]))?;
+                }
+                // LZW code size, then the image data
+                skip(reader, 1)?;
+                skip_sub_blocks(reader)?;
+            }
+            // Extension
+            0x21 => {
+                skip(reader, 1)?;
+                skip_sub_blocks(reader)?;
+            }
+            // Trailer
+            _ => return Ok(false),
+        }
+    }
+}
+
+/// Bytes of a GIF color table whose size is packed into `flags`
+fn color_table_len(flags: u8) -> u64 {
+    3 * (2 << (flags & 0x07))
+}
+
+fn skip_sub_blocks(reader: &mut impl Read) -> io::Result<()> {
+    loop {
+        let mut len = [0u8; 1];
+        reader.read_exact(&mut len)?;
+        if len[0] == 0 {
+            return Ok(());
+        }
+        skip(reader, u64::from(len[0]))?;
+    }
+}
+
+/// An APNG has an acTL chunk before its image data
+fn png_is_animated(reader: &mut impl Read) -> io::Result<bool> {
+    let mut signature = [0u8; 8];
+    reader.read_exact(&mut signature)?;
+    if &signature != b"\x89PNG\r\n\x1a\n" {
+        return Ok(false);
+    }
+
+    loop {
+        let mut chunk = [0u8; 8];
+        reader.read_exact(&mut chunk)?;
+        match &chunk[4..] {
+            b"acTL" => return Ok(true),
+            b"IDAT" | b"IEND" => return Ok(false),
+            _ => {
+                let len = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+                // Data and CRC
+                skip(reader, u64::from(len) + 4)?;
+            }
+        }
+    }
+}
+
+/// Extended WebP files flag animation in their VP8X chunk
+fn webp_is_animated(reader: &mut impl Read) -> io::Result<bool> {
+    let mut header = [0u8; 21];
+    reader.read_exact(&mut header)?;
+    Ok(&header[..4] == b"RIFF"
+        && &header[8..12] == b"WEBP"
+        && &header[12..16] == b"VP8X"
+        && header[20] & 0x02 != 0)
+}
+
+fn skip(reader: &mut impl Read, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(len), &mut io::sink())?;
+    if skipped < len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(())
+}
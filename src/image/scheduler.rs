@@ -0,0 +1,94 @@
+//! Thumbnail decode scheduling
+//!
+//! Only a handful of decodes run at once. Each time one finishes or the
+//! gallery scrolls, the next ones are picked from the tiles nearest the
+//! viewport, and decodes for tiles that scrolled far away are dropped.
+
+use super::ImageCache;
+use cosmic::iced::task::Handle;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+};
+
+/// Decodes running at the same time
+const MAX_IN_FLIGHT: usize = 16;
+
+#[derive(Default)]
+pub struct ThumbnailScheduler {
+    in_flight: HashMap<PathBuf, Handle>,
+    /// Thumbnails that failed to decode and why, so they aren't retried in a
+    /// loop
+    failed: HashMap<PathBuf, String>,
+}
+
+impl ThumbnailScheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paths from `wanted`, most urgent first, to start decoding now
+    pub fn next(&self, wanted: &[PathBuf], cache: &ImageCache) -> Vec<PathBuf> {
+        let free = MAX_IN_FLIGHT.saturating_sub(self.in_flight.len());
+
+        wanted
+            .iter()
+            .filter(|path| !self.failed.contains_key(*path))
+            .filter(|path| cache.needs_thumbnail(path) && !cache.is_thumbnail_pending(path))
+            .take(free)
+            .cloned()
+            .collect()
+    }
+
+    pub fn started(&mut self, path: PathBuf, handle: Handle) {
+        self.in_flight.insert(path, handle);
+    }
+
+    /// Free the slot of a decode that finished, with the error if it failed;
+    /// returns false for paths this scheduler didn't start
+    pub fn finished(&mut self, path: &PathBuf, error: Option<String>) -> bool {
+        if let Some(error) = error {
+            self.failed.insert(path.clone(), error);
+        }
+        self.in_flight.remove(path).is_some()
+    }
+
+    /// Abort decodes for tiles that are no longer wanted, returning their paths.
+    /// A decode already running on a worker thread still finishes, but its
+    /// result is dropped and the slot goes to a visible tile.
+    pub fn cancel_unwanted(&mut self, wanted: &[PathBuf]) -> Vec<PathBuf> {
+        let wanted: HashSet<&PathBuf> = wanted.iter().collect();
+        let unwanted: Vec<PathBuf> = self
+            .in_flight
+            .keys()
+            .filter(|path| !wanted.contains(path))
+            .cloned()
+            .collect();
+
+        for path in &unwanted {
+            if let Some(handle) = self.in_flight.remove(path) {
+                handle.abort();
+            }
+        }
+
+        unwanted
+    }
+
+    /// Why the thumbnail of `path` failed to decode
+    pub fn failure(&self, path: &PathBuf) -> Option<&str> {
+        self.failed.get(path).map(String::as_str)
+    }
+
+    /// Give a thumbnail that failed before another try, e.g. after an edit
+    pub fn retry(&mut self, path: &PathBuf) {
+        self.failed.remove(path);
+    }
+
+    /// Forget everything, e.g. when the thumbnail size changes
+    pub fn reset(&mut self) {
+        for (_, handle) in self.in_flight.drain() {
+            handle.abort();
+        }
+        self.failed.clear();
+    }
+}
@@ -1,5 +1,8 @@
-use crate::config::{SortMode, SortOrder};
-use crate::message::{ContextPage, EditMessage, Message, NavMessage, SettingsMessage, ViewMessage};
+use crate::config::{QUICK_SORT_SLOTS, SortMode, SortOrder};
+use crate::message::{
+    ContextPage, EditMessage, Message, NavMessage, RenameMessage, SettingsMessage, TransferKind,
+    ViewMessage,
+};
 use cosmic::{
     iced::keyboard::{Key, key::Named},
     widget::menu::{
@@ -45,10 +48,23 @@ pub enum MenuAction {
     SortByName,
     SortByDate,
     SortBySize,
+    SortByDateTaken,
+    SortByDimensions,
+    SortByType,
+    SortRandom,
     SortAscending,
     SortDescending,
     OpenRecentFolder(usize),
     ClearRecentFolders,
+    Filter,
+    PinFolder,
+    GoUp,
+    SelectAll,
+    SelectNone,
+    CopyTo,
+    MoveTo,
+    QuickSort(usize),
+    Rename,
 }
 
 impl MenuAction {
@@ -87,6 +103,16 @@ impl MenuAction {
             MenuAction::SortByName => Message::Settings(SettingsMessage::SortMode(SortMode::Name)),
             MenuAction::SortByDate => Message::Settings(SettingsMessage::SortMode(SortMode::Date)),
             MenuAction::SortBySize => Message::Settings(SettingsMessage::SortMode(SortMode::Size)),
+            MenuAction::SortByDateTaken => {
+                Message::Settings(SettingsMessage::SortMode(SortMode::DateTaken))
+            }
+            MenuAction::SortByDimensions => {
+                Message::Settings(SettingsMessage::SortMode(SortMode::Dimensions))
+            }
+            MenuAction::SortByType => Message::Settings(SettingsMessage::SortMode(SortMode::Type)),
+            MenuAction::SortRandom => {
+                Message::Settings(SettingsMessage::SortMode(SortMode::Random))
+            }
             MenuAction::SortAscending => {
                 Message::Settings(SettingsMessage::SortOrder(SortOrder::Ascending))
             }
@@ -95,6 +121,15 @@ impl MenuAction {
             }
             MenuAction::OpenRecentFolder(idx) => Message::OpenRecentFolder(idx),
             MenuAction::ClearRecentFolders => Message::ClearRecentFolders,
+            MenuAction::Filter => Message::View(ViewMessage::ShowFilter),
+            MenuAction::PinFolder => Message::TogglePinFolder,
+            MenuAction::GoUp => Message::Nav(NavMessage::GoUp),
+            MenuAction::SelectAll => Message::View(ViewMessage::SelectAll),
+            MenuAction::SelectNone => Message::View(ViewMessage::ClearSelection),
+            MenuAction::CopyTo => Message::ShowTransferDialog(TransferKind::Copy),
+            MenuAction::MoveTo => Message::ShowTransferDialog(TransferKind::Move),
+            MenuAction::QuickSort(slot) => Message::QuickSort(slot),
+            MenuAction::Rename => Message::Rename(RenameMessage::Start),
         }
     }
 }
@@ -256,6 +291,85 @@ pub fn init_key_binds() -> HashMap<KeyBind, MenuAction> {
         MenuAction::ToggleSlideshow,
     );
 
+    binds.insert(
+        KeyBind {
+            modifiers: vec![],
+            key: Key::Named(Named::Backspace),
+        },
+        MenuAction::GoUp,
+    );
+
+    // Filter
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl],
+            key: Key::Character("l".into()),
+        },
+        MenuAction::Filter,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![],
+            key: Key::Character("/".into()),
+        },
+        MenuAction::Filter,
+    );
+
+    // Selection
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl],
+            key: Key::Character("a".into()),
+        },
+        MenuAction::SelectAll,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl, Modifier::Shift],
+            key: Key::Character("a".into()),
+        },
+        MenuAction::SelectNone,
+    );
+
+    // Rename
+    binds.insert(
+        KeyBind {
+            modifiers: vec![],
+            key: Key::Named(Named::F2),
+        },
+        MenuAction::Rename,
+    );
+
+    // Copy and move
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl, Modifier::Shift],
+            key: Key::Character("c".into()),
+        },
+        MenuAction::CopyTo,
+    );
+
+    binds.insert(
+        KeyBind {
+            modifiers: vec![Modifier::Ctrl, Modifier::Shift],
+            key: Key::Character("m".into()),
+        },
+        MenuAction::MoveTo,
+    );
+
+    // Quick sort: Alt+1 to Alt+9
+    for slot in 0..QUICK_SORT_SLOTS {
+        binds.insert(
+            KeyBind {
+                modifiers: vec![Modifier::Alt],
+                key: Key::Character((slot + 1).to_string().into()),
+            },
+            MenuAction::QuickSort(slot),
+        );
+    }
+
     // Info
     binds.insert(
         KeyBind {
@@ -1,18 +1,25 @@
 pub mod app;
+pub mod batch;
+pub mod cli;
 pub mod config;
+pub mod dbus;
 pub mod edit;
+pub mod file_ops;
+pub mod folder_tree;
+pub mod format;
 pub mod image;
 pub mod key_binds;
 pub mod localize;
 pub mod menu;
 pub mod message;
 pub mod nav;
+pub mod rename;
 pub mod views;
 pub mod watcher;
 pub mod widgets;
 
 use app::ImageViewer;
-use std::path::PathBuf;
+use cli::Command;
 
 fn main() -> cosmic::iced::Result {
     /*
@@ -29,7 +36,55 @@ fn main() -> cosmic::iced::Result {
             .min_height(300.0),
     );
 
-    let optional_image = std::env::args().nth(1).map(PathBuf::from);
+    let mut args = match cli::parse(std::env::args().skip(1)) {
+        Ok(Command::Run(args)) => args,
+        Ok(Command::Help) => {
+            print!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(Command::Batch(job)) => std::process::exit(batch::run(job)),
+        Ok(Command::BatchHelp) => {
+            print!("{}", batch::USAGE);
+            return Ok(());
+        }
+        Ok(Command::Version) => {
+            println!("cupola {}", env!("CARGO_PKG_VERSION"));
+            return Ok(());
+        }
+        Err(why) => {
+            eprintln!("cupola: {why}\n\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
 
-    cosmic::app::run::<ImageViewer>(settings, optional_image)
+    // File managers pass every selected item; more than one opens a collection
+    args.paths = args
+        .paths
+        .into_iter()
+        .map(|path| std::path::absolute(&path).unwrap_or(path))
+        .collect();
+    args.start_at = args
+        .start_at
+        .map(|path| std::path::absolute(&path).unwrap_or(path));
+
+    // Claim the bus name before opening a window, so of two launches at once
+    // only one becomes the instance. The runtime keeps answering activations
+    // for as long as the app runs.
+    let runtime = tokio::runtime::Runtime::new();
+    match &runtime {
+        Ok(runtime) if !args.new_instance => match runtime.block_on(dbus::claim()) {
+            Ok(dbus::Claim::Owned(bus)) => args.bus = Some(bus),
+            // Open in the running window so everything shares one cache
+            Ok(dbus::Claim::Taken) => {
+                if args.forwardable() && runtime.block_on(dbus::forward(&args.paths)) {
+                    return Ok(());
+                }
+            }
+            Err(why) => tracing::warn!("Couldn't register on the session bus: {why}"),
+        },
+        Ok(_) => {}
+        Err(why) => tracing::warn!("Couldn't start the D-Bus runtime: {why}"),
+    }
+
+    cosmic::app::run::<ImageViewer>(settings, args)
 }
@@ -11,10 +11,22 @@ use std::{collections::HashMap, path::Path, sync::LazyLock};
 static MENU_ID: LazyLock<cosmic::widget::Id> =
     LazyLock::new(|| cosmic::widget::Id::new("responsive-menu"));
 
-fn build_file_menu(recent_folders: &[String]) -> Vec<menu::Item<MenuAction, String>> {
+fn build_file_menu(
+    recent_folders: &[String],
+    is_pinned: bool,
+) -> Vec<menu::Item<MenuAction, String>> {
     let mut items = vec![
         menu::Item::button(fl!("menu-open"), None, MenuAction::Open),
         menu::Item::button(fl!("menu-open-folder"), None, MenuAction::OpenFolder),
+        menu::Item::button(
+            if is_pinned {
+                fl!("menu-unpin-folder")
+            } else {
+                fl!("menu-pin-folder")
+            },
+            None,
+            MenuAction::PinFolder,
+        ),
     ];
 
     if !recent_folders.is_empty() {
@@ -50,6 +62,22 @@ fn build_file_menu(recent_folders: &[String]) -> Vec<menu::Item<MenuAction, Stri
         MenuAction::SaveAs,
     ));
     items.push(menu::Item::divider());
+    items.push(menu::Item::button(
+        fl!("menu-rename"),
+        None,
+        MenuAction::Rename,
+    ));
+    items.push(menu::Item::button(
+        fl!("menu-copy-to"),
+        None,
+        MenuAction::CopyTo,
+    ));
+    items.push(menu::Item::button(
+        fl!("menu-move-to"),
+        None,
+        MenuAction::MoveTo,
+    ));
+    items.push(menu::Item::divider());
     items.push(menu::Item::button(
         fl!("menu-set-wallpaper"),
         None,
@@ -76,9 +104,10 @@ pub fn menu_bar<'a>(
     core: &Core,
     key_binds: &HashMap<KeyBind, MenuAction>,
     is_slideshow_active: bool,
+    is_pinned: bool,
     recent_folders: &[String],
 ) -> Element<'a, Message> {
-    let file_menu = build_file_menu(recent_folders);
+    let file_menu = build_file_menu(recent_folders, is_pinned);
 
     responsive_menu_bar()
         .item_height(ItemHeight::Dynamic(40))
@@ -111,6 +140,9 @@ pub fn menu_bar<'a>(
                         menu::Item::button(fl!("menu-crop"), None, MenuAction::StartCrop),
                         menu::Item::divider(),
                         menu::Item::button(fl!("menu-undo"), None, MenuAction::Undo),
+                        menu::Item::divider(),
+                        menu::Item::button(fl!("menu-select-all"), None, MenuAction::SelectAll),
+                        menu::Item::button(fl!("menu-select-none"), None, MenuAction::SelectNone),
                     ],
                 ),
                 (
@@ -132,9 +164,23 @@ pub fn menu_bar<'a>(
                             MenuAction::ToggleSlideshow,
                         ),
                         menu::Item::divider(),
+                        menu::Item::button(fl!("menu-filter"), None, MenuAction::Filter),
+                        menu::Item::divider(),
                         menu::Item::button(fl!("menu-sort-name"), None, MenuAction::SortByName),
                         menu::Item::button(fl!("menu-sort-date"), None, MenuAction::SortByDate),
                         menu::Item::button(fl!("menu-sort-size"), None, MenuAction::SortBySize),
+                        menu::Item::button(
+                            fl!("menu-sort-date-taken"),
+                            None,
+                            MenuAction::SortByDateTaken,
+                        ),
+                        menu::Item::button(
+                            fl!("menu-sort-dimensions"),
+                            None,
+                            MenuAction::SortByDimensions,
+                        ),
+                        menu::Item::button(fl!("menu-sort-type"), None, MenuAction::SortByType),
+                        menu::Item::button(fl!("menu-sort-random"), None, MenuAction::SortRandom),
                         menu::Item::divider(),
                         menu::Item::button(
                             fl!("menu-sort-ascending"),
@@ -156,6 +202,8 @@ pub fn menu_bar<'a>(
                         menu::Item::divider(),
                         menu::Item::button(fl!("menu-first"), None, MenuAction::First),
                         menu::Item::button(fl!("menu-last"), None, MenuAction::Last),
+                        menu::Item::divider(),
+                        menu::Item::button(fl!("menu-go-up"), None, MenuAction::GoUp),
                     ],
                 ),
                 (
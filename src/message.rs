@@ -1,7 +1,14 @@
 use cosmic::widget::image::Handle;
 use std::{path::PathBuf, sync::Arc};
 
-pub use crate::{key_binds::MenuAction, widgets::DragHandle};
+pub use crate::{
+    file_ops::{Collision, TransferKind, TransferReport, TransformReport},
+    folder_tree::FolderInfo,
+    key_binds::MenuAction,
+    nav::{ImageDetails, ImageEntry},
+    rename::{FileInfo, RenameReport},
+    widgets::{DragHandle, SelectionChange, ZoomRequest},
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum WallpaperTarget {
@@ -35,10 +42,20 @@ pub enum Message {
     OpenFolderDialog,
     OpenRecentFolder(usize),
     ClearRecentFolders,
+    TogglePinFolder,
+    FolderTree(FolderTreeMessage),
+    Breadcrumb(BreadcrumbMessage),
+    Rename(RenameMessage),
     Cancelled,
     OpenError(Arc<String>),
+    CloseToast(cosmic::widget::ToastId),
     FilesSelected(Vec<PathBuf>),
     OpenPath(PathBuf),
+    /// Let the desktop pick another application for the file
+    OpenWith(PathBuf),
+    /// Another launch handed its paths over, with the activation token it
+    /// was started with; no paths just raises the window
+    Activate(Vec<PathBuf>, Option<String>),
     SystemThemeChanged,
     ConfigChanged,
     WatcherEvent(crate::watcher::WatcherEvent),
@@ -51,13 +68,95 @@ pub enum Message {
     WallpaperResult(Result<(), String>),
     DeleteImage,
     ShowDeleteDialog(std::path::PathBuf),
-    ConfirmDelete(std::path::PathBuf, DeleteAction),
+    ConfirmDelete(Vec<PathBuf>, DeleteAction),
     CloseDeleteDialog,
-    DeleteResult(Result<(), String>),
+    DeleteProgress {
+        done: usize,
+        total: usize,
+    },
+    DeleteFinished(crate::file_ops::DeleteReport),
+    /// Put the files of the last trash delete back, closing the toast that
+    /// offered it
+    UndoTrash(Option<cosmic::widget::ToastId>),
+    TrashRestored(Result<usize, String>),
+    /// Ask for a folder to copy or move the current image or selection to
+    ShowTransferDialog(TransferKind),
+    TransferTo(TransferKind, PathBuf),
+    /// Move to the quick-sort destination in this slot
+    QuickSort(usize),
+    /// Ask for the folder of a quick-sort slot
+    PickQuickSortFolder(usize),
+    ConfirmTransfer(Collision),
+    CloseTransferDialog,
+    TransferProgress {
+        done: usize,
+        total: usize,
+    },
+    TransferFinished(TransferReport),
+    /// Rotate or flip the images waiting in the confirmation dialog
+    ConfirmTransform,
+    CloseTransformDialog,
+    TransformProgress {
+        done: usize,
+        total: usize,
+    },
+    TransformFinished(TransformReport),
+    /// Put back the images of the last rotate or flip, closing the toast that
+    /// offered it
+    UndoTransform(Option<cosmic::widget::ToastId>),
+    TransformUndone(Result<Vec<PathBuf>, String>),
     Quit,
     Surface(cosmic::surface::Action),
 }
 
+#[derive(Debug, Clone)]
+pub enum FolderTreeMessage {
+    ChildrenLoaded {
+        parent: PathBuf,
+        folders: Vec<FolderInfo>,
+    },
+    CountsLoaded(Vec<FolderInfo>),
+}
+
+#[derive(Debug, Clone)]
+pub enum BreadcrumbMessage {
+    SiblingsLoaded {
+        dir: PathBuf,
+        siblings: Vec<Vec<PathBuf>>,
+    },
+    /// Segment index, then sibling index
+    SelectSibling(usize, usize),
+    /// Open or close the menu of siblings of a segment
+    ToggleSiblings(usize),
+    CloseSiblings,
+    StartEdit,
+    Input(String),
+    CompletionsLoaded {
+        input: String,
+        completions: Vec<PathBuf>,
+    },
+    Complete(PathBuf),
+    Submit,
+    CancelEdit,
+}
+
+#[derive(Debug, Clone)]
+pub enum RenameMessage {
+    /// Rename the target image inline, or the selection with a pattern
+    Start,
+    Input(String),
+    InfoLoaded {
+        paths: Vec<PathBuf>,
+        info: Vec<FileInfo>,
+    },
+    Submit,
+    Cancel,
+    Finished(RenameReport),
+    /// Take the last rename back, from its toast or Ctrl+Z
+    Undo(Option<cosmic::widget::ToastId>),
+    Undone(RenameReport),
+}
+
 #[derive(Debug, Clone)]
 pub enum ImageMessage {
     Loading(PathBuf),
@@ -75,6 +174,12 @@ pub enum ImageMessage {
         path: PathBuf,
         handle: Handle,
     },
+    ThumbnailFailed {
+        path: PathBuf,
+        error: String,
+    },
+    /// Load an image that failed before again
+    Retry(PathBuf),
     Clear,
 }
 
@@ -85,15 +190,35 @@ pub enum NavMessage {
     First,
     Last,
     GoTo(usize),
+    DirectoryBatch {
+        scan_id: u64,
+        images: Vec<ImageEntry>,
+        target: PathBuf,
+    },
     DirectoryScanned {
-        images: Vec<PathBuf>,
+        scan_id: u64,
+        images: Vec<ImageEntry>,
         target: PathBuf,
     },
     DirectoryRefreshed {
-        images: Vec<PathBuf>,
+        images: Vec<ImageEntry>,
+    },
+    CollectionScanned {
+        scan_id: u64,
+        images: Vec<ImageEntry>,
+        /// First file named on the command line, opened like a single file would be
+        open: Option<PathBuf>,
+    },
+    FoldersScanned {
+        dir: PathBuf,
+        folders: Vec<FolderInfo>,
     },
+    FolderFocus(usize),
+    GoUp,
     GalleryFocus(usize),
     GallerySelect(usize),
+    FilterChanged(String),
+    ClearFilter,
 }
 
 #[derive(Debug, Clone)]
@@ -112,6 +237,23 @@ pub enum ViewMessage {
     ToggleSlideshow,
     ImageEditEvent,
     GalleryScrollTo(f32),
+    /// Ctrl+wheel over the gallery
+    GalleryZoom(ZoomRequest),
+    /// Write the thumbnail size a gallery zoom left, once the wheel rests
+    SaveGalleryZoom,
+    /// The gallery scrolled or was resized
+    GalleryScrolled(cosmic::iced::widget::scrollable::Viewport),
+    ShowFilter,
+    /// Ctrl/Shift click, Shift+arrows or a rubber band in the gallery
+    Select(SelectionChange),
+    SelectAll,
+    ClearSelection,
+    /// Dimensions and capture dates read for rows of the details list
+    DetailsLoaded(Vec<(PathBuf, ImageDetails)>),
+    /// Header of a timeline section clicked, by section
+    ToggleGroup(usize),
+    /// "Select all" of a timeline section
+    SelectGroup(usize),
 }
 
 #[derive(Debug, Clone)]
@@ -121,6 +263,9 @@ pub enum SettingsMessage {
     FitToWindow(bool),
     SmoothScaling(bool),
     ThumbnailSize(crate::config::ThumbnailSize),
+    GalleryLayout(crate::config::GalleryLayout),
+    DateGrouping(crate::config::DateGrouping),
+    ThumbnailBadge(crate::config::Badge, bool),
     ShowHiddenFiles(bool),
     SlideshowInterval(u32),
     CacheSize(usize),
@@ -128,6 +273,10 @@ pub enum SettingsMessage {
     WallpaperBehavior(crate::config::WallpaperBehavior),
     SortMode(crate::config::SortMode),
     SortOrder(crate::config::SortOrder),
+    IncludeSubfolders(bool),
+    SubfolderDepth(u32),
+    GroupByFolder(bool),
+    QuickSortFolder(usize, Option<String>),
 }
 
 #[derive(Debug, Clone)]
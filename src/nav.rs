@@ -1,20 +1,296 @@
-use crate::config::{SortMode, SortOrder};
+use crate::{
+    config::{DateGrouping, GalleryLayout, SortMode, SortOrder, ViewerConfig},
+    folder_tree::FolderInfo,
+    image::metadata,
+};
+use rayon::prelude::*;
+use regex::{Regex, RegexBuilder};
 use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
     fs,
+    hash::{DefaultHasher, Hash, Hasher},
     path::{Path, PathBuf},
+    sync::LazyLock,
+    time::SystemTime,
 };
 
-use tokio::task::spawn_blocking;
+use cosmic::iced_futures::futures::Stream;
+use tokio::{sync::mpsc, task::spawn_blocking};
+use walkdir::WalkDir;
+
+/// Seed for `SortMode::Random`, fixed for the lifetime of the process so
+/// re-sorting or refreshing keeps the same shuffle
+static SESSION_SEED: LazyLock<u64> = LazyLock::new(|| {
+    let nanos = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|dur| dur.as_nanos() as u64)
+        .unwrap_or_default();
+    nanos ^ u64::from(std::process::id())
+});
 
 pub const EXTENSIONS: &[&str] = &[
     "png", "jpg", "jpeg", "gif", "webp", "bmp", "tiff", "tif", "ico", "avif", "raw", "cr2", "cr3",
     "nef", "arw", "dng", "orf", "rw2",
 ];
 
+/// Camera raw formats among [`EXTENSIONS`]
+pub const RAW_EXTENSIONS: &[&str] = &["raw", "cr2", "cr3", "nef", "arw", "dng", "orf", "rw2"];
+
+/// Filename filter applied on top of the scanned image list
+#[derive(Debug, Clone)]
+pub enum NavFilter {
+    /// Case-insensitive substring match
+    Substring(String),
+    /// Shell-style glob (`*`, `?`, `[...]`) matched against the whole file name
+    Glob(Regex),
+    /// Regular expression, entered as `re:<expr>` or `/<expr>/`
+    Regex(Regex),
+}
+
+impl NavFilter {
+    /// Parse a filter query. Returns `Ok(None)` for an empty query.
+    pub fn parse(query: &str) -> Result<Option<Self>, regex::Error> {
+        let query = query.trim();
+        if query.is_empty() {
+            return Ok(None);
+        }
+
+        let regex_src = query.strip_prefix("re:").or_else(|| {
+            query
+                .strip_prefix('/')
+                .and_then(|rest| rest.strip_suffix('/'))
+                .filter(|rest| !rest.is_empty())
+        });
+
+        if let Some(src) = regex_src {
+            let re = RegexBuilder::new(src).case_insensitive(true).build()?;
+            return Ok(Some(Self::Regex(re)));
+        }
+
+        if query.contains(['*', '?', '[']) {
+            let re = RegexBuilder::new(&glob_to_regex(query))
+                .case_insensitive(true)
+                .build()?;
+            return Ok(Some(Self::Glob(re)));
+        }
+
+        Ok(Some(Self::Substring(query.to_lowercase())))
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+            return false;
+        };
+
+        match self {
+            NavFilter::Substring(needle) => name.to_lowercase().contains(needle.as_str()),
+            NavFilter::Glob(re) | NavFilter::Regex(re) => re.is_match(name),
+        }
+    }
+}
+
+/// Translate a shell glob into an anchored regex pattern
+pub fn glob_to_regex(glob: &str) -> String {
+    let mut pattern = String::with_capacity(glob.len() * 2 + 2);
+    pattern.push('^');
+
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' => pattern.push_str(".*"),
+            '?' => pattern.push('.'),
+            '[' => {
+                // Copy the character class through, translating `[!...]` to `[^...]`
+                pattern.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    pattern.push('^');
+                }
+                for class_char in chars.by_ref() {
+                    if class_char == ']' {
+                        break;
+                    }
+                    if class_char == '\\' {
+                        pattern.push_str("\\\\");
+                    } else {
+                        pattern.push(class_char);
+                    }
+                }
+                pattern.push(']');
+            }
+            other => pattern.push_str(&regex::escape(&other.to_string())),
+        }
+    }
+
+    pattern.push('$');
+    pattern
+}
+
+/// A scanned image together with the metadata gathered for it during the scan,
+/// so sorting and the info page never have to stat the file again
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageEntry {
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// EXIF capture time, only read when the sort mode needs it
+    pub taken: Option<SystemTime>,
+    /// Pixel dimensions, only read when sorting or the layout needs them
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl ImageEntry {
+    /// Stat the file once, plus any slower reads the options depend on
+    pub fn read(path: PathBuf, options: &ScanOptions) -> Self {
+        let meta = fs::metadata(&path).ok();
+        let size = meta.as_ref().map(|m| m.len()).unwrap_or(0);
+        let modified = meta.and_then(|m| m.modified().ok());
+
+        let taken = (options.sort_mode == SortMode::DateTaken)
+            .then(|| metadata::capture_time(&path))
+            .flatten();
+        let dimensions = (options.sort_mode == SortMode::Dimensions || options.read_dimensions)
+            .then(|| metadata::dimensions(&path))
+            .flatten();
+
+        Self {
+            path,
+            size,
+            modified,
+            taken,
+            dimensions,
+        }
+    }
+
+    pub fn file_name(&self) -> &str {
+        file_name(&self.path)
+    }
+
+    /// Capture time with the modification time as fallback
+    pub fn date_taken(&self) -> Option<SystemTime> {
+        self.taken.or(self.modified)
+    }
+
+    pub fn pixel_count(&self) -> u64 {
+        self.dimensions
+            .map(|(w, h)| u64::from(w) * u64::from(h))
+            .unwrap_or(0)
+    }
+}
+
+/// Which parts of [`ImageDetails`] are worth reading
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DetailFields {
+    pub dimensions: bool,
+    pub taken: bool,
+    pub rating: bool,
+    pub animated: bool,
+}
+
+impl DetailFields {
+    /// What the details list and the shown badges put on screen
+    pub fn from_config(config: &ViewerConfig) -> Self {
+        let list = config.gallery_layout == GalleryLayout::List;
+        Self {
+            dimensions: list,
+            taken: list,
+            rating: config.thumbnail_badges.rating,
+            animated: config.thumbnail_badges.animation,
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.dimensions || self.taken || self.rating || self.animated
+    }
+
+    /// Whether everything `other` asks for is among these
+    pub fn covers(&self, other: DetailFields) -> bool {
+        (self.dimensions || !other.dimensions)
+            && (self.taken || !other.taken)
+            && (self.rating || !other.rating)
+            && (self.animated || !other.animated)
+    }
+}
+
+/// Metadata the details list and the gallery's badges show that the scan
+/// only reads when sorting needs it; read for the tiles in view instead
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageDetails {
+    /// Modification time when these were read, to tell when they are stale
+    pub modified: Option<SystemTime>,
+    /// What was read; the other fields are left empty
+    pub fields: DetailFields,
+    pub dimensions: Option<(u32, u32)>,
+    pub taken: Option<SystemTime>,
+    /// Stars from 1 to 5
+    pub rating: Option<u8>,
+    pub animated: bool,
+}
+
+impl ImageDetails {
+    /// Read `fields` only, parsing the EXIF at most once
+    pub fn read(path: &Path, fields: DetailFields) -> Self {
+        let exif = (fields.taken || fields.rating)
+            .then(|| metadata::read_exif(path))
+            .flatten();
+
+        Self {
+            modified: fs::metadata(path).and_then(|meta| meta.modified()).ok(),
+            fields,
+            dimensions: fields
+                .dimensions
+                .then(|| metadata::dimensions(path))
+                .flatten(),
+            taken: exif
+                .as_ref()
+                .filter(|_| fields.taken)
+                .and_then(metadata::exif_capture_time),
+            rating: fields
+                .rating
+                .then(|| metadata::rating(path, exif.as_ref()))
+                .flatten(),
+            animated: fields.animated && metadata::is_animated(path),
+        }
+    }
+}
+
+/// Read `fields` of the [`ImageDetails`] of every path
+pub async fn read_details(
+    paths: Vec<PathBuf>,
+    fields: DetailFields,
+) -> Vec<(PathBuf, ImageDetails)> {
+    spawn_blocking(move || {
+        paths
+            .into_par_iter()
+            .map(|path| {
+                let details = ImageDetails::read(&path, fields);
+                (path, details)
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct NavState {
-    images: Vec<PathBuf>,
+    /// Every image in the directory, in sort order
+    all_images: Vec<ImageEntry>,
+    /// Images visible through the current filter; indices refer to this list
+    images: Vec<ImageEntry>,
     cur_idx: Option<usize>,
+    filter: Option<NavFilter>,
+    /// Folder the listing was read from
+    dir: Option<PathBuf>,
+    /// Whether the listing includes subfolders of `dir`
+    recursive: bool,
+    /// Subfolders of `dir`, shown as tiles ahead of the images
+    folders: Vec<FolderInfo>,
+    /// Files and folders the listing was assembled from, when it is an ad-hoc
+    /// collection rather than a single directory
+    collection: Option<Vec<PathBuf>>,
 }
 
 impl NavState {
@@ -23,6 +299,10 @@ impl NavState {
     }
 
     pub fn current(&self) -> Option<&PathBuf> {
+        self.current_entry().map(|entry| &entry.path)
+    }
+
+    pub fn current_entry(&self) -> Option<&ImageEntry> {
         self.cur_idx.and_then(|idx| self.images.get(idx))
     }
 
@@ -38,18 +318,167 @@ impl NavState {
         self.images.len()
     }
 
+    /// Number of images before filtering
+    pub fn total_unfiltered(&self) -> usize {
+        self.all_images.len()
+    }
+
+    pub fn is_filtered(&self) -> bool {
+        self.filter.is_some()
+    }
+
     pub fn is_empty(&self) -> bool {
         self.images.is_empty()
     }
 
     pub fn images(&self) -> Vec<PathBuf> {
-        self.images.clone()
+        self.images.iter().map(|entry| entry.path.clone()).collect()
+    }
+
+    pub fn entries(&self) -> &[ImageEntry] {
+        &self.images
+    }
+
+    pub fn get(&self, idx: usize) -> Option<&PathBuf> {
+        self.images.get(idx).map(|entry| &entry.path)
+    }
+
+    pub fn entry(&self, idx: usize) -> Option<&ImageEntry> {
+        self.images.get(idx)
+    }
+
+    pub fn dir(&self) -> Option<&PathBuf> {
+        self.dir.as_ref()
+    }
+
+    pub fn set_dir(&mut self, dir: Option<PathBuf>, recursive: bool) {
+        if self.dir != dir {
+            self.folders.clear();
+        }
+        self.dir = dir;
+        self.recursive = recursive;
+        self.collection = None;
+    }
+
+    pub fn collection(&self) -> Option<&[PathBuf]> {
+        self.collection.as_deref()
+    }
+
+    pub fn is_collection(&self) -> bool {
+        self.collection.is_some()
+    }
+
+    /// Switch to a listing assembled from `sources` instead of one directory
+    pub fn set_collection(&mut self, sources: Vec<PathBuf>) {
+        self.dir = None;
+        self.recursive = false;
+        self.folders.clear();
+        self.collection = Some(sources);
+    }
+
+    /// Folder above `dir`, reachable through the ".." tile
+    pub fn parent_dir(&self) -> Option<&Path> {
+        self.dir.as_deref().and_then(Path::parent)
+    }
+
+    pub fn folders(&self) -> &[FolderInfo] {
+        &self.folders
+    }
+
+    pub fn set_folders(&mut self, folders: Vec<FolderInfo>) {
+        self.folders = folders;
+    }
+
+    /// Path below the scanned folder, for labelling images from subfolders
+    pub fn relative_path<'a>(&self, path: &'a Path) -> Option<&'a Path> {
+        if !self.recursive {
+            return None;
+        }
+        self.dir
+            .as_deref()
+            .and_then(|dir| path.strip_prefix(dir).ok())
+    }
+
+    /// Append entries from a streaming scan, keeping the current selection
+    pub fn extend_images(&mut self, images: Vec<ImageEntry>) {
+        match &self.filter {
+            Some(filter) => self.images.extend(
+                images
+                    .iter()
+                    .filter(|entry| filter.matches(&entry.path))
+                    .cloned(),
+            ),
+            None => self.images.extend(images.iter().cloned()),
+        }
+        self.all_images.extend(images);
     }
 
-    pub fn set_images(&mut self, images: Vec<PathBuf>, select: Option<&Path>) {
-        self.images = images;
+    pub fn set_images(&mut self, images: Vec<ImageEntry>, select: Option<&Path>) {
+        self.all_images = images;
+        self.apply_filter();
         // Only set selection if explicitly requested and path exists
-        self.cur_idx = select.and_then(|path| self.images.iter().position(|pos| pos == path));
+        self.cur_idx = select.and_then(|path| self.position(path));
+    }
+
+    /// Drop images that were moved away, keeping the current image selected or,
+    /// if it went too, the one that took its place
+    pub fn remove_images(&mut self, removed: &HashSet<PathBuf>) {
+        let current = self.current().cloned();
+        // Images left ahead of the current one
+        let kept_before = self.cur_idx.map(|idx| {
+            self.images[..idx]
+                .iter()
+                .filter(|entry| !removed.contains(&entry.path))
+                .count()
+        });
+
+        self.all_images
+            .retain(|entry| !removed.contains(&entry.path));
+        self.apply_filter();
+
+        self.cur_idx = match current {
+            Some(path) if !removed.contains(&path) => self.position(&path),
+            _ if self.images.is_empty() => None,
+            _ => kept_before.map(|idx| idx.min(self.images.len() - 1)),
+        };
+    }
+
+    /// Point entries at their new paths after a rename; the order stays until
+    /// the next scan sorts them again
+    pub fn rename_images(&mut self, renamed: &[(PathBuf, PathBuf)]) {
+        // Looked up once per entry, so names passed along ("a" -> "b" -> "c") map correctly
+        let renamed: HashMap<&PathBuf, &PathBuf> =
+            renamed.iter().map(|(from, to)| (from, to)).collect();
+
+        for entry in self.all_images.iter_mut().chain(self.images.iter_mut()) {
+            if let Some(to) = renamed.get(&entry.path) {
+                entry.path = (*to).clone();
+            }
+        }
+    }
+
+    /// Replace the filter, keeping the current image selected if it is still visible
+    pub fn set_filter(&mut self, filter: Option<NavFilter>) {
+        let current = self.current().cloned();
+        self.filter = filter;
+        self.apply_filter();
+        self.cur_idx = current.and_then(|path| self.position(&path));
+    }
+
+    pub fn position(&self, path: &Path) -> Option<usize> {
+        self.images.iter().position(|entry| entry.path == path)
+    }
+
+    fn apply_filter(&mut self) {
+        self.images = match &self.filter {
+            Some(filter) => self
+                .all_images
+                .iter()
+                .filter(|entry| filter.matches(&entry.path))
+                .cloned()
+                .collect(),
+            None => self.all_images.clone(),
+        };
     }
 
     pub fn select(&mut self, idx: usize) -> Option<&PathBuf> {
@@ -129,71 +558,228 @@ pub fn get_image_dir(path: &Path) -> Option<PathBuf> {
     }
 }
 
-pub async fn scan_dir(
-    dir: &Path,
-    include_hidden: bool,
-    sort_mode: SortMode,
-    sort_order: SortOrder,
-) -> Vec<PathBuf> {
+/// What a directory scan lists and how it orders the result
+#[derive(Debug, Clone, Copy)]
+pub struct ScanOptions {
+    pub include_hidden: bool,
+    /// Levels of subfolders to descend into; 0 lists only the folder itself
+    pub depth: usize,
+    pub sort_mode: SortMode,
+    pub sort_order: SortOrder,
+    /// Keep images from the same folder together, folders in name order
+    pub group_by_folder: bool,
+    /// Read every image's dimensions, so justified rows are laid out before
+    /// the thumbnails arrive
+    pub read_dimensions: bool,
+}
+
+impl ScanOptions {
+    pub fn from_config(config: &ViewerConfig) -> Self {
+        // Date groups need the photos in capture order, not split by folder;
+        // the details list sorts by its own columns instead
+        let grouped = config.date_grouping != DateGrouping::Off
+            && config.gallery_layout != GalleryLayout::List;
+
+        Self {
+            include_hidden: config.show_hidden_files,
+            depth: if config.include_subfolders {
+                config.subfolder_depth as usize
+            } else {
+                0
+            },
+            sort_mode: if grouped {
+                SortMode::DateTaken
+            } else {
+                config.sort_mode
+            },
+            sort_order: config.sort_order,
+            group_by_folder: !grouped && config.include_subfolders && config.group_by_folder,
+            read_dimensions: config.gallery_layout == GalleryLayout::Justified,
+        }
+    }
+
+    pub fn is_recursive(&self) -> bool {
+        self.depth > 0
+    }
+}
+
+pub async fn scan_dir(dir: &Path, options: ScanOptions) -> Vec<ImageEntry> {
     let dir = dir.to_path_buf();
 
-    spawn_blocking(move || scan_dir_sync(&dir, include_hidden, sort_mode, sort_order))
+    spawn_blocking(move || scan_dir_sync(&dir, options))
         .await
         .unwrap_or_default()
 }
 
-fn scan_dir_sync(
-    dir: &Path,
-    include_hidden: bool,
-    sort_mode: SortMode,
-    sort_order: SortOrder,
-) -> Vec<PathBuf> {
-    let mut images: Vec<PathBuf> = fs::read_dir(dir)
-        .into_iter()
-        .flatten()
-        .filter_map(|entry| entry.ok())
-        .map(|entry| entry.path())
-        .filter(|path| {
-            if !include_hidden
-                && let Some(name) = path.file_name().and_then(|name| name.to_str())
-                && name.starts_with('.')
-            {
-                return false;
+/// Gather the images named by `paths`, in the order given. Folders are
+/// expanded in place; a collection made only of folders is merged and sorted
+/// as one listing.
+pub async fn scan_collection(paths: Vec<PathBuf>, options: ScanOptions) -> Vec<ImageEntry> {
+    spawn_blocking(move || {
+        let only_folders = paths.iter().all(|path| path.is_dir());
+        let mut seen = HashSet::new();
+        let mut images = Vec::new();
+
+        for path in paths {
+            if path.is_dir() {
+                images.extend(scan_dir_sync(&path, options));
+            } else if is_supported_image(&path) {
+                images.push(ImageEntry::read(path, &options));
             }
-            is_supported_image(path)
-        })
-        .collect();
+        }
 
-    images.sort_by(|a, b| {
-        let ordering = match sort_mode {
-            SortMode::Name => {
-                let a_name = a.file_name().and_then(|name| name.to_str()).unwrap_or("");
-                let b_name = b.file_name().and_then(|name| name.to_str()).unwrap_or("");
-                human_sort(a_name, b_name)
-            }
-            SortMode::Date => {
-                let a_time = fs::metadata(a)
-                    .and_then(|m| m.modified())
-                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-                let b_time = fs::metadata(b)
-                    .and_then(|m| m.modified())
-                    .unwrap_or(std::time::SystemTime::UNIX_EPOCH);
-                a_time.cmp(&b_time)
+        // The same image may be named directly and through its folder
+        images.retain(|entry| seen.insert(entry.path.clone()));
+
+        if only_folders {
+            sort_entries(&mut images, &options);
+        }
+
+        images
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Progress of a streaming directory scan
+#[derive(Debug, Clone)]
+pub enum ScanEvent {
+    /// Entries as they are read from disk, sorted within the batch only
+    Batch(Vec<ImageEntry>),
+    /// The complete, sorted listing
+    Done(Vec<ImageEntry>),
+}
+
+/// Scan a directory, yielding batches as they are read so the gallery can
+/// start filling before the listing of a huge directory is complete
+pub fn scan_dir_streaming(dir: PathBuf, options: ScanOptions) -> impl Stream<Item = ScanEvent> {
+    cosmic::iced::stream::channel(4, move |mut output| async move {
+        use cosmic::iced_futures::futures::SinkExt;
+
+        let (tx, mut rx) = mpsc::channel(4);
+
+        let scan = spawn_blocking(move || {
+            scan_dir_batched(&dir, options, |event| tx.blocking_send(event).is_ok())
+        });
+
+        while let Some(event) = rx.recv().await {
+            if output.send(event).await.is_err() {
+                break;
             }
-            SortMode::Size => {
-                let a_size = fs::metadata(a).map(|m| m.len()).unwrap_or(0);
-                let b_size = fs::metadata(b).map(|m| m.len()).unwrap_or(0);
-                a_size.cmp(&b_size)
+        }
+
+        let _ = scan.await;
+    })
+}
+
+/// Number of image files gathered before a batch is handed to the UI
+const SCAN_BATCH_SIZE: usize = 512;
+
+/// Read `dir` in batches, calling `emit` for each one and once more with the
+/// sorted result. Stops early if `emit` returns false (the receiver is gone).
+fn scan_dir_batched(dir: &Path, options: ScanOptions, mut emit: impl FnMut(ScanEvent) -> bool) {
+    let mut images = Vec::new();
+    let mut pending = Vec::with_capacity(SCAN_BATCH_SIZE);
+
+    for path in list_images(dir, &options) {
+        pending.push(path);
+
+        if pending.len() == SCAN_BATCH_SIZE {
+            let mut batch = read_entries(std::mem::take(&mut pending), &options);
+            sort_entries(&mut batch, &options);
+            images.extend(batch.iter().cloned());
+
+            if !emit(ScanEvent::Batch(batch)) {
+                return;
             }
+        }
+    }
+
+    if !pending.is_empty() {
+        let mut batch = read_entries(pending, &options);
+        sort_entries(&mut batch, &options);
+        images.extend(batch.iter().cloned());
+
+        if !emit(ScanEvent::Batch(batch)) {
+            return;
+        }
+    }
+
+    sort_entries(&mut images, &options);
+    emit(ScanEvent::Done(images));
+}
+
+fn scan_dir_sync(dir: &Path, options: ScanOptions) -> Vec<ImageEntry> {
+    let paths: Vec<PathBuf> = list_images(dir, &options).collect();
+
+    let mut images = read_entries(paths, &options);
+    sort_entries(&mut images, &options);
+
+    images
+}
+
+/// Image files in `dir` and, up to `options.depth` levels down, its subfolders.
+/// Symlinked folders are not followed so link cycles can't trap the walk.
+fn list_images(dir: &Path, options: &ScanOptions) -> impl Iterator<Item = PathBuf> {
+    let include_hidden = options.include_hidden;
+
+    WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(options.depth + 1)
+        .into_iter()
+        .filter_entry(move |entry| include_hidden || !is_hidden(entry.file_name()))
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| !entry.file_type().is_dir())
+        .map(|entry| entry.into_path())
+        .filter(|path| is_supported_image(path))
+}
+
+fn is_hidden(name: &OsStr) -> bool {
+    name.to_str().is_some_and(|name| name.starts_with('.'))
+}
+
+/// Stat every file exactly once, in parallel; slow network mounts
+/// benefit the most from overlapping the round trips
+fn read_entries(paths: Vec<PathBuf>, options: &ScanOptions) -> Vec<ImageEntry> {
+    paths
+        .into_par_iter()
+        .map(|path| ImageEntry::read(path, options))
+        .collect()
+}
+
+pub fn sort_entries(images: &mut [ImageEntry], options: &ScanOptions) {
+    let epoch = SystemTime::UNIX_EPOCH;
+
+    images.sort_by(|a, b| {
+        let folder = if options.group_by_folder {
+            human_sort(&parent_dir(&a.path), &parent_dir(&b.path))
+        } else {
+            Ordering::Equal
+        };
+
+        let ordering = match options.sort_mode {
+            SortMode::Name => human_sort(a.file_name(), b.file_name()),
+            SortMode::Date => a
+                .modified
+                .unwrap_or(epoch)
+                .cmp(&b.modified.unwrap_or(epoch)),
+            SortMode::Size => a.size.cmp(&b.size),
+            SortMode::DateTaken => a
+                .date_taken()
+                .unwrap_or(epoch)
+                .cmp(&b.date_taken().unwrap_or(epoch)),
+            SortMode::Dimensions => a.pixel_count().cmp(&b.pixel_count()),
+            SortMode::Type => extension_lowercase(&a.path)
+                .cmp(&extension_lowercase(&b.path))
+                .then_with(|| human_sort(a.file_name(), b.file_name())),
+            SortMode::Random => shuffle_key(&a.path).cmp(&shuffle_key(&b.path)),
         };
 
-        match sort_order {
+        folder.then(match options.sort_order {
             SortOrder::Ascending => ordering,
             SortOrder::Descending => ordering.reverse(),
-        }
+        })
     });
-
-    images
 }
 
 pub fn is_supported_image(path: &Path) -> bool {
@@ -203,7 +789,33 @@ pub fn is_supported_image(path: &Path) -> bool {
         .unwrap_or(false)
 }
 
-fn human_sort(a: &str, b: &str) -> std::cmp::Ordering {
+fn file_name(path: &Path) -> &str {
+    path.file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("")
+}
+
+fn parent_dir(path: &Path) -> std::borrow::Cow<'_, str> {
+    path.parent()
+        .map(|parent| parent.to_string_lossy())
+        .unwrap_or_default()
+}
+
+fn extension_lowercase(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default()
+}
+
+fn shuffle_key(path: &Path) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    SESSION_SEED.hash(&mut hasher);
+    path.hash(&mut hasher);
+    hasher.finish()
+}
+
+pub fn human_sort(a: &str, b: &str) -> std::cmp::Ordering {
     let mut a_chars = a.chars().peekable();
     let mut b_chars = b.chars().peekable();
 
@@ -0,0 +1,563 @@
+//! Renaming one or many images
+//!
+//! A batch rename builds every new name from a pattern. Placeholders are
+//! filled in per file:
+//!
+//! - `{name}`: the file name without its extension
+//! - `{ext}`: the extension, without the dot
+//! - `{n}`, `{n:03}`: a counter starting at 1, optionally zero padded
+//! - `{date}`, `{date:%Y-%m-%d}`: when the photo was taken, falling back to
+//!   when the file was last modified
+//! - `{camera}`: the camera model
+//! - `{width}`, `{height}`: the image dimensions
+//!
+//! Use `{{` and `}}` for literal braces.
+
+use crate::image::metadata;
+use chrono::{
+    NaiveDateTime,
+    format::{Item, StrftimeItems},
+};
+use rayon::prelude::*;
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tokio::task::spawn_blocking;
+
+/// Date format used by a bare `{date}`
+const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Pattern offered when a batch rename opens
+pub const DEFAULT_PATTERN: &str = "{name}_{n:03}.{ext}";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Name,
+    Ext,
+    Counter { width: usize },
+    Date(String),
+    Camera,
+    Width,
+    Height,
+}
+
+/// A parsed rename pattern
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+impl Pattern {
+    pub fn parse(src: &str) -> Result<Self, String> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+        let mut chars = src.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut placeholder = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => placeholder.push(c),
+                            None => return Err(format!("unclosed {{{placeholder}")),
+                        }
+                    }
+
+                    if !text.is_empty() {
+                        tokens.push(Token::Text(std::mem::take(&mut text)));
+                    }
+                    tokens.push(parse_placeholder(&placeholder)?);
+                }
+                '}' => return Err("unmatched }".to_string()),
+                c => text.push(c),
+            }
+        }
+
+        if !text.is_empty() {
+            tokens.push(Token::Text(text));
+        }
+
+        Ok(Self { tokens })
+    }
+
+    /// Whether filling in the pattern needs [`FileInfo`] read from the files
+    pub fn needs_info(&self) -> bool {
+        self.tokens.iter().any(|token| {
+            matches!(
+                token,
+                Token::Date(_) | Token::Camera | Token::Width | Token::Height
+            )
+        })
+    }
+
+    /// The new file name for `path`, the `n`th file of the batch counting from 1
+    pub fn apply(&self, path: &Path, n: usize, info: &FileInfo) -> String {
+        let stem = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let ext = path
+            .extension()
+            .map(|ext| ext.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let mut name = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Text(text) => name.push_str(text),
+                Token::Name => name.push_str(&stem),
+                Token::Ext => name.push_str(&ext),
+                Token::Counter { width } => {
+                    name.push_str(&format!("{n:0width$}", width = *width));
+                }
+                Token::Date(format) => {
+                    if let Some(date) = info.date {
+                        name.push_str(&sanitize(&date.format(format).to_string()));
+                    }
+                }
+                Token::Camera => name.push_str(&sanitize(info.camera.as_deref().unwrap_or(""))),
+                Token::Width => {
+                    if let Some((width, _)) = info.dimensions {
+                        name.push_str(&width.to_string());
+                    }
+                }
+                Token::Height => {
+                    if let Some((_, height)) = info.dimensions {
+                        name.push_str(&height.to_string());
+                    }
+                }
+            }
+        }
+
+        name
+    }
+}
+
+fn parse_placeholder(placeholder: &str) -> Result<Token, String> {
+    let (key, arg) = match placeholder.split_once(':') {
+        Some((key, arg)) => (key.trim(), Some(arg)),
+        None => (placeholder.trim(), None),
+    };
+
+    match (key, arg) {
+        ("name", None) => Ok(Token::Name),
+        ("ext", None) => Ok(Token::Ext),
+        ("n", None) => Ok(Token::Counter { width: 1 }),
+        ("n", Some(width)) => width
+            .trim()
+            .parse()
+            .ok()
+            .filter(|width| *width <= 12)
+            .map(|width| Token::Counter { width })
+            .ok_or_else(|| format!("invalid counter width '{width}'")),
+        ("date", None) => Ok(Token::Date(DEFAULT_DATE_FORMAT.to_string())),
+        ("date", Some(format)) => {
+            // An unknown specifier would only fail when the date is formatted
+            if StrftimeItems::new(format).any(|item| matches!(item, Item::Error)) {
+                Err(format!("invalid date format '{format}'"))
+            } else {
+                Ok(Token::Date(format.to_string()))
+            }
+        }
+        ("camera", None) => Ok(Token::Camera),
+        ("width", None) => Ok(Token::Width),
+        ("height", None) => Ok(Token::Height),
+        _ => Err(format!("unknown placeholder {{{placeholder}}}")),
+    }
+}
+
+/// Keep values read from the file from adding path separators
+fn sanitize(value: &str) -> String {
+    value.replace(['/', '\0'], "_")
+}
+
+/// What the placeholders beyond the file name need, read once per file
+#[derive(Debug, Clone, Default)]
+pub struct FileInfo {
+    pub date: Option<NaiveDateTime>,
+    pub camera: Option<String>,
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl FileInfo {
+    pub fn read(path: &Path) -> Self {
+        let date = metadata::capture_datetime(path).or_else(|| {
+            let modified = fs::metadata(path).ok()?.modified().ok()?;
+            Some(chrono::DateTime::<chrono::Local>::from(modified).naive_local())
+        });
+
+        Self {
+            date,
+            camera: metadata::camera(path),
+            dimensions: metadata::dimensions(path),
+        }
+    }
+}
+
+/// Read the [`FileInfo`] of every path, in the same order
+pub async fn read_info(paths: Vec<PathBuf>) -> Vec<FileInfo> {
+    spawn_blocking(move || paths.par_iter().map(|path| FileInfo::read(path)).collect())
+        .await
+        .unwrap_or_default()
+}
+
+/// Why a planned rename can't go ahead
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    Empty,
+    /// The name is `.`, `..` or contains a slash
+    Invalid,
+    /// Another file of the batch would get the same name
+    Duplicate,
+    /// A file that isn't being renamed already has the name
+    Exists,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlannedRename {
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub problem: Option<Problem>,
+}
+
+impl PlannedRename {
+    pub fn is_unchanged(&self) -> bool {
+        self.from == self.to
+    }
+}
+
+/// New paths for `paths` with the names `names`, flagging every rename that
+/// can't be done
+pub fn plan(paths: &[PathBuf], names: Vec<String>) -> Vec<PlannedRename> {
+    let sources: HashSet<&PathBuf> = paths.iter().collect();
+    let mut planned: Vec<PlannedRename> = paths
+        .iter()
+        .zip(names)
+        .map(|(from, name)| {
+            let problem = if name.trim().is_empty() {
+                Some(Problem::Empty)
+            } else if name == "." || name == ".." || name.contains('/') {
+                Some(Problem::Invalid)
+            } else {
+                None
+            };
+
+            PlannedRename {
+                from: from.clone(),
+                to: from.with_file_name(&name),
+                problem,
+            }
+        })
+        .collect();
+
+    let mut targets: HashMap<PathBuf, usize> = HashMap::new();
+    for rename in &planned {
+        *targets.entry(rename.to.clone()).or_default() += 1;
+    }
+
+    for rename in planned.iter_mut().filter(|rename| rename.problem.is_none()) {
+        if targets[&rename.to] > 1 {
+            rename.problem = Some(Problem::Duplicate);
+        } else if !sources.contains(&rename.to) && rename.to.exists() {
+            rename.problem = Some(Problem::Exists);
+        }
+    }
+
+    planned
+}
+
+/// What a finished rename did
+#[derive(Debug, Clone, Default)]
+pub struct RenameReport {
+    /// Old and new path of every file that was renamed
+    pub renamed: Vec<(PathBuf, PathBuf)>,
+    pub errors: Vec<String>,
+}
+
+impl RenameReport {
+    /// The renames that take this one back
+    pub fn reversed(&self) -> Vec<(PathBuf, PathBuf)> {
+        self.renamed
+            .iter()
+            .map(|(from, to)| (to.clone(), from.clone()))
+            .collect()
+    }
+}
+
+/// Rename every `(from, to)` pair, never replacing a file that isn't part of
+/// the batch
+pub async fn rename(renames: Vec<(PathBuf, PathBuf)>) -> RenameReport {
+    spawn_blocking(move || rename_all(renames))
+        .await
+        .unwrap_or_else(|err| RenameReport {
+            renamed: Vec::new(),
+            errors: vec![err.to_string()],
+        })
+}
+
+fn rename_all(renames: Vec<(PathBuf, PathBuf)>) -> RenameReport {
+    let mut report = RenameReport::default();
+    let sources: HashSet<&PathBuf> = renames.iter().map(|(from, _)| from).collect();
+
+    // Names passed around inside the batch ("a" -> "b", "b" -> "c") need a
+    // detour through temporary names so nothing is renamed onto a file that
+    // hasn't moved out of the way yet
+    let chained = renames
+        .iter()
+        .any(|(from, to)| from != to && sources.contains(to));
+
+    if !chained {
+        for (from, to) in renames {
+            match rename_new(&from, &to) {
+                Ok(()) => report.renamed.push((from, to)),
+                Err(err) => report.errors.push(format!("{}: {err}", from.display())),
+            }
+        }
+        return report;
+    }
+
+    let mut staged = Vec::with_capacity(renames.len());
+    for (from, to) in renames {
+        let temp = temp_name(&from);
+        match rename_new(&from, &temp) {
+            Ok(()) => staged.push((from, temp, to)),
+            Err(err) => report.errors.push(format!("{}: {err}", from.display())),
+        }
+    }
+
+    for (from, temp, to) in staged {
+        match rename_new(&temp, &to) {
+            Ok(()) => report.renamed.push((from, to)),
+            Err(err) => {
+                report.errors.push(format!("{}: {err}", from.display()));
+                // Put it back under its old name rather than leave it hidden
+                if let Err(err) = rename_new(&temp, &from) {
+                    report.errors.push(format!("{}: {err}", temp.display()));
+                }
+            }
+        }
+    }
+
+    report
+}
+
+/// `fs::rename` replaces an existing target; this refuses to
+fn rename_new(from: &Path, to: &Path) -> io::Result<()> {
+    if to.exists() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", to.display()),
+        ));
+    }
+    fs::rename(from, to)
+}
+
+/// A hidden name next to `path` that nothing has
+fn temp_name(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    (0..)
+        .map(|n| path.with_file_name(format!(".{name}.{n}.cupola-rename")))
+        .find(|candidate| !candidate.exists())
+        .expect("some temporary name is free")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(pattern: &str, path: &str, n: usize) -> String {
+        Pattern::parse(pattern)
+            .unwrap()
+            .apply(Path::new(path), n, &FileInfo::default())
+    }
+
+    /// An empty folder of its own for a test, holding `files`
+    fn scratch_dir(test: &str, files: &[&str]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cupola-{test}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        for file in files {
+            fs::write(dir.join(file), file).unwrap();
+        }
+        dir
+    }
+
+    #[test]
+    fn parses_placeholders_and_text() {
+        assert_eq!(
+            apply("{name}_{n:03}.{ext}", "/photos/beach.jpg", 7),
+            "beach_007.jpg"
+        );
+        assert_eq!(apply("{n}-{name}", "/photos/beach.jpg", 12), "12-beach");
+        assert_eq!(apply("{ n : 2 }", "/photos/beach.jpg", 3), "03");
+    }
+
+    #[test]
+    fn doubled_braces_are_literal() {
+        assert_eq!(apply("{{name}}", "/photos/beach.jpg", 1), "{name}");
+        assert_eq!(apply("{{{name}}}", "/photos/beach.jpg", 1), "{beach}");
+        assert_eq!(
+            Pattern::parse("a{{b}}c").unwrap(),
+            Pattern {
+                tokens: vec![Token::Text("a{b}c".to_string())]
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_bad_patterns() {
+        for pattern in [
+            "{name",
+            "name}",
+            "{}",
+            "{size}",
+            "{ext:x}",
+            "{n:wide}",
+            "{n:13}",
+            "{date:%Q}",
+        ] {
+            assert!(Pattern::parse(pattern).is_err(), "{pattern} parsed");
+        }
+    }
+
+    #[test]
+    fn fills_in_file_info() {
+        let info = FileInfo {
+            date: chrono::NaiveDate::from_ymd_opt(2024, 3, 5)
+                .and_then(|date| date.and_hms_opt(14, 30, 0)),
+            camera: Some("EOS R/6".to_string()),
+            dimensions: Some((6000, 4000)),
+        };
+        let pattern = Pattern::parse("{date}_{date:%H%M}_{camera}_{width}x{height}").unwrap();
+
+        assert!(pattern.needs_info());
+        assert!(!Pattern::parse(DEFAULT_PATTERN).unwrap().needs_info());
+        assert_eq!(
+            pattern.apply(Path::new("/photos/IMG_1.jpg"), 1, &info),
+            "2024-03-05_1430_EOS R_6_6000x4000"
+        );
+        // Slashes from a date format can't move the file elsewhere
+        assert_eq!(
+            Pattern::parse("{date:%Y/%m}")
+                .unwrap()
+                .apply(Path::new("/photos/IMG_1.jpg"), 1, &info),
+            "2024_03"
+        );
+    }
+
+    #[test]
+    fn plan_flags_empty_invalid_and_duplicate_names() {
+        let paths: Vec<PathBuf> = ["a.jpg", "b.jpg", "c.jpg", "d.jpg", "e.jpg"]
+            .iter()
+            .map(|name| Path::new("/nonexistent/cupola").join(name))
+            .collect();
+        let names = ["", "..", "x/y.jpg", "same.jpg", "same.jpg"]
+            .map(str::to_string)
+            .to_vec();
+
+        let problems: Vec<Option<Problem>> = plan(&paths, names)
+            .into_iter()
+            .map(|rename| rename.problem)
+            .collect();
+        assert_eq!(
+            problems,
+            [
+                Some(Problem::Empty),
+                Some(Problem::Invalid),
+                Some(Problem::Invalid),
+                Some(Problem::Duplicate),
+                Some(Problem::Duplicate),
+            ]
+        );
+    }
+
+    #[test]
+    fn plan_allows_names_freed_within_the_batch() {
+        let dir = scratch_dir("plan", &["a.jpg", "b.jpg", "other.jpg"]);
+        let paths = vec![dir.join("a.jpg"), dir.join("b.jpg")];
+
+        // b takes a's name while a moves away; other.jpg isn't part of the batch
+        let planned = plan(&paths, vec!["other.jpg".into(), "a.jpg".into()]);
+        assert_eq!(planned[0].problem, Some(Problem::Exists));
+        assert_eq!(planned[1].problem, None);
+
+        // Keeping a name is fine, and shows as unchanged
+        let planned = plan(&paths, vec!["a.jpg".into(), "c.jpg".into()]);
+        assert_eq!(planned[0].problem, None);
+        assert!(planned[0].is_unchanged());
+        assert!(!planned[1].is_unchanged());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn renames_chains_and_swaps_through_temporary_names() {
+        let dir = scratch_dir("chain", &["a.jpg", "b.jpg", "c.jpg"]);
+
+        // a -> b -> c -> d, each onto a name the next one frees
+        let report = rename_all(vec![
+            (dir.join("a.jpg"), dir.join("b.jpg")),
+            (dir.join("b.jpg"), dir.join("c.jpg")),
+            (dir.join("c.jpg"), dir.join("d.jpg")),
+        ]);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(report.renamed.len(), 3);
+        assert!(!dir.join("a.jpg").exists());
+        assert_eq!(fs::read_to_string(dir.join("b.jpg")).unwrap(), "a.jpg");
+        assert_eq!(fs::read_to_string(dir.join("c.jpg")).unwrap(), "b.jpg");
+        assert_eq!(fs::read_to_string(dir.join("d.jpg")).unwrap(), "c.jpg");
+
+        // Undoing goes through the same detour
+        let report = rename_all(report.reversed());
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        for name in ["a.jpg", "b.jpg", "c.jpg"] {
+            assert_eq!(fs::read_to_string(dir.join(name)).unwrap(), name);
+        }
+        assert!(!dir.join("d.jpg").exists());
+
+        let report = rename_all(vec![
+            (dir.join("a.jpg"), dir.join("b.jpg")),
+            (dir.join("b.jpg"), dir.join("a.jpg")),
+        ]);
+        assert!(report.errors.is_empty(), "{:?}", report.errors);
+        assert_eq!(fs::read_to_string(dir.join("a.jpg")).unwrap(), "b.jpg");
+        assert_eq!(fs::read_to_string(dir.join("b.jpg")).unwrap(), "a.jpg");
+
+        // No temporary names are left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn never_renames_onto_a_file_outside_the_batch() {
+        let dir = scratch_dir("existing", &["a.jpg", "taken.jpg"]);
+
+        let report = rename_all(vec![(dir.join("a.jpg"), dir.join("taken.jpg"))]);
+        assert!(report.renamed.is_empty());
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(fs::read_to_string(dir.join("a.jpg")).unwrap(), "a.jpg");
+        assert_eq!(
+            fs::read_to_string(dir.join("taken.jpg")).unwrap(),
+            "taken.jpg"
+        );
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,5 +1,9 @@
+pub mod breadcrumb;
 pub mod gallery;
 pub mod image_view_state;
+pub mod rename;
 
+pub use breadcrumb::Breadcrumb;
 pub use gallery::GalleryView;
 pub use image_view_state::ImageViewState;
+pub use rename::RenameDialog;
@@ -0,0 +1,325 @@
+//! Path bar above the gallery
+//!
+//! Shows the open directory as clickable segments, each followed by a menu of
+//! its sibling folders, and switches to an editable path with completion.
+
+use crate::{
+    fl, folder_tree,
+    message::{BreadcrumbMessage, Message},
+    nav,
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    theme,
+    widget::{Id, button, column, container, icon, popover, row, scrollable, text, text_input},
+};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tokio::task::spawn_blocking;
+
+/// Completions offered below the path editor
+const MAX_COMPLETIONS: usize = 8;
+
+#[derive(Debug, Clone, Default)]
+pub struct Breadcrumb {
+    /// Directory the segments and siblings were built for
+    dir: Option<PathBuf>,
+    /// Sibling folders of each segment, aligned with `segments(dir)`
+    siblings: Vec<Vec<PathBuf>>,
+    /// Segment whose menu of siblings is open
+    open_siblings: Option<usize>,
+    pub editing: bool,
+    pub input: String,
+    pub completions: Vec<PathBuf>,
+}
+
+impl Breadcrumb {
+    pub const INPUT_ID: &'static str = "breadcrumb-input";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_dir(&mut self, dir: Option<PathBuf>) {
+        if self.dir != dir {
+            self.siblings.clear();
+            self.open_siblings = None;
+        }
+        self.dir = dir;
+    }
+
+    pub fn set_siblings(&mut self, dir: &Path, siblings: Vec<Vec<PathBuf>>) {
+        if self.dir.as_deref() == Some(dir) {
+            self.siblings = siblings;
+        }
+    }
+
+    /// Sibling `idx` of segment `segment`
+    pub fn sibling(&self, segment: usize, idx: usize) -> Option<&PathBuf> {
+        self.siblings.get(segment)?.get(idx)
+    }
+
+    /// Open the menu of siblings of `segment`, or close it if it is open
+    pub fn toggle_siblings(&mut self, segment: usize) {
+        self.open_siblings = (self.open_siblings != Some(segment)).then_some(segment);
+    }
+
+    pub fn close_siblings(&mut self) {
+        self.open_siblings = None;
+    }
+
+    pub fn start_edit(&mut self) {
+        self.editing = true;
+        self.open_siblings = None;
+        self.input = self
+            .dir
+            .as_ref()
+            .map(|dir| format!("{}/", dir.to_string_lossy().trim_end_matches('/')))
+            .unwrap_or_default();
+        self.completions.clear();
+    }
+
+    pub fn cancel_edit(&mut self) {
+        self.editing = false;
+        self.input.clear();
+        self.completions.clear();
+    }
+
+    /// Folder the typed path points at, falling back to a single completion
+    pub fn submitted_dir(&self) -> Option<PathBuf> {
+        let path = expand_home(self.input.trim());
+        if path.is_dir() {
+            return Some(path);
+        }
+
+        match self.completions.as_slice() {
+            [only] => Some(only.clone()),
+            _ => None,
+        }
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let content: Element<'_, Message> = if self.editing {
+            self.editor()
+        } else {
+            self.segments_row()
+        };
+
+        container(content)
+            .padding([spacing.space_xxs, spacing.space_s])
+            .width(Length::Fill)
+            .into()
+    }
+
+    fn segments_row(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let mut bar = row().spacing(spacing.space_xxxs).align_y(Alignment::Center);
+
+        if let Some(dir) = &self.dir {
+            for (idx, (name, path)) in segments(dir).into_iter().enumerate() {
+                let is_last = path == *dir;
+
+                bar = bar.push(
+                    button::text(name)
+                        .on_press_maybe((!is_last).then(|| Message::OpenPath(path.clone())))
+                        .class(if is_last {
+                            theme::Button::Text
+                        } else {
+                            theme::Button::Link
+                        }),
+                );
+
+                // Other folders at this level, to switch sideways
+                if let Some(siblings) = self.siblings.get(idx)
+                    && !siblings.is_empty()
+                {
+                    let chevron = button::icon(icon::from_name("pan-down-symbolic").size(16))
+                        .tooltip(fl!("breadcrumb-siblings"))
+                        .on_press(Message::Breadcrumb(BreadcrumbMessage::ToggleSiblings(idx)));
+
+                    let mut menu = popover(chevron)
+                        .on_close(Message::Breadcrumb(BreadcrumbMessage::CloseSiblings));
+                    if self.open_siblings == Some(idx) {
+                        menu = menu.popup(Self::siblings_menu(idx, siblings, &path));
+                    }
+                    bar = bar.push(menu);
+                }
+            }
+        }
+
+        row()
+            .push(bar.width(Length::Fill))
+            .push(
+                button::icon(icon::from_name("document-edit-symbolic"))
+                    .tooltip(fl!("breadcrumb-edit"))
+                    .on_press(Message::Breadcrumb(BreadcrumbMessage::StartEdit)),
+            )
+            .align_y(Alignment::Center)
+            .into()
+    }
+
+    /// Folders beside `current`; `current` itself is listed but can't be picked
+    fn siblings_menu<'a>(
+        segment: usize,
+        siblings: &'a [PathBuf],
+        current: &Path,
+    ) -> Element<'a, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let items =
+            siblings.iter().enumerate().map(|(idx, sibling)| {
+                let is_current = sibling == current;
+                button::text(folder_tree::display_name(sibling))
+                    .on_press_maybe((!is_current).then(|| {
+                        Message::Breadcrumb(BreadcrumbMessage::SelectSibling(segment, idx))
+                    }))
+                    .class(theme::Button::MenuItem)
+                    .width(Length::Fill)
+                    .into()
+            });
+
+        container(scrollable(
+            column().extend(items).spacing(spacing.space_xxxs),
+        ))
+        .padding(spacing.space_xxs)
+        .width(Length::Fixed(240.0))
+        .max_height(320.0)
+        .class(theme::Container::Dialog)
+        .into()
+    }
+
+    fn editor(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let input = text_input(fl!("breadcrumb-placeholder"), self.input.as_str())
+            .id(Id::new(Self::INPUT_ID))
+            .on_input(|input| Message::Breadcrumb(BreadcrumbMessage::Input(input)))
+            .on_submit(|_| Message::Breadcrumb(BreadcrumbMessage::Submit))
+            .width(Length::Fill);
+
+        let header = row()
+            .push(input)
+            .push(
+                button::icon(icon::from_name("window-close-symbolic"))
+                    .on_press(Message::Breadcrumb(BreadcrumbMessage::CancelEdit)),
+            )
+            .spacing(spacing.space_xxs)
+            .align_y(Alignment::Center);
+
+        let completions = self.completions.iter().map(|path| {
+            button::text(format!("{}/", path.to_string_lossy()))
+                .on_press(Message::Breadcrumb(BreadcrumbMessage::Complete(
+                    path.clone(),
+                )))
+                .class(theme::Button::MenuItem)
+                .width(Length::Fill)
+                .into()
+        });
+
+        column()
+            .push(header)
+            .extend(completions)
+            .spacing(spacing.space_xxxs)
+            .into()
+    }
+}
+
+/// Display name and path of every folder from the top of `dir` down to it.
+/// Paths inside the home folder start at "Home" rather than the filesystem root.
+pub fn segments(dir: &Path) -> Vec<(String, PathBuf)> {
+    let home = dirs::home_dir();
+    let mut segments = Vec::new();
+
+    for ancestor in dir.ancestors() {
+        if Some(ancestor) == home.as_deref() {
+            segments.push((fl!("folder-tree-home"), ancestor.to_path_buf()));
+            break;
+        }
+
+        let name = match ancestor.file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => ancestor.to_string_lossy().into_owned(),
+        };
+        segments.push((name, ancestor.to_path_buf()));
+    }
+
+    segments.reverse();
+    segments
+}
+
+/// Sibling folders for each segment of `dir`; the top segment has none
+pub async fn load_siblings(dir: PathBuf, include_hidden: bool) -> Vec<Vec<PathBuf>> {
+    spawn_blocking(move || {
+        let segments = segments(&dir);
+
+        segments
+            .iter()
+            .enumerate()
+            .map(|(idx, (_, path))| match path.parent() {
+                Some(parent) if idx > 0 => subfolders(parent, include_hidden),
+                _ => Vec::new(),
+            })
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+/// Folders matching the partially typed path in `input`
+pub async fn complete(input: String, include_hidden: bool) -> Vec<PathBuf> {
+    spawn_blocking(move || {
+        let path = expand_home(&input);
+
+        // "/a/b/" lists b, "/a/b" lists a filtered by the prefix "b"
+        let (parent, prefix) = if input.ends_with('/') {
+            (path.as_path(), String::new())
+        } else {
+            match (path.parent(), path.file_name()) {
+                (Some(parent), Some(name)) => (parent, name.to_string_lossy().to_lowercase()),
+                _ => return Vec::new(),
+            }
+        };
+
+        subfolders(parent, include_hidden || prefix.starts_with('.'))
+            .into_iter()
+            .filter(|folder| {
+                folder
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().to_lowercase().starts_with(&prefix))
+            })
+            .take(MAX_COMPLETIONS)
+            .collect()
+    })
+    .await
+    .unwrap_or_default()
+}
+
+fn subfolders(dir: &Path, include_hidden: bool) -> Vec<PathBuf> {
+    let mut folders: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter(|entry| include_hidden || !entry.file_name().to_string_lossy().starts_with('.'))
+        .map(|entry| entry.path())
+        .collect();
+
+    folders.sort_by(|a, b| {
+        nav::human_sort(&folder_tree::display_name(a), &folder_tree::display_name(b))
+    });
+    folders
+}
+
+fn expand_home(input: &str) -> PathBuf {
+    match input.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => dirs::home_dir()
+            .map(|home| home.join(rest.trim_start_matches('/')))
+            .unwrap_or_else(|| PathBuf::from(input)),
+        _ => PathBuf::from(input),
+    }
+}
@@ -1,15 +1,28 @@
 use crate::{
+    config::{DateGrouping, GalleryLayout, SortMode, SortOrder, ViewerConfig},
     edit::EditState,
-    fl,
-    image::{CachedImage, ImageCache},
-    message::{Message, NavMessage, ViewMessage},
-    nav::NavState,
+    fl, folder_tree,
+    format::{format_size, format_system_time},
+    image::{CachedImage, ImageCache, ThumbnailScheduler},
+    message::{ImageMessage, Message, NavMessage, SettingsMessage, ViewMessage},
+    nav::{self, DetailFields, ImageDetails, ImageEntry, NavState},
     views::ImageViewState,
-    widgets::flex_grid::{gallery_grid, GalleryItem},
+    widgets::{
+        ScrubberMark, SelectionChange, ZoomRequest,
+        flex_grid::{
+            core, gallery_grid, grid_metrics, handle_aspect, justified_metrics, list_metrics,
+            sectioned_metrics, GalleryItem, GallerySection, LIST_ROW_HEIGHT, TileBadges,
+        },
+        scrubber,
+    },
 };
+use chrono::{Datelike, NaiveDate};
 use cosmic::{
     Element,
-    iced::{Alignment, ContentFit, Length},
+    iced::{
+        Alignment, ContentFit, Length, Padding, Point, Rectangle, Size,
+        widget::scrollable::Viewport,
+    },
     iced_widget::{
         scrollable::{Direction, Scrollbar},
         stack,
@@ -17,51 +30,829 @@ use cosmic::{
     theme,
     widget::{
         Id, Space, button, column, container, horizontal_space, icon, image, mouse_area,
-        responsive, row, scrollable, text,
+        responsive, row, scrollable, search_input, text,
     },
 };
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    ops::Range,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// Columns of the details list after the name, the sort mode each header
+/// switches to and its width
+const DETAIL_COLUMNS: [(SortMode, f32); 5] = [
+    (SortMode::Dimensions, 110.0),
+    (SortMode::Size, 90.0),
+    (SortMode::Type, 70.0),
+    (SortMode::Date, 140.0),
+    (SortMode::DateTaken, 140.0),
+];
+
+/// Files from this size on have it on their format badge
+const LARGE_FILE_SIZE: u64 = 25 * 1024 * 1024;
+
+/// What a section of the timeline holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum GroupKey {
+    Folders,
+    /// Photos taken on this day, or in the month or year starting on it
+    Date(NaiveDate),
+    Undated,
+}
+
+/// A section of the timeline; `tiles` are grid indices, folder tiles included
+#[derive(Debug, Clone)]
+struct DateGroup {
+    key: GroupKey,
+    tiles: Range<usize>,
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct GalleryView {
-    pub selected: Vec<usize>,
+    /// Selected images, kept by path so the selection survives re-sorting and
+    /// refreshes of the list
+    pub selected: HashSet<PathBuf>,
+    /// Where Shift+click and Shift+arrows extend the selection from
+    pub selection_anchor: Option<PathBuf>,
     pub cols: usize,
     pub row_height: f32,
     pub focused_index: Option<usize>,
-    pub viewport: Option<cosmic::iced::widget::scrollable::Viewport>,
+    /// Focused folder tile; takes precedence over `focused_index`
+    pub focused_folder: Option<usize>,
+    pub viewport: Option<Viewport>,
+    /// Direction of the last scroll, to load thumbnails ahead of it
+    pub scrolling_down: bool,
+    pub filter_query: String,
+    pub filter_visible: bool,
+    /// Focused image before filtering started, restored when the filter is cleared
+    pub filter_prev_focus: Option<PathBuf>,
+    /// Dimensions and capture dates read for the details list
+    details: HashMap<PathBuf, ImageDetails>,
+    /// Images whose details are being read
+    details_requested: HashSet<PathBuf>,
+    /// Timeline sections folded away
+    collapsed_groups: HashSet<GroupKey>,
+    /// Images edited and saved in this session
+    edited: HashSet<PathBuf>,
+    /// Images of the listing that failed to load
+    unreadable: HashSet<PathBuf>,
+    /// How many of those each folder has, for the status bar
+    unreadable_counts: BTreeMap<PathBuf, usize>,
 }
 
 impl GalleryView {
     pub const SCROLL_ID: &'static str = "gallery-scroll";
+    pub const FILTER_ID: &'static str = "gallery-filter";
 
     pub fn new() -> Self {
         Self {
-            selected: Vec::new(),
+            selected: HashSet::new(),
+            selection_anchor: None,
             cols: 4,
             row_height: 4.0,
             focused_index: None,
+            focused_folder: None,
             viewport: None,
+            scrolling_down: true,
+            filter_query: String::new(),
+            filter_visible: false,
+            filter_prev_focus: None,
+            details: HashMap::new(),
+            details_requested: HashSet::new(),
+            collapsed_groups: HashSet::new(),
+            edited: HashSet::new(),
+            unreadable: HashSet::new(),
+            unreadable_counts: BTreeMap::new(),
+        }
+    }
+
+    /// Rows past the visible ones to load in the scroll direction
+    const LOAD_AHEAD_ROWS: usize = 6;
+    /// Rows past the visible ones to load against the scroll direction
+    const LOAD_BEHIND_ROWS: usize = 2;
+    /// Tiles to load before the grid has been measured
+    const UNMEASURED_TILES: usize = 120;
+
+    pub fn set_viewport(&mut self, viewport: Viewport) {
+        if let Some(previous) = self.viewport {
+            let (previous, current) = (previous.absolute_offset().y, viewport.absolute_offset().y);
+            if current != previous {
+                self.scrolling_down = current > previous;
+            }
+        }
+        self.viewport = Some(viewport);
+    }
+
+    /// Folder tiles ("..", then subfolders) shown before the images; hidden
+    /// while filtering by filename
+    pub fn folder_tile_count(nav: &NavState) -> usize {
+        if nav.is_filtered() {
+            0
+        } else {
+            usize::from(nav.parent_dir().is_some()) + nav.folders().len()
+        }
+    }
+
+    /// Grid tiles whose thumbnails should load now, most urgent first: the
+    /// ones on screen, then rows ahead in the scroll direction, then a few
+    /// rows behind
+    pub fn tiles_to_load(
+        &self,
+        nav: &NavState,
+        cache: &ImageCache,
+        config: &ViewerConfig,
+    ) -> Vec<usize> {
+        let thumbnail_size = config.thumbnail_size.pixels();
+        let folder_tiles = Self::folder_tile_count(nav);
+        let tile_count = folder_tiles + nav.total();
+
+        let Some(viewport) = self.viewport else {
+            return (0..tile_count.min(Self::UNMEASURED_TILES)).collect();
+        };
+
+        // Must match the spacing and captions `view` gives the grid
+        let spacing = theme::active().cosmic().spacing;
+        let padding = Padding::from(spacing.space_s);
+        let labelled = Self::is_labelled(nav);
+
+        if Self::is_grouped(config) {
+            let layout = self.timeline_layout(nav, cache, config, viewport.bounds().width);
+            let visible =
+                layout.visible_rows(viewport.absolute_offset().y, viewport.bounds().height);
+
+            return layout.items_by_priority(
+                visible,
+                self.scrolling_down,
+                Self::LOAD_AHEAD_ROWS,
+                Self::LOAD_BEHIND_ROWS,
+            );
+        }
+
+        if config.gallery_layout == GalleryLayout::Justified {
+            let layout = justified_metrics(
+                viewport.bounds().width,
+                &Self::tile_aspects(nav, cache),
+                thumbnail_size,
+                spacing.space_xs,
+                padding,
+                labelled,
+            );
+            let visible =
+                layout.visible_rows(viewport.absolute_offset().y, viewport.bounds().height);
+
+            return layout.items_by_priority(
+                visible,
+                self.scrolling_down,
+                Self::LOAD_AHEAD_ROWS,
+                Self::LOAD_BEHIND_ROWS,
+            );
+        }
+
+        // The details list has no space between its rows
+        let (metrics, row_spacing) = if config.gallery_layout == GalleryLayout::List {
+            (list_metrics(tile_count), 0.0)
+        } else {
+            let metrics = grid_metrics(
+                viewport.bounds().width,
+                tile_count,
+                thumbnail_size,
+                spacing.space_xs,
+                padding,
+                labelled,
+            );
+            (metrics, spacing.space_xs as f32)
+        };
+        let visible = core::visible_rows(
+            &metrics,
+            row_spacing,
+            padding.top,
+            viewport.absolute_offset().y,
+            viewport.bounds().height,
+        );
+
+        core::items_by_priority(
+            &metrics,
+            tile_count,
+            visible,
+            self.scrolling_down,
+            Self::LOAD_AHEAD_ROWS,
+            Self::LOAD_BEHIND_ROWS,
+        )
+    }
+
+    /// Whether the grid shows captions, as it does once any tile has one
+    fn is_labelled(nav: &NavState) -> bool {
+        Self::folder_tile_count(nav) > 0
+            || nav
+                .get(0)
+                .is_some_and(|path| nav.relative_path(path).is_some())
+    }
+
+    /// Aspect ratios of every tile, the same ones `view` gives the grid
+    fn tile_aspects(nav: &NavState, cache: &ImageCache) -> Vec<f32> {
+        std::iter::repeat_n(1.0, Self::folder_tile_count(nav))
+            .chain((0..nav.total()).map(|idx| Self::image_aspect(nav, cache, idx).unwrap_or(1.0)))
+            .collect()
+    }
+
+    /// Whether the gallery is grouped by date; the details list never is
+    fn is_grouped(config: &ViewerConfig) -> bool {
+        config.date_grouping != DateGrouping::Off && config.gallery_layout != GalleryLayout::List
+    }
+
+    /// Timeline sections: folder tiles, then a run of images for each day,
+    /// month or year. The scan sorts by capture date while grouping, so every
+    /// date is one run.
+    fn date_groups(nav: &NavState, grouping: DateGrouping) -> Vec<DateGroup> {
+        let folder_tiles = Self::folder_tile_count(nav);
+        let mut groups = Vec::new();
+        if folder_tiles > 0 {
+            groups.push(DateGroup {
+                key: GroupKey::Folders,
+                tiles: 0..folder_tiles,
+            });
+        }
+
+        for (idx, entry) in nav.entries().iter().enumerate() {
+            let key = entry
+                .date_taken()
+                .and_then(|time| group_date(time, grouping))
+                .map_or(GroupKey::Undated, GroupKey::Date);
+            let tile = folder_tiles + idx;
+
+            match groups.last_mut() {
+                Some(group) if group.key == key => group.tiles.end = tile + 1,
+                _ => groups.push(DateGroup {
+                    key,
+                    tiles: tile..tile + 1,
+                }),
+            }
+        }
+
+        groups
+    }
+
+    /// Where the timeline puts its headers and tiles, the same way the grid
+    /// lays them out
+    fn timeline_layout(
+        &self,
+        nav: &NavState,
+        cache: &ImageCache,
+        config: &ViewerConfig,
+        width: f32,
+    ) -> core::JustifiedLayout {
+        let spacing = theme::active().cosmic().spacing;
+        let sections: Vec<core::Section> = Self::date_groups(nav, config.date_grouping)
+            .into_iter()
+            .map(|group| core::Section {
+                collapsed: self.collapsed_groups.contains(&group.key),
+                items: group.tiles,
+            })
+            .collect();
+        let aspects = (config.gallery_layout == GalleryLayout::Justified)
+            .then(|| Self::tile_aspects(nav, cache));
+
+        sectioned_metrics(
+            width,
+            &sections,
+            aspects.as_deref(),
+            config.thumbnail_size.pixels(),
+            spacing.space_xs,
+            Padding::from(spacing.space_s),
+            Self::is_labelled(nav),
+        )
+    }
+
+    /// Marks for the date scrubber: each year, and each month when grouping
+    /// by day or month, at the header of its first section
+    fn scrubber_marks(
+        groups: &[DateGroup],
+        layout: &core::JustifiedLayout,
+        grouping: DateGrouping,
+    ) -> Vec<ScrubberMark> {
+        let mut marks: Vec<ScrubberMark> = Vec::new();
+        let mut last: Option<NaiveDate> = None;
+
+        for (group, header) in groups.iter().zip(&layout.headers) {
+            let GroupKey::Date(date) = group.key else {
+                continue;
+            };
+            let major = last.is_none_or(|last| last.year() != date.year());
+            let minor = grouping != DateGrouping::Year
+                && last.is_some_and(|last| last.month() != date.month());
+            if !major && !minor {
+                continue;
+            }
+
+            last = Some(date);
+            marks.push(ScrubberMark {
+                fraction: header.y / layout.height.max(1.0),
+                label: date.format(if major { "%Y" } else { "%b" }).to_string(),
+                major,
+                offset: header.y,
+            });
+        }
+
+        marks
+    }
+
+    /// Fold a timeline section away, or show it again
+    pub fn toggle_group(&mut self, nav: &NavState, config: &ViewerConfig, index: usize) {
+        if let Some(group) = Self::date_groups(nav, config.date_grouping).get(index)
+            && !self.collapsed_groups.remove(&group.key)
+        {
+            self.collapsed_groups.insert(group.key);
+        }
+    }
+
+    /// Add the images of a timeline section to the selection
+    pub fn select_group(&mut self, nav: &NavState, config: &ViewerConfig, index: usize) {
+        let groups = Self::date_groups(nav, config.date_grouping);
+        let Some(group) = groups.get(index) else {
+            return;
+        };
+
+        let folder_tiles = Self::folder_tile_count(nav);
+        self.selected.extend(
+            group
+                .tiles
+                .clone()
+                .filter_map(|tile| nav.get(tile.checked_sub(folder_tiles)?).cloned()),
+        );
+    }
+
+    /// Where tile `tile` is in the grid, once the grid has been measured
+    fn tile_rect(
+        &self,
+        nav: &NavState,
+        cache: &ImageCache,
+        config: &ViewerConfig,
+        tile: usize,
+    ) -> Option<Rectangle> {
+        let width = self.viewport?.bounds().width;
+        let spacing = theme::active().cosmic().spacing;
+        let padding = Padding::from(spacing.space_s);
+        let thumbnail_size = config.thumbnail_size.pixels();
+
+        if Self::is_grouped(config) {
+            return self
+                .timeline_layout(nav, cache, config, width)
+                .cells
+                .get(tile)
+                .copied();
+        }
+
+        match config.gallery_layout {
+            GalleryLayout::List => None,
+            GalleryLayout::Justified => justified_metrics(
+                width,
+                &Self::tile_aspects(nav, cache),
+                thumbnail_size,
+                spacing.space_xs,
+                padding,
+                Self::is_labelled(nav),
+            )
+            .cells
+            .get(tile)
+            .copied(),
+            GalleryLayout::Grid => {
+                let metrics = grid_metrics(
+                    width,
+                    Self::folder_tile_count(nav) + nav.total(),
+                    thumbnail_size,
+                    spacing.space_xs,
+                    padding,
+                    Self::is_labelled(nav),
+                );
+                let row = (tile / metrics.cols.max(1)) as f32;
+                let y = padding.top + row * (metrics.row_height + spacing.space_xs as f32);
+                Some(Rectangle::new(
+                    Point::new(padding.left, y),
+                    Size::new(width - padding.horizontal(), metrics.row_height),
+                ))
+            }
         }
     }
 
-    pub fn toggle_selection(&mut self, idx: usize) {
-        if let Some(pos) = self.selected.iter().position(|&i| i == idx) {
-            self.selected.remove(pos);
+    /// Scroll offset that keeps the item under the cursor in place after
+    /// Ctrl+wheel resized the thumbnails; `config` has the new size
+    pub fn zoom_offset(
+        &self,
+        nav: &NavState,
+        cache: &ImageCache,
+        config: &ViewerConfig,
+        request: ZoomRequest,
+    ) -> Option<f32> {
+        let anchor = request.anchor?;
+        let cell = self.tile_rect(nav, cache, config, anchor.index)?;
+        Some((cell.y + anchor.fraction * cell.height - anchor.view_y).max(0.0))
+    }
+
+    /// Width over height of the image at `idx`, from the scan so rows keep
+    /// their shape while thumbnails load, or else from its thumbnail
+    fn image_aspect(nav: &NavState, cache: &ImageCache, idx: usize) -> Option<f32> {
+        let entry = nav.entry(idx)?;
+        entry
+            .dimensions
+            .filter(|(_, height)| *height > 0)
+            .map(|(width, height)| width as f32 / height as f32)
+            .or_else(|| {
+                cache
+                    .get_thumbnail(&entry.path)
+                    .as_ref()
+                    .and_then(handle_aspect)
+            })
+    }
+
+    /// Rows of the details list on screen, plus the ones loaded ahead of
+    /// scrolling either way
+    fn list_rows_near_view(&self, tile_count: usize) -> Range<usize> {
+        let Some(viewport) = self.viewport else {
+            return 0..tile_count.min(Self::UNMEASURED_TILES);
+        };
+
+        let padding = theme::active().cosmic().spacing.space_s as f32;
+        let visible = core::visible_rows(
+            &list_metrics(tile_count),
+            0.0,
+            padding,
+            viewport.absolute_offset().y,
+            viewport.bounds().height,
+        );
+
+        visible.start.saturating_sub(Self::LOAD_AHEAD_ROWS)
+            ..(visible.end + Self::LOAD_AHEAD_ROWS).min(tile_count)
+    }
+
+    /// Images near the view whose details haven't been read, or changed
+    /// since, when the details list or the badges show them; they count as
+    /// being read from now on
+    pub fn details_to_read(
+        &mut self,
+        nav: &NavState,
+        cache: &ImageCache,
+        config: &ViewerConfig,
+    ) -> Vec<PathBuf> {
+        let fields = DetailFields::from_config(config);
+        let folder_tiles = Self::folder_tile_count(nav);
+        let tiles: Vec<usize> = if config.gallery_layout == GalleryLayout::List {
+            self.list_rows_near_view(folder_tiles + nav.total())
+                .collect()
+        } else if fields.any() {
+            self.tiles_to_load(nav, cache, config)
         } else {
-            self.selected.push(idx);
+            return Vec::new();
+        };
+
+        let paths: Vec<PathBuf> = tiles
+            .into_iter()
+            .filter_map(|tile| nav.entry(tile.checked_sub(folder_tiles)?))
+            .filter(|entry| {
+                !self
+                    .details_of(entry)
+                    .is_some_and(|details| details.fields.covers(fields))
+            })
+            .filter(|entry| !self.details_requested.contains(&entry.path))
+            .map(|entry| entry.path.clone())
+            .collect();
+
+        self.details_requested.extend(paths.iter().cloned());
+        paths
+    }
+
+    pub fn set_details(&mut self, details: Vec<(PathBuf, ImageDetails)>) {
+        for (path, details) in details {
+            self.details_requested.remove(&path);
+            self.details.insert(path, details);
         }
     }
 
+    pub fn mark_edited(&mut self, path: PathBuf) {
+        self.edited.insert(path);
+    }
+
+    /// Badges over an image's thumbnail, those the settings show
+    fn tile_badges(
+        &self,
+        entry: &ImageEntry,
+        config: &ViewerConfig,
+        thumbnails: &ThumbnailScheduler,
+        edit_state: &EditState,
+    ) -> TileBadges {
+        let shown = config.thumbnail_badges;
+        let details = self.details_of(entry);
+
+        let format = shown.format.then(|| {
+            let extension = entry
+                .path
+                .extension()
+                .map(|ext| ext.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+            let kind = if nav::RAW_EXTENSIONS.contains(&extension.as_str()) {
+                "RAW".to_string()
+            } else {
+                extension.to_uppercase()
+            };
+
+            if entry.size >= LARGE_FILE_SIZE {
+                format!("{kind} · {}", format_size(entry.size))
+            } else {
+                kind
+            }
+        });
+        // Unsaved edits count too
+        let edited = self.edited.contains(&entry.path)
+            || (edit_state.is_modified && edit_state.original_path.as_ref() == Some(&entry.path));
+
+        TileBadges {
+            format: format.filter(|format| !format.is_empty()),
+            animated: shown.animation && details.is_some_and(|details| details.animated),
+            rating: details
+                .and_then(|details| details.rating)
+                .filter(|_| shown.rating),
+            edited: shown.edited && edited,
+            failed: shown.failed && thumbnails.failure(&entry.path).is_some(),
+        }
+    }
+
+    /// Count the images of the listing that failed to load, per folder.
+    /// Run when the listing changes; later failures and retries go through
+    /// [`Self::set_unreadable`].
+    pub fn recount_unreadable(
+        &mut self,
+        nav: &NavState,
+        cache: &ImageCache,
+        thumbnails: &ThumbnailScheduler,
+    ) {
+        let failed = cache.failed_paths();
+        self.unreadable.clear();
+        self.unreadable_counts.clear();
+
+        for entry in nav.entries() {
+            if failed.contains(&entry.path) || thumbnails.failure(&entry.path).is_some() {
+                self.set_unreadable(&entry.path, true);
+            }
+        }
+    }
+
+    /// Count `path` as failing to load, or as loading again after a retry
+    pub fn set_unreadable(&mut self, path: &Path, failed: bool) {
+        let Some(folder) = path.parent() else {
+            return;
+        };
+
+        if failed && self.unreadable.insert(path.to_path_buf()) {
+            *self
+                .unreadable_counts
+                .entry(folder.to_path_buf())
+                .or_default() += 1;
+        } else if !failed
+            && self.unreadable.remove(path)
+            && let Some(count) = self.unreadable_counts.get_mut(folder)
+        {
+            *count -= 1;
+            if *count == 0 {
+                self.unreadable_counts.remove(folder);
+            }
+        }
+    }
+
+    /// Details read for `entry`, unless the file changed since
+    fn details_of(&self, entry: &ImageEntry) -> Option<&ImageDetails> {
+        self.details
+            .get(&entry.path)
+            .filter(|details| details.modified == entry.modified)
+    }
+
+    /// Cells of an image's row in the details list, in header order
+    fn detail_columns(&self, nav: &NavState, entry: &ImageEntry) -> Vec<String> {
+        let details = self.details_of(entry);
+        let name = match nav.relative_path(&entry.path) {
+            Some(relative) => relative.to_string_lossy().into_owned(),
+            None => entry.file_name().to_string(),
+        };
+        let dimensions = details
+            .and_then(|details| details.dimensions)
+            .or(entry.dimensions)
+            .map(|(width, height)| format!("{width} × {height}"))
+            .unwrap_or_default();
+        let kind = entry
+            .path
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_uppercase())
+            .unwrap_or_default();
+        let taken = details.and_then(|details| details.taken).or(entry.taken);
+
+        vec![
+            name,
+            dimensions,
+            format_size(entry.size),
+            kind,
+            entry.modified.map(format_system_time).unwrap_or_default(),
+            taken.map(format_system_time).unwrap_or_default(),
+        ]
+    }
+
+    /// Column headers of the details list; clicking one sorts by it, or
+    /// reverses the order when the list is already sorted by it
+    fn details_header(&self, config: &ViewerConfig) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let header = |mode: SortMode, width: Length| {
+            let sorted = config.sort_mode == mode;
+            let message = if sorted {
+                SettingsMessage::SortOrder(config.sort_order.toggle())
+            } else {
+                SettingsMessage::SortMode(mode)
+            };
+            let arrow = sorted.then(|| {
+                icon::from_name(match config.sort_order {
+                    SortOrder::Ascending => "pan-up-symbolic",
+                    SortOrder::Descending => "pan-down-symbolic",
+                })
+                .size(12)
+            });
+
+            button::custom(
+                row()
+                    .push(text::caption_heading(column_title(mode)))
+                    .push_maybe(arrow)
+                    .spacing(spacing.space_xxxs)
+                    .align_y(Alignment::Center),
+            )
+            .class(theme::Button::Text)
+            .padding([spacing.space_xxs, 0])
+            .width(width)
+            .on_press(Message::Settings(message))
+        };
+
+        // Lined up with the columns the grid draws below
+        container(
+            row()
+                .push(Space::with_width(Length::Fixed(LIST_ROW_HEIGHT)))
+                .push(header(SortMode::Name, Length::Fill))
+                .extend(
+                    DETAIL_COLUMNS
+                        .iter()
+                        .map(|&(mode, width)| header(mode, Length::Fixed(width)).into()),
+                )
+                .spacing(spacing.space_xs)
+                .align_y(Alignment::Center),
+        )
+        .padding([0, spacing.space_s])
+        .width(Length::Fill)
+        .into()
+    }
+
+    pub fn toggle_selection(&mut self, path: &Path) {
+        if !self.selected.remove(path) {
+            self.selected.insert(path.to_path_buf());
+        }
+        self.selection_anchor = Some(path.to_path_buf());
+    }
+
+    /// Apply a change made in the grid; indices are image indices. Ranges start
+    /// at the anchor, or the focused image when nothing was clicked yet.
+    pub fn change_selection(&mut self, nav: &NavState, change: SelectionChange) {
+        match change {
+            SelectionChange::Toggle(idx) => {
+                if let Some(path) = nav.get(idx) {
+                    self.toggle_selection(path);
+                }
+            }
+            SelectionChange::Extend(idx) => {
+                let anchor = self
+                    .selection_anchor
+                    .as_ref()
+                    .and_then(|path| nav.position(path))
+                    .or(self.focused_index)
+                    .unwrap_or(idx);
+                let range = anchor.min(idx)..=anchor.max(idx);
+
+                self.selected = range.filter_map(|i| nav.get(i).cloned()).collect();
+                self.selection_anchor = nav.get(anchor).cloned();
+            }
+            SelectionChange::Set(indices) => {
+                self.selected = indices
+                    .iter()
+                    .filter_map(|&i| nav.get(i).cloned())
+                    .collect();
+            }
+        }
+    }
+
+    pub fn select_all(&mut self, nav: &NavState) {
+        self.selected = nav.images().into_iter().collect();
+    }
+
     pub fn clear_selection(&mut self) {
         self.selected.clear();
+        self.selection_anchor = None;
     }
 
-    pub fn is_selected(&self, idx: usize) -> bool {
-        self.selected.contains(&idx)
+    /// Keep renamed images selected under their new paths
+    pub fn rename_selection(&mut self, renamed: &[(PathBuf, PathBuf)]) {
+        let renamed: HashMap<&PathBuf, &PathBuf> =
+            renamed.iter().map(|(from, to)| (from, to)).collect();
+        let new_path = |path: PathBuf| match renamed.get(&path) {
+            Some(to) => (*to).clone(),
+            None => path,
+        };
+
+        self.selected = std::mem::take(&mut self.selected)
+            .into_iter()
+            .map(new_path)
+            .collect();
+        self.selection_anchor = self.selection_anchor.take().map(new_path);
+        self.edited = std::mem::take(&mut self.edited)
+            .into_iter()
+            .map(new_path)
+            .collect();
+    }
+
+    pub fn is_selected(&self, path: &Path) -> bool {
+        self.selected.contains(path)
+    }
+
+    /// Selected images that are still listed (not deleted or filtered out),
+    /// in gallery order
+    pub fn selected_paths(&self, nav: &NavState) -> Vec<PathBuf> {
+        if self.selected.is_empty() {
+            return Vec::new();
+        }
+
+        nav.entries()
+            .iter()
+            .filter(|entry| self.selected.contains(&entry.path))
+            .map(|entry| entry.path.clone())
+            .collect()
+    }
+
+    fn filter_bar(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        container(
+            search_input(fl!("filter-placeholder"), self.filter_query.as_str())
+                .id(Id::new(Self::FILTER_ID))
+                .on_input(|query| Message::Nav(NavMessage::FilterChanged(query)))
+                .on_clear(Message::Nav(NavMessage::ClearFilter))
+                .width(Length::Fill),
+        )
+        .padding([spacing.space_xxs, spacing.space_s])
+        .width(Length::Fill)
+        .into()
     }
 
     fn modal_loading(&self) -> Element<'static, Message> {
         let spacing = theme::active().cosmic().spacing;
 
+        self.modal_placeholder(
+            column()
+                .push(icon::from_name("content-loading-symbolic").size(48))
+                .push(text("Loading...").size(14))
+                .spacing(spacing.space_s)
+                .align_x(Alignment::Center)
+                .into(),
+        )
+    }
+
+    /// Why the image couldn't be loaded, with ways to get at it anyway
+    fn modal_error(&self, path: &Path, error: String) -> Element<'static, Message> {
+        let spacing = theme::active().cosmic().spacing;
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let buttons = row()
+            .push(
+                button::standard(fl!("load-error-retry"))
+                    .on_press(Message::Image(ImageMessage::Retry(path.to_path_buf()))),
+            )
+            .push(
+                button::standard(fl!("load-error-open-with"))
+                    .on_press(Message::OpenWith(path.to_path_buf())),
+            )
+            .spacing(spacing.space_s);
+
+        self.modal_placeholder(
+            column()
+                .push(icon::from_name("image-missing-symbolic").size(48))
+                .push(text::title4(fl!("load-error-title", name = name)))
+                .push(text::body(error))
+                .push(buttons)
+                .spacing(spacing.space_s)
+                .align_x(Alignment::Center)
+                .into(),
+        )
+    }
+
+    /// Modal frame around something shown in place of the image
+    fn modal_placeholder(&self, body: Element<'static, Message>) -> Element<'static, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
         let close_btn = button::icon(icon::from_name("window-close-symbolic"))
             .on_press(Message::View(ViewMessage::CloseModal))
             .padding(spacing.space_xs)
@@ -73,23 +864,18 @@ impl GalleryView {
             .width(Length::Fill)
             .padding(spacing.space_xs);
 
-        let loading = container(
-            column()
-                .push(icon::from_name("content-loading-symbolic").size(48))
-                .push(text("Loading...").size(14))
-                .spacing(spacing.space_s)
-                .align_x(Alignment::Center),
-        )
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .center(Length::Fill);
+        let body = container(body)
+            .padding(spacing.space_m)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center(Length::Fill);
 
         container(
             mouse_area(
                 container(
                     column()
                         .push(header)
-                        .push(loading)
+                        .push(body)
                         .width(Length::Fill)
                         .height(Length::Fill),
                 )
@@ -284,14 +1070,17 @@ impl GalleryView {
         &self,
         nav: &NavState,
         cache: &ImageCache,
-        thumbnail_size: u32,
+        thumbnails: &ThumbnailScheduler,
+        config: &ViewerConfig,
         image_state: &ImageViewState,
         edit_state: &EditState,
     ) -> Element<'_, Message> {
         let spacing = theme::active().cosmic().spacing;
+        let thumbnail_size = config.thumbnail_size.pixels();
+        let list = config.gallery_layout == GalleryLayout::List;
         let images = nav.images();
 
-        if images.is_empty() {
+        if nav.total_unfiltered() == 0 && nav.folders().is_empty() {
             return container(
                 column()
                     .push(icon::from_name("folder-pictures-symbolic").size(64))
@@ -304,37 +1093,217 @@ impl GalleryView {
             .into();
         }
 
+        let show_filter = self.filter_visible || nav.is_filtered();
+
+        if images.is_empty() && nav.is_filtered() {
+            // Everything is hidden by the filter
+            return column()
+                .push_maybe(show_filter.then(|| self.filter_bar()))
+                .push(container(text(fl!("filter-no-matches")).size(14)).center(Length::Fill))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into();
+        }
+
+        // Folder tiles come first; they are hidden while filtering by filename
+        let mut items: Vec<GalleryItem> = Vec::new();
+        if !nav.is_filtered() {
+            if let Some(parent) = nav.parent_dir() {
+                items.push(
+                    GalleryItem::parent(parent.to_path_buf())
+                        .label("..")
+                        .columns(vec!["..".to_string()]),
+                );
+            }
+
+            items.extend(nav.folders().iter().map(|folder| {
+                let previews = folder
+                    .previews
+                    .iter()
+                    .filter_map(|path| cache.get_thumbnail(path))
+                    .collect();
+                let name = folder_tree::display_name(&folder.path);
+                GalleryItem::folder(folder.path.clone(), previews)
+                    .columns(vec![
+                        name.clone(),
+                        String::new(),
+                        String::new(),
+                        fl!("details-type-folder"),
+                    ])
+                    .label(name)
+            }));
+        }
+
+        // Grid indices past the folder tiles map onto the image list
+        let offset = items.len();
+        let folder_paths: Vec<PathBuf> = items.iter().map(|item| item.path.clone()).collect();
+
+        // Only rows of the details list near the view get their text, so a
+        // long list stays quick to rebuild while scrolling
+        let described = if list {
+            self.list_rows_near_view(offset + images.len())
+        } else {
+            0..0
+        };
+
         // Build gallery items
-        let items: Vec<GalleryItem> = images
-            .iter()
-            .map(|path| GalleryItem::new(path.clone(), cache.get_thumbnail(path)))
-            .collect();
+        items.extend(nav.entries().iter().enumerate().map(|(idx, entry)| {
+            let path = &entry.path;
+            let item = GalleryItem::new(path.clone(), cache.get_thumbnail(path))
+                .aspect(Self::image_aspect(nav, cache, idx))
+                .badges(self.tile_badges(entry, config, thumbnails, edit_state))
+                .error(thumbnails.failure(path).map(str::to_string));
+            let item = if described.contains(&(idx + offset)) {
+                item.columns(self.detail_columns(nav, entry))
+            } else {
+                item
+            };
+            // With subfolders included, show where each image lives
+            match nav.relative_path(path) {
+                Some(relative) => item.label(relative.to_string_lossy()),
+                None => item,
+            }
+        }));
+
+        let focused = self
+            .focused_folder
+            .or(self.focused_index.map(|idx| idx + offset));
 
         // Disable keyboard nav when modal is open (modal handles arrow keys)
         let modal_open = nav.index().is_some();
 
-        let content = gallery_grid(items)
+        let grid = gallery_grid(items)
             .thumbnail_size(thumbnail_size)
-            .focused(self.focused_index)
-            .selected(self.selected.clone())
+            .justified(config.gallery_layout == GalleryLayout::Justified);
+        let grid = if list {
+            grid.list(DETAIL_COLUMNS.iter().map(|&(_, width)| width).collect())
+        } else {
+            grid
+        };
+
+        let groups = if Self::is_grouped(config) {
+            Self::date_groups(nav, config.date_grouping)
+        } else {
+            Vec::new()
+        };
+        let grid = if groups.is_empty() {
+            grid
+        } else {
+            grid.sections(
+                groups
+                    .iter()
+                    .map(|group| GallerySection {
+                        title: group_title(group, config.date_grouping),
+                        items: group.tiles.clone(),
+                        collapsed: self.collapsed_groups.contains(&group.key),
+                    })
+                    .collect(),
+            )
+            .on_section_toggle(|index| Message::View(ViewMessage::ToggleGroup(index)))
+            .on_section_select(fl!("timeline-select-all"), |index| {
+                Message::View(ViewMessage::SelectGroup(index))
+            })
+        };
+
+        let content = grid
+            .focused(focused)
+            .selected(
+                images
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, path)| self.is_selected(path))
+                    .map(|(idx, _)| idx + offset)
+                    .collect(),
+            )
+            .selectable_from(offset)
             .spacing(spacing.space_xs)
             .padding(spacing.space_s)
             .scrollable(Id::new(Self::SCROLL_ID))
             .keyboard_navigation(!modal_open)
-            .on_focus(|idx| Message::Nav(NavMessage::GalleryFocus(idx)))
-            .on_activate(|idx| Message::Nav(NavMessage::GallerySelect(idx)))
+            .on_focus(move |idx| {
+                if idx < offset {
+                    Message::Nav(NavMessage::FolderFocus(idx))
+                } else {
+                    Message::Nav(NavMessage::GalleryFocus(idx - offset))
+                }
+            })
+            .on_activate(move |idx| match folder_paths.get(idx) {
+                Some(path) => Message::OpenPath(path.clone()),
+                None => Message::Nav(NavMessage::GallerySelect(idx - offset)),
+            })
+            .on_select(move |change| {
+                Message::View(ViewMessage::Select(match change {
+                    SelectionChange::Toggle(idx) => SelectionChange::Toggle(idx - offset),
+                    SelectionChange::Extend(idx) => SelectionChange::Extend(idx - offset),
+                    SelectionChange::Set(indices) => {
+                        SelectionChange::Set(indices.into_iter().map(|idx| idx - offset).collect())
+                    }
+                }))
+            })
             .on_scroll_request(|req| {
                 Message::View(ViewMessage::GalleryScrollTo(req.offset_y))
             })
+            .on_scroll(|viewport| Message::View(ViewMessage::GalleryScrolled(viewport)))
+            .on_zoom(|request| Message::View(ViewMessage::GalleryZoom(request)))
             .into_element();
 
+        // Date scrubber beside the scrollbar, once the grid has been measured
+        let marks = match self.viewport {
+            Some(viewport) if !groups.is_empty() => {
+                let layout = self.timeline_layout(nav, cache, config, viewport.bounds().width);
+                Self::scrubber_marks(&groups, &layout, config.date_grouping)
+            }
+            _ => Vec::new(),
+        };
+        let content: Element<'_, Message> = if marks.len() > 1 {
+            row()
+                .push(content)
+                .push(scrubber(marks))
+                .width(Length::Fill)
+                .height(Length::Fill)
+                .into()
+        } else {
+            content
+        };
+
         // Status bar
+        let count = if nav.is_filtered() {
+            format!("{} of {} images", images.len(), nav.total_unfiltered())
+        } else {
+            format!("{} images", images.len())
+        };
+
+        // Files that couldn't be read, per folder when subfolders are included
+        let unreadable: Vec<String> = self
+            .unreadable_counts
+            .iter()
+            .map(|(folder, &count)| {
+                if nav.dir() == Some(folder) {
+                    fl!("status-unreadable", count = count)
+                } else {
+                    let folder = folder_tree::display_name(&folder);
+                    fl!("status-unreadable-in", folder = folder, count = count)
+                }
+            })
+            .collect();
+        let unreadable = (!unreadable.is_empty()).then(|| {
+            row()
+                .push(icon::from_name("dialog-warning-symbolic").size(16))
+                .push(text(unreadable.join(" · ")).size(12))
+                .spacing(spacing.space_xxs)
+                .align_y(Alignment::Center)
+        });
+
         let status = row()
-            .push(text(format!("{} images", images.len())).size(12))
+            .push(text(count).size(12))
+            .push_maybe(unreadable)
+            .spacing(spacing.space_m)
             .padding([spacing.space_xxs, spacing.space_s])
             .align_y(Alignment::Center);
 
         let gallery: Element<'_, Message> = column()
+            .push_maybe(show_filter.then(|| self.filter_bar()))
+            .push_maybe(list.then(|| self.details_header(config)))
             .push(content)
             .push(status)
             .width(Length::Fill)
@@ -358,6 +1327,8 @@ impl GalleryView {
             // Show modal with image if cached, or loading state if not
             let modal = if let Some(cached) = cache.get_full(path) {
                 self.modal_content(&cached, image_state)
+            } else if let Some(error) = cache.failure(path) {
+                self.modal_error(path, error)
             } else {
                 self.modal_loading()
             };
@@ -370,3 +1341,41 @@ impl GalleryView {
         gallery
     }
 }
+
+fn column_title(mode: SortMode) -> String {
+    match mode {
+        SortMode::Name => fl!("details-name"),
+        SortMode::Dimensions => fl!("details-dimensions"),
+        SortMode::Size => fl!("details-size"),
+        SortMode::Type => fl!("details-type"),
+        SortMode::Date => fl!("details-modified"),
+        SortMode::DateTaken => fl!("details-taken"),
+        SortMode::Random => mode.to_string(),
+    }
+}
+
+/// The local day, month or year `time` falls in, as its first day
+fn group_date(time: SystemTime, grouping: DateGrouping) -> Option<NaiveDate> {
+    let date = chrono::DateTime::<chrono::Local>::from(time).date_naive();
+    match grouping {
+        DateGrouping::Off | DateGrouping::Day => Some(date),
+        DateGrouping::Month => date.with_day(1),
+        DateGrouping::Year => NaiveDate::from_ymd_opt(date.year(), 1, 1),
+    }
+}
+
+fn group_title(group: &DateGroup, grouping: DateGrouping) -> String {
+    let title = match group.key {
+        GroupKey::Folders => return fl!("timeline-folders"),
+        GroupKey::Undated => fl!("timeline-undated"),
+        GroupKey::Date(date) => date
+            .format(match grouping {
+                DateGrouping::Year => "%Y",
+                DateGrouping::Month => "%B %Y",
+                DateGrouping::Off | DateGrouping::Day => "%A, %B %-d, %Y",
+            })
+            .to_string(),
+    };
+
+    fl!("timeline-section", title = title, count = group.tiles.len())
+}
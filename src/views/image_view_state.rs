@@ -59,6 +59,12 @@ impl ImageViewState {
         self.fit_to_window = true;
     }
 
+    /// Start a newly opened image fitted or at a fixed zoom
+    pub fn reset_zoom(&mut self, fit_to_window: bool, zoom_level: f32) {
+        self.fit_to_window = fit_to_window;
+        self.zoom_level = zoom_level;
+    }
+
     pub fn set_window_size(&mut self, width: f32, height: f32) {
         self.window_width = width;
         self.window_height = height;
@@ -0,0 +1,283 @@
+//! Renaming from the viewer
+//!
+//! One image is renamed in a bar above the gallery. Several get a dialog
+//! that builds their names from a pattern and previews every rename, with
+//! the ones that can't be done marked.
+
+use crate::{
+    fl,
+    message::{Message, RenameMessage},
+    rename::{self, FileInfo, Pattern, PlannedRename, Problem},
+};
+use cosmic::{
+    Element,
+    iced::{Alignment, Length},
+    theme,
+    widget::{Id, Space, button, column, container, icon, row, scrollable, text, text_input},
+};
+use std::path::PathBuf;
+
+/// Rows of the batch preview; the rest are summed up as "and N more"
+const PREVIEW_ROWS: usize = 500;
+
+#[derive(Debug, Clone)]
+pub struct RenameDialog {
+    pub paths: Vec<PathBuf>,
+    /// The new name, or the pattern for a batch
+    pub input: String,
+    /// Read once the pattern first needs it
+    info: Option<Vec<FileInfo>>,
+    info_requested: bool,
+    plan: Vec<PlannedRename>,
+    /// Why the pattern doesn't parse
+    error: Option<String>,
+}
+
+impl RenameDialog {
+    pub const INPUT_ID: &'static str = "rename-input";
+
+    pub fn new(paths: Vec<PathBuf>) -> Self {
+        let input = match paths.as_slice() {
+            [path] => path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            _ => rename::DEFAULT_PATTERN.to_string(),
+        };
+
+        let mut dialog = Self {
+            paths,
+            input,
+            info: None,
+            info_requested: false,
+            plan: Vec::new(),
+            error: None,
+        };
+        dialog.update_plan();
+        dialog
+    }
+
+    pub fn is_batch(&self) -> bool {
+        self.paths.len() > 1
+    }
+
+    pub fn set_input(&mut self, input: String) {
+        self.input = input;
+        self.update_plan();
+    }
+
+    pub fn set_info(&mut self, paths: &[PathBuf], info: Vec<FileInfo>) {
+        if paths == self.paths.as_slice() {
+            self.info = Some(info);
+            self.update_plan();
+        }
+    }
+
+    /// Paths to read [`FileInfo`] for, the first time the pattern needs it
+    pub fn request_info(&mut self) -> Option<Vec<PathBuf>> {
+        if self.info_requested || !self.is_batch() {
+            return None;
+        }
+
+        let needed = Pattern::parse(&self.input).is_ok_and(|pattern| pattern.needs_info());
+        self.info_requested = needed;
+        needed.then(|| self.paths.clone())
+    }
+
+    fn update_plan(&mut self) {
+        self.error = None;
+
+        if !self.is_batch() {
+            self.plan = rename::plan(&self.paths, vec![self.input.clone()]);
+            return;
+        }
+
+        match Pattern::parse(&self.input) {
+            // The preview waits for the info rather than showing half-built names
+            Ok(pattern) if pattern.needs_info() && self.info.is_none() => self.plan.clear(),
+            Ok(pattern) => {
+                let none = FileInfo::default();
+                let names = self
+                    .paths
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, path)| {
+                        let info = self
+                            .info
+                            .as_ref()
+                            .and_then(|info| info.get(idx))
+                            .unwrap_or(&none);
+                        pattern.apply(path, idx + 1, info)
+                    })
+                    .collect();
+                self.plan = rename::plan(&self.paths, names);
+            }
+            Err(err) => {
+                self.plan.clear();
+                self.error = Some(err);
+            }
+        }
+    }
+
+    /// The renames to do, or `None` while any of them has a problem or there
+    /// is nothing to change
+    pub fn renames(&self) -> Option<Vec<(PathBuf, PathBuf)>> {
+        if self.plan.is_empty() || self.plan.iter().any(|planned| planned.problem.is_some()) {
+            return None;
+        }
+
+        let renames: Vec<_> = self
+            .plan
+            .iter()
+            .filter(|planned| !planned.is_unchanged())
+            .map(|planned| (planned.from.clone(), planned.to.clone()))
+            .collect();
+
+        (!renames.is_empty()).then_some(renames)
+    }
+
+    /// Inline editor for a single image, shown above the gallery
+    pub fn bar(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let input = text_input(fl!("rename-placeholder"), self.input.as_str())
+            .id(Id::new(Self::INPUT_ID))
+            .on_input(|input| Message::Rename(RenameMessage::Input(input)))
+            .on_submit(|_| Message::Rename(RenameMessage::Submit))
+            .width(Length::Fill);
+
+        let problem = self
+            .plan
+            .first()
+            .and_then(|planned| planned.problem)
+            .map(|problem| text::caption(problem_text(problem)));
+
+        container(
+            row()
+                .push(text::body(fl!("rename-label")))
+                .push(input)
+                .push_maybe(problem)
+                .push(
+                    button::suggested(fl!("rename-confirm")).on_press_maybe(
+                        self.renames()
+                            .map(|_| Message::Rename(RenameMessage::Submit)),
+                    ),
+                )
+                .push(
+                    button::icon(icon::from_name("window-close-symbolic"))
+                        .on_press(Message::Rename(RenameMessage::Cancel)),
+                )
+                .spacing(spacing.space_xs)
+                .align_y(Alignment::Center),
+        )
+        .padding([spacing.space_xxs, spacing.space_s])
+        .width(Length::Fill)
+        .into()
+    }
+
+    /// Pattern editor with a preview of every new name, for a batch
+    pub fn dialog(&self) -> Element<'_, Message> {
+        let spacing = theme::active().cosmic().spacing;
+
+        let input = text_input(rename::DEFAULT_PATTERN, self.input.as_str())
+            .id(Id::new(Self::INPUT_ID))
+            .on_input(|input| Message::Rename(RenameMessage::Input(input)))
+            .on_submit(|_| Message::Rename(RenameMessage::Submit))
+            .width(Length::Fill);
+
+        let preview: Element<'_, Message> = if let Some(error) = &self.error {
+            text::body(error.as_str()).into()
+        } else if self.plan.is_empty() {
+            text::body(fl!("rename-reading")).into()
+        } else {
+            let rows = self.plan.iter().take(PREVIEW_ROWS).map(|planned| {
+                let name = |path: &PathBuf| {
+                    path.file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default()
+                };
+
+                row()
+                    .push(text::body(name(&planned.from)).width(Length::FillPortion(2)))
+                    .push(icon::from_name("go-next-symbolic").size(16))
+                    .push(text::body(name(&planned.to)).width(Length::FillPortion(2)))
+                    .push(
+                        text::caption(planned.problem.map(problem_text).unwrap_or_default())
+                            .width(Length::FillPortion(1)),
+                    )
+                    .spacing(spacing.space_xs)
+                    .align_y(Alignment::Center)
+                    .into()
+            });
+
+            let more = (self.plan.len() > PREVIEW_ROWS)
+                .then(|| text::caption(fl!("rename-more", count = self.plan.len() - PREVIEW_ROWS)));
+
+            scrollable(
+                column()
+                    .extend(rows)
+                    .push_maybe(more)
+                    .spacing(spacing.space_xxxs),
+            )
+            .height(Length::Fixed(280.0))
+            .into()
+        };
+
+        let conflicts = self
+            .plan
+            .iter()
+            .filter(|planned| planned.problem.is_some())
+            .count();
+
+        let button_row = row()
+            .push(
+                button::text(fl!("rename-cancel")).on_press(Message::Rename(RenameMessage::Cancel)),
+            )
+            .push(
+                button::suggested(fl!("rename-confirm")).on_press_maybe(
+                    self.renames()
+                        .map(|_| Message::Rename(RenameMessage::Submit)),
+                ),
+            )
+            .spacing(spacing.space_s);
+
+        let content = column()
+            .push(text::title4(fl!(
+                "rename-dialog-title",
+                count = self.paths.len()
+            )))
+            .push(input)
+            .push(text::caption(fl!("rename-pattern-help")))
+            .push(Space::with_height(Length::Fixed(spacing.space_xs as f32)))
+            .push(preview)
+            .push_maybe(
+                (conflicts > 0).then(|| text::body(fl!("rename-conflicts", count = conflicts))),
+            )
+            .push(Space::with_height(Length::Fixed(spacing.space_s as f32)))
+            .push(button_row)
+            .spacing(spacing.space_xxs)
+            .align_x(Alignment::End);
+
+        let dialog_container = container(content)
+            .padding(spacing.space_m)
+            .width(Length::Fixed(640.0))
+            .class(theme::Container::Dialog);
+
+        // Center the dialog on screen
+        container(dialog_container)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(cosmic::iced::alignment::Horizontal::Center)
+            .align_y(cosmic::iced::alignment::Vertical::Center)
+            .into()
+    }
+}
+
+fn problem_text(problem: Problem) -> String {
+    match problem {
+        Problem::Empty => fl!("rename-problem-empty"),
+        Problem::Invalid => fl!("rename-problem-invalid"),
+        Problem::Duplicate => fl!("rename-problem-duplicate"),
+        Problem::Exists => fl!("rename-problem-exists"),
+    }
+}
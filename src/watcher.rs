@@ -2,8 +2,9 @@ use cosmic::{
     iced::Subscription,
 };
 use notify::{Config, Event, RecommendedWatcher, RecursiveMode, Watcher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tokio::sync::mpsc;
+use walkdir::WalkDir;
 
 #[derive(Debug, Clone)]
 pub enum WatcherEvent {
@@ -13,9 +14,15 @@ pub enum WatcherEvent {
     Error(String),
 }
 
-pub fn watch_directory(dir: Option<PathBuf>) -> Subscription<WatcherEvent> {
+/// Watch `dir` for changes, along with its subfolders down to `depth` levels,
+/// the same folders a scan with that depth lists images from
+pub fn watch_directory(
+    dir: Option<PathBuf>,
+    depth: usize,
+    include_hidden: bool,
+) -> Subscription<WatcherEvent> {
     Subscription::run_with_id(
-        dir.clone(),
+        (dir.clone(), depth, include_hidden),
         cosmic::iced::stream::channel(100, move |mut output| async move {
             use cosmic::iced_futures::futures::SinkExt;
 
@@ -43,7 +50,9 @@ pub fn watch_directory(dir: Option<PathBuf>) -> Subscription<WatcherEvent> {
                 }
             };
 
-            if let Err(e) = watcher.watch(&dir, RecursiveMode::NonRecursive) {
+            // One watch per folder rather than a recursive one, which would
+            // cover the whole tree and can run into the inotify watch limit
+            if let Err(e) = watch_tree(&mut watcher, &dir, depth, include_hidden) {
                 let _ = output.send(WatcherEvent::Error(e.to_string())).await;
                 std::future::pending::<()>().await;
                 unreachable!();
@@ -55,6 +64,15 @@ pub fn watch_directory(dir: Option<PathBuf>) -> Subscription<WatcherEvent> {
                     Ok(event) => {
                         use notify::EventKind;
                         for path in event.paths {
+                            // Folders made inside the watched levels get watched too
+                            if matches!(event.kind, EventKind::Create(_))
+                                && path.is_dir()
+                                && let Ok(below) = path.strip_prefix(&dir)
+                                && let Some(levels) = depth.checked_sub(below.components().count())
+                            {
+                                let _ = watch_tree(&mut watcher, &path, levels, include_hidden);
+                            }
+
                             let msg = match event.kind {
                                 EventKind::Create(_) => Some(WatcherEvent::Created(path)),
                                 EventKind::Remove(_) => Some(WatcherEvent::Removed(path)),
@@ -78,3 +96,29 @@ pub fn watch_directory(dir: Option<PathBuf>) -> Subscription<WatcherEvent> {
         }),
     )
 }
+
+/// Watch `dir` and the folders up to `levels` below it, each on its own
+fn watch_tree(
+    watcher: &mut RecommendedWatcher,
+    dir: &Path,
+    levels: usize,
+    include_hidden: bool,
+) -> notify::Result<()> {
+    watcher.watch(dir, RecursiveMode::NonRecursive)?;
+
+    let subfolders = WalkDir::new(dir)
+        .min_depth(1)
+        .max_depth(levels)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.file_type().is_dir()
+                && (include_hidden || !entry.file_name().to_string_lossy().starts_with('.'))
+        })
+        .filter_map(|entry| entry.ok());
+    for folder in subfolders {
+        // A folder that vanished or can't be read just goes unwatched
+        let _ = watcher.watch(folder.path(), RecursiveMode::NonRecursive);
+    }
+
+    Ok(())
+}
@@ -1,6 +1,8 @@
 pub mod crop;
 pub mod flex_grid;
+pub mod scrubber;
 
 pub use crop::{CropOverlay, CropRegion, CropSelection, CropWidget, DragHandle, crop_overlay, crop_widget};
 pub use flex_grid::{FlexGrid, ScrollRequest, flex_grid};
-pub use flex_grid::{GalleryGrid, GalleryItem, gallery_grid};
+pub use flex_grid::{GalleryGrid, GalleryItem, SelectionChange, ZoomRequest, gallery_grid};
+pub use scrubber::{Scrubber, ScrubberMark, scrubber};
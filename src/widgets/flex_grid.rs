@@ -3,4 +3,4 @@ mod widget;
 mod gallery;
 
 pub use widget::{flex_grid, FlexGrid, ScrollRequest};
-pub use gallery::{gallery_grid, GalleryGrid, GalleryItem, ScrollRequest as GalleryScrollRequest};
+pub use gallery::{gallery_grid, grid_metrics, handle_aspect, justified_metrics, list_metrics, sectioned_metrics, GalleryGrid, GalleryItem, GallerySection, LIST_ROW_HEIGHT, SECTION_HEADER_HEIGHT, ScrollRequest as GalleryScrollRequest, SelectionChange, TileBadges, ZoomAnchor, ZoomRequest};
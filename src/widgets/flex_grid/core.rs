@@ -1,6 +1,7 @@
 //! Shared grid layout utilities
 
-use cosmic::iced::{Padding, Rectangle};
+use cosmic::iced::{Padding, Point, Rectangle, Size};
+use std::ops::Range;
 
 /// Configuration for grid layout calculation
 #[derive(Debug, Clone)]
@@ -77,6 +78,358 @@ pub fn calculate_scroll_offset(
     }
 }
 
+/// Rows that overlap the visible part of the grid
+pub fn visible_rows(
+    metrics: &GridMetrics,
+    row_spacing: f32,
+    padding_top: f32,
+    viewport_top: f32,
+    viewport_height: f32,
+) -> Range<usize> {
+    let cell_height = metrics.row_height + row_spacing;
+    if metrics.rows == 0 || cell_height <= 0.0 {
+        return 0..0;
+    }
+
+    let first = ((viewport_top - padding_top) / cell_height)
+        .floor()
+        .max(0.0) as usize;
+    let last = ((viewport_top + viewport_height - padding_top) / cell_height)
+        .ceil()
+        .max(0.0) as usize;
+
+    first.min(metrics.rows)..last.min(metrics.rows)
+}
+
+/// Item indices in the order they matter to the user: the visible rows, then
+/// up to `ahead` rows in the scroll direction, then up to `behind` rows the
+/// other way, nearest first. Rows further out are left off.
+pub fn items_by_priority(
+    metrics: &GridMetrics,
+    item_count: usize,
+    visible: Range<usize>,
+    scrolling_down: bool,
+    ahead: usize,
+    behind: usize,
+) -> Vec<usize> {
+    if metrics.cols == 0 {
+        return Vec::new();
+    }
+
+    rows_by_priority(metrics.rows, visible, scrolling_down, ahead, behind)
+        .into_iter()
+        .flat_map(|row| row * metrics.cols..((row + 1) * metrics.cols).min(item_count))
+        .collect()
+}
+
+/// Row indices in the order [`items_by_priority`] loads them
+fn rows_by_priority(
+    rows: usize,
+    visible: Range<usize>,
+    scrolling_down: bool,
+    ahead: usize,
+    behind: usize,
+) -> Vec<usize> {
+    let (below, above) = if scrolling_down {
+        (ahead, behind)
+    } else {
+        (behind, ahead)
+    };
+
+    let below = visible.end..(visible.end + below).min(rows);
+    let above = (visible.start.saturating_sub(above)..visible.start).rev();
+
+    if scrolling_down {
+        visible.chain(below).chain(above).collect()
+    } else {
+        visible.chain(above).chain(below).collect()
+    }
+}
+
+/// Aspect ratios are clamped to this range so a panorama or a sliver can't
+/// take a whole row or vanish
+const JUSTIFIED_ASPECT_RANGE: (f32, f32) = (0.25, 4.0);
+
+/// Configuration for a justified layout
+#[derive(Debug, Clone)]
+pub struct JustifiedConfig {
+    /// Height rows aim for before they are stretched to the full width
+    pub target_height: f32,
+    /// Space between a tile's edge and its image
+    pub tile_padding: f32,
+    /// Added below every image, e.g. for captions
+    pub label_height: f32,
+    pub column_spacing: f32,
+    pub row_spacing: f32,
+    pub padding: Padding,
+}
+
+/// One row of a justified layout
+#[derive(Debug, Clone, PartialEq)]
+pub struct JustifiedRow {
+    pub items: Range<usize>,
+    /// Relative to the top of the grid, padding included
+    pub top: f32,
+    /// Tile height, padding and label included
+    pub height: f32,
+}
+
+/// Tiles that keep their image's aspect ratio, in rows that fill the width
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct JustifiedLayout {
+    pub rows: Vec<JustifiedRow>,
+    /// Tile bounds by item index, relative to the grid's top left corner
+    pub cells: Vec<Rectangle>,
+    /// Total height, padding included
+    pub height: f32,
+    /// Header bands of a sectioned layout, by section
+    pub headers: Vec<Rectangle>,
+}
+
+impl JustifiedLayout {
+    /// Row holding `index`
+    pub fn row_of(&self, index: usize) -> Option<usize> {
+        self.rows.iter().position(|row| row.items.contains(&index))
+    }
+
+    /// Item whose tile contains `position`, relative to the grid
+    pub fn item_at(&self, position: Point) -> Option<usize> {
+        // Rows are sorted top to bottom, so find the last one starting above
+        let row = self.rows.partition_point(|row| row.top <= position.y);
+        let row = self.rows.get(row.checked_sub(1)?)?;
+
+        row.items
+            .clone()
+            .find(|&index| self.cells[index].contains(position))
+    }
+
+    /// Items whose tiles overlap `rect`, relative to the grid
+    pub fn items_in(&self, rect: Rectangle) -> impl Iterator<Item = usize> + '_ {
+        self.rows
+            .iter()
+            .filter(move |row| row.top <= rect.y + rect.height && row.top + row.height >= rect.y)
+            .flat_map(|row| row.items.clone())
+            .filter(move |&index| self.cells[index].intersects(&rect))
+    }
+
+    /// Item in the row above (`up`) or below `index` nearest to it horizontally
+    pub fn nearest_in_adjacent_row(&self, index: usize, up: bool) -> Option<usize> {
+        let row = self.row_of(index)?;
+        let target = if up { row.checked_sub(1)? } else { row + 1 };
+        let center = self.cells[index].center_x();
+
+        self.rows.get(target)?.items.clone().min_by(|&a, &b| {
+            let distance = |index: usize| (self.cells[index].center_x() - center).abs();
+            distance(a).total_cmp(&distance(b))
+        })
+    }
+
+    /// Rows that overlap the visible part of the grid
+    pub fn visible_rows(&self, viewport_top: f32, viewport_height: f32) -> Range<usize> {
+        let first = self
+            .rows
+            .partition_point(|row| row.top + row.height < viewport_top);
+        let last = self
+            .rows
+            .partition_point(|row| row.top <= viewport_top + viewport_height);
+
+        first..last.max(first)
+    }
+
+    /// Like [`items_by_priority`], for rows of varying length
+    pub fn items_by_priority(
+        &self,
+        visible: Range<usize>,
+        scrolling_down: bool,
+        ahead: usize,
+        behind: usize,
+    ) -> Vec<usize> {
+        rows_by_priority(self.rows.len(), visible, scrolling_down, ahead, behind)
+            .into_iter()
+            .flat_map(|row| self.rows[row].items.clone())
+            .collect()
+    }
+}
+
+/// Lay out tiles with the given aspect ratios (width / height) in rows that
+/// are stretched to fill `width`. The last row keeps the target height.
+pub fn justified_layout(aspects: &[f32], width: f32, config: &JustifiedConfig) -> JustifiedLayout {
+    let available = (width - config.padding.horizontal()).max(1.0);
+    let pad = config.tile_padding * 2.0;
+    let aspects: Vec<f32> = aspects
+        .iter()
+        .map(|aspect| {
+            if aspect.is_finite() {
+                aspect.clamp(JUSTIFIED_ASPECT_RANGE.0, JUSTIFIED_ASPECT_RANGE.1)
+            } else {
+                1.0
+            }
+        })
+        .collect();
+
+    let mut layout = JustifiedLayout {
+        rows: Vec::new(),
+        cells: Vec::with_capacity(aspects.len()),
+        height: config.padding.top,
+        headers: Vec::new(),
+    };
+    // Horizontal space a row of `count` tiles needs besides the images
+    let gaps =
+        |count: usize| count as f32 * pad + count.saturating_sub(1) as f32 * config.column_spacing;
+    let mut start = 0;
+
+    while start < aspects.len() {
+        // Take tiles until the row is as wide as the grid at the target height
+        let mut end = start;
+        let mut aspect_sum = 0.0;
+        let mut full = false;
+        while end < aspects.len() && !full {
+            aspect_sum += aspects[end];
+            end += 1;
+
+            full = aspect_sum * config.target_height + gaps(end - start) >= available;
+        }
+
+        // Full rows shrink to fit exactly; the last one keeps the target height
+        let image_height = if full {
+            ((available - gaps(end - start)) / aspect_sum).max(1.0)
+        } else {
+            config.target_height
+        };
+        let height = image_height + pad + config.label_height;
+
+        let top = layout.height;
+        let mut x = config.padding.left;
+        for aspect in &aspects[start..end] {
+            let cell_width = aspect * image_height + pad;
+            layout.cells.push(Rectangle::new(
+                Point::new(x, top),
+                Size::new(cell_width, height),
+            ));
+            x += cell_width + config.column_spacing;
+        }
+
+        layout.rows.push(JustifiedRow {
+            items: start..end,
+            top,
+            height,
+        });
+        layout.height = top + height + config.row_spacing;
+        start = end;
+    }
+
+    // No spacing after the last row, just the padding
+    if !layout.rows.is_empty() {
+        layout.height -= config.row_spacing;
+    }
+    layout.height += config.padding.bottom;
+    layout
+}
+
+/// Cells of one size in `cols` columns, as a [`JustifiedLayout`] so square
+/// grids can be combined with other layouts
+pub fn uniform_layout(
+    count: usize,
+    cols: usize,
+    cell: Size,
+    column_spacing: f32,
+    row_spacing: f32,
+    padding: Padding,
+) -> JustifiedLayout {
+    let cols = cols.max(1);
+    let mut layout = JustifiedLayout {
+        cells: Vec::with_capacity(count),
+        height: padding.top,
+        ..JustifiedLayout::default()
+    };
+
+    for start in (0..count).step_by(cols) {
+        let items = start..(start + cols).min(count);
+        let top = layout.height;
+        for col in 0..items.len() {
+            layout.cells.push(Rectangle::new(
+                Point::new(
+                    padding.left + col as f32 * (cell.width + column_spacing),
+                    top,
+                ),
+                cell,
+            ));
+        }
+
+        layout.rows.push(JustifiedRow {
+            items,
+            top,
+            height: cell.height,
+        });
+        layout.height = top + cell.height + row_spacing;
+    }
+
+    if !layout.rows.is_empty() {
+        layout.height -= row_spacing;
+    }
+    layout.height += padding.bottom;
+    layout
+}
+
+/// A run of consecutive items laid out under its own header
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section {
+    pub items: Range<usize>,
+    pub collapsed: bool,
+}
+
+/// Lay out `sections` one below the other, each under a header
+/// `header_height` tall and starting on a new row. `layout_section` lays out
+/// the items of one section from a top of zero, horizontal padding included.
+/// Items of collapsed sections get an empty tile that is in no row.
+pub fn sectioned_layout(
+    sections: &[Section],
+    header_height: f32,
+    row_spacing: f32,
+    padding: Padding,
+    width: f32,
+    mut layout_section: impl FnMut(Range<usize>) -> JustifiedLayout,
+) -> JustifiedLayout {
+    let item_count = sections.last().map_or(0, |section| section.items.end);
+    let mut layout = JustifiedLayout {
+        cells: vec![Rectangle::with_size(Size::ZERO); item_count],
+        ..JustifiedLayout::default()
+    };
+    let header_width = (width - padding.horizontal()).max(0.0);
+    let mut top = padding.top;
+
+    for section in sections {
+        layout.headers.push(Rectangle::new(
+            Point::new(padding.left, top),
+            Size::new(header_width, header_height),
+        ));
+        top += header_height;
+
+        if section.collapsed || section.items.is_empty() {
+            continue;
+        }
+
+        let start = section.items.start;
+        let inner = layout_section(section.items.clone());
+        for (index, cell) in inner.cells.into_iter().enumerate() {
+            if let Some(slot) = layout.cells.get_mut(start + index) {
+                *slot = Rectangle::new(Point::new(cell.x, cell.y + top), cell.size());
+            }
+        }
+        layout
+            .rows
+            .extend(inner.rows.into_iter().map(|row| JustifiedRow {
+                items: row.items.start + start..row.items.end + start,
+                top: row.top + top,
+                height: row.height,
+            }));
+        top += inner.height + row_spacing;
+    }
+
+    layout.height = top + padding.bottom;
+    layout
+}
+
 /// Calculate the index of an item at a given position
 pub fn item_at_position(
     position: (f32, f32),
@@ -155,8 +508,5 @@ pub fn calculate_centered_image_bounds(
     let x = cell_bounds.x + (cell_bounds.width - scaled_width) / 2.0;
     let y = cell_bounds.y + (cell_bounds.height - scaled_height) / 2.0;
 
-    Rectangle::new(
-        cosmic::iced::Point::new(x, y),
-        cosmic::iced::Size::new(scaled_width, scaled_height),
-    )
+    Rectangle::new(Point::new(x, y), Size::new(scaled_width, scaled_height))
 }
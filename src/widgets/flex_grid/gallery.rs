@@ -2,58 +2,420 @@
 //!
 //! Features:
 //! - Built-in thumbnail rendering with proper centering
+//! - Square cells, or justified rows that keep each image's aspect ratio
+//! - A details list with one row per item, drawing only the rows in view
+//! - Sections under collapsible headers that stay pinned while scrolling
 //! - Internal focus/selection tracking
 //! - Mouse hover updates focus
 //! - Keyboard navigation (arrows)
+//! - Multi-selection: Ctrl+click, Shift+click/arrows and rubber-band drag
 //! - Auto-scroll on focus change
-
-use std::cell::Cell;
+//! - Ctrl+wheel asks for bigger or smaller thumbnails
+//! - Optional caption below each thumbnail
+//! - Folder tiles (thumbnail mosaic) and a parent folder tile
+//! - Badges over thumbnails: format, animation, rating, edits, load failures
+//! - Error tiles for images that failed to load, with the reason on hover
+
+use std::cell::{Cell, RefCell};
+use std::ops::Range;
 use std::path::PathBuf;
 
 use cosmic::{
     Element, Renderer,
     iced::{
-        Color, Length, Padding, Point, Rectangle, Size,
+        Color, Length, Padding, Pixels, Point, Rectangle, Size, alignment,
         advanced::{
             Clipboard, Layout, Shell, Widget,
             image::Renderer as ImageRenderer,
             layout::{Limits, Node},
             overlay,
             renderer::{self as iced_renderer, Quad, Renderer as QuadRenderer},
-            widget::{Id, Operation, Tree},
+            text::{self as adv_text, Renderer as TextRenderer},
+            widget::{Id, Operation, Tree, tree},
         },
         event::{Event, Status},
         keyboard::{self, Key},
         mouse::{self, Button, Cursor},
+        widget::scrollable::Viewport,
     },
     widget::{container, image::Handle, scrollable},
 };
 
-use super::core;
+use super::core::{self, GridMetrics, JustifiedConfig, JustifiedLayout, Section};
+
+/// What a grid cell stands for
+#[derive(Debug, Clone, Default)]
+pub enum TileKind {
+    #[default]
+    Image,
+    /// A subfolder, drawn as a mosaic of up to four of its thumbnails
+    Folder(Vec<Handle>),
+    /// The folder above the current one
+    Parent,
+}
 
 /// An item in the gallery grid
 #[derive(Debug, Clone)]
 pub struct GalleryItem {
     pub path: PathBuf,
     pub handle: Option<Handle>,
+    /// Caption drawn under the thumbnail
+    pub label: Option<String>,
+    /// Width over height of the image, used by the justified layout
+    pub aspect: Option<f32>,
+    /// Cells of the item's row in the details list
+    pub columns: Vec<String>,
+    pub badges: TileBadges,
+    /// Why the image couldn't be loaded, shown when the tile is hovered
+    pub error: Option<String>,
+    pub kind: TileKind,
+}
+
+/// Small marks drawn over an image's thumbnail
+#[derive(Debug, Clone, Default)]
+pub struct TileBadges {
+    /// Format chip, e.g. "RAW" or "TIFF · 48 MB"
+    pub format: Option<String>,
+    pub animated: bool,
+    /// Stars from 1 to 5
+    pub rating: Option<u8>,
+    /// Edited in this session
+    pub edited: bool,
+    /// The thumbnail couldn't be loaded
+    pub failed: bool,
 }
 
 impl GalleryItem {
     pub fn new(path: PathBuf, handle: Option<Handle>) -> Self {
-        Self { path, handle }
+        Self {
+            path,
+            handle,
+            label: None,
+            aspect: None,
+            columns: Vec::new(),
+            badges: TileBadges::default(),
+            error: None,
+            kind: TileKind::Image,
+        }
+    }
+
+    pub fn folder(path: PathBuf, previews: Vec<Handle>) -> Self {
+        Self {
+            path,
+            handle: None,
+            label: None,
+            aspect: None,
+            columns: Vec::new(),
+            badges: TileBadges::default(),
+            error: None,
+            kind: TileKind::Folder(previews),
+        }
+    }
+
+    pub fn parent(path: PathBuf) -> Self {
+        Self {
+            path,
+            handle: None,
+            label: None,
+            aspect: None,
+            columns: Vec::new(),
+            badges: TileBadges::default(),
+            error: None,
+            kind: TileKind::Parent,
+        }
+    }
+
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub fn aspect(mut self, aspect: Option<f32>) -> Self {
+        self.aspect = aspect;
+        self
+    }
+
+    pub fn columns(mut self, columns: Vec<String>) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn badges(mut self, badges: TileBadges) -> Self {
+        self.badges = badges;
+        self
+    }
+
+    pub fn error(mut self, error: Option<String>) -> Self {
+        self.error = error;
+        self
+    }
+
+    /// Aspect ratio the justified layout gives the tile; folders are square
+    fn layout_aspect(&self) -> f32 {
+        match self.kind {
+            TileKind::Image => self.aspect.unwrap_or(1.0),
+            TileKind::Folder(_) | TileKind::Parent => 1.0,
+        }
+    }
+}
+
+/// Width over height of a decoded thumbnail
+pub fn handle_aspect(handle: &Handle) -> Option<f32> {
+    match handle {
+        Handle::Rgba { width, height, .. } if *height > 0 => Some(*width as f32 / *height as f32),
+        _ => None,
     }
 }
 
+/// Height reserved below thumbnails for captions
+const LABEL_HEIGHT: f32 = 20.0;
+const LABEL_TEXT_SIZE: f32 = 12.0;
+/// Gap between the thumbnails of a folder mosaic
+const MOSAIC_GAP: f32 = 2.0;
+const BADGE_TEXT_SIZE: f32 = 10.0;
+const BADGE_HEIGHT: f32 = 16.0;
+/// Space between a badge and the edge of the thumbnail
+const BADGE_MARGIN: f32 = 4.0;
+/// Thumbnails smaller than this only get the edit and failure marks
+const BADGE_MIN_SIZE: f32 = 72.0;
+const BADGE_BACKGROUND: Color = Color::from_rgba(0.0, 0.0, 0.0, 0.6);
+const TOOLTIP_MAX_WIDTH: f32 = 280.0;
+const TOOLTIP_PADDING: f32 = 6.0;
+/// Distance of a tooltip from the cursor
+const TOOLTIP_OFFSET: f32 = 12.0;
+
+/// Touchpad scrolling that counts as one wheel step
+const WHEEL_PIXELS_PER_STEP: f32 = 40.0;
+
+/// Height of a section header
+pub const SECTION_HEADER_HEIGHT: f32 = 36.0;
+/// Width of the action at the right end of a section header
+const SECTION_ACTION_WIDTH: f32 = 140.0;
+const SECTION_TEXT_SIZE: f32 = 14.0;
+
+/// A titled run of consecutive items, e.g. the photos of one day
+#[derive(Debug, Clone)]
+pub struct GallerySection {
+    pub title: String,
+    pub items: Range<usize>,
+    pub collapsed: bool,
+}
+
+/// Height of a details list row; its thumbnail is a square inset by
+/// `LIST_ROW_PADDING`, so the thumbnail column is this wide too
+pub const LIST_ROW_HEIGHT: f32 = 40.0;
+const LIST_ROW_PADDING: f32 = 4.0;
+const LIST_TEXT_SIZE: f32 = 13.0;
+
+/// Distance the pointer has to move with the button held before a click
+/// becomes a rubber-band drag
+const DRAG_THRESHOLD: f32 = 4.0;
+
 /// Scroll request for auto-scrolling
 #[derive(Debug, Clone, Copy)]
 pub struct ScrollRequest {
     pub offset_y: f32,
 }
 
+/// Ctrl+wheel over the grid, asking to resize the thumbnails
+#[derive(Debug, Clone, Copy)]
+pub struct ZoomRequest {
+    /// Wheel steps; positive to enlarge
+    pub steps: f32,
+    /// Item under the cursor, to keep in place while the grid reflows
+    pub anchor: Option<ZoomAnchor>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct ZoomAnchor {
+    /// Grid index of the item
+    pub index: usize,
+    /// Height of the cursor in the view
+    pub view_y: f32,
+    /// Height of the cursor on the item, as a fraction of the item's height
+    pub fraction: f32,
+}
+
+/// How the user changed the selection; indices are grid indices
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectionChange {
+    /// Ctrl+click: add or remove one item
+    Toggle(usize),
+    /// Shift+click or Shift+arrow: select from the last clicked item to this one
+    Extend(usize),
+    /// Rubber band: exactly these items
+    Set(Vec<usize>),
+}
+
+/// Widget state kept across redraws
+#[derive(Debug, Default)]
+struct State {
+    modifiers: keyboard::Modifiers,
+    press: Option<Press>,
+}
+
+/// Left button held down on the grid
+#[derive(Debug)]
+struct Press {
+    /// Relative to the grid's top left corner
+    origin: Point,
+    /// Tile under the pointer when the button went down
+    index: Option<usize>,
+    band: Option<Band>,
+}
+
+/// Rubber band being dragged out
+#[derive(Debug)]
+struct Band {
+    /// Relative to the grid's top left corner
+    current: Point,
+    /// Selection kept underneath the band (Ctrl adds to the existing one)
+    base: Vec<usize>,
+    /// Last selection published, to skip repeats
+    published: Vec<usize>,
+}
+
+impl Band {
+    fn rect(&self, origin: Point) -> Rectangle {
+        let top_left = Point::new(origin.x.min(self.current.x), origin.y.min(self.current.y));
+        Rectangle::new(
+            top_left,
+            Size::new(
+                (origin.x - self.current.x).abs(),
+                (origin.y - self.current.y).abs(),
+            ),
+        )
+    }
+}
+
+/// Columns, rows and row height the grid lays out for `item_count` tiles in
+/// `width`, so code outside the widget can tell which tiles are in view
+pub fn grid_metrics(
+    width: f32,
+    item_count: usize,
+    thumbnail_size: u32,
+    column_spacing: u16,
+    padding: Padding,
+    labelled: bool,
+) -> GridMetrics {
+    let available_width = width - padding.horizontal();
+    let button_padding = column_spacing as f32; // Use spacing as button padding
+    let cell_size = thumbnail_size as f32 + (button_padding * 2.0);
+
+    let cols = core::calculate_columns(
+        available_width,
+        cell_size,
+        column_spacing as f32,
+        1,
+        None,
+        item_count,
+    );
+
+    GridMetrics {
+        cols,
+        rows: item_count.div_ceil(cols),
+        row_height: cell_size + if labelled { LABEL_HEIGHT } else { 0.0 },
+    }
+}
+
+/// Rows of the details list for `item_count` items, so code outside the
+/// widget can tell which are in view; the list has no space between rows
+pub fn list_metrics(item_count: usize) -> GridMetrics {
+    GridMetrics {
+        cols: 1,
+        rows: item_count,
+        row_height: LIST_ROW_HEIGHT,
+    }
+}
+
+/// Justified rows the grid lays out for tiles with `aspects` in `width`, so
+/// code outside the widget can tell which tiles are in view. Rows aim for the
+/// height square cells would have.
+pub fn justified_metrics(
+    width: f32,
+    aspects: &[f32],
+    thumbnail_size: u32,
+    column_spacing: u16,
+    padding: Padding,
+    labelled: bool,
+) -> JustifiedLayout {
+    core::justified_layout(
+        aspects,
+        width,
+        &JustifiedConfig {
+            target_height: thumbnail_size as f32,
+            tile_padding: column_spacing as f32, // Same button padding as square cells
+            label_height: if labelled { LABEL_HEIGHT } else { 0.0 },
+            column_spacing: column_spacing as f32,
+            row_spacing: column_spacing as f32,
+            padding,
+        },
+    )
+}
+
+/// Sections laid out one below the other under their headers, so code outside
+/// the grid can tell which tiles are in view and where each section starts.
+/// Tiles are square cells, or justified rows when `aspects` are given.
+pub fn sectioned_metrics(
+    width: f32,
+    sections: &[Section],
+    aspects: Option<&[f32]>,
+    thumbnail_size: u32,
+    column_spacing: u16,
+    padding: Padding,
+    labelled: bool,
+) -> JustifiedLayout {
+    let spacing = column_spacing as f32;
+    // Headers keep sections apart; inside them only the side padding applies
+    let inner_padding = Padding {
+        top: 0.0,
+        bottom: 0.0,
+        ..padding
+    };
+
+    core::sectioned_layout(
+        sections,
+        SECTION_HEADER_HEIGHT,
+        spacing,
+        padding,
+        width,
+        |items| match aspects {
+            Some(aspects) => justified_metrics(
+                width,
+                &aspects[items],
+                thumbnail_size,
+                column_spacing,
+                inner_padding,
+                labelled,
+            ),
+            None => {
+                let metrics = grid_metrics(
+                    width,
+                    items.len(),
+                    thumbnail_size,
+                    column_spacing,
+                    inner_padding,
+                    labelled,
+                );
+                let cell_size = thumbnail_size as f32 + spacing * 2.0;
+                core::uniform_layout(
+                    items.len(),
+                    metrics.cols,
+                    Size::new(cell_size, metrics.row_height),
+                    spacing,
+                    spacing,
+                    inner_padding,
+                )
+            }
+        },
+    )
+}
+
 /// Builder for GalleryGrid
 pub struct GalleryGrid<'a, M> {
     inner: GalleryGridInner<'a, M>,
     scrollable_id: Option<Id>,
+    on_scroll: Option<Box<dyn Fn(Viewport) -> M + 'a>>,
     keyboard_nav_enabled: bool,
 }
 
@@ -65,6 +427,7 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
                 thumbnail_size: 128,
                 focused_index: None,
                 selected_indices: Vec::new(),
+                selectable_from: 0,
                 padding: Padding::ZERO,
                 column_spacing: 8,
                 row_spacing: 8,
@@ -72,13 +435,24 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
                 height: Length::Fill,
                 on_focus: None,
                 on_activate: None,
+                on_select: None,
                 on_scroll_request: None,
+                on_zoom: None,
                 last_layout: Cell::new((0, 0)),
                 cached_cols: Cell::new(0),
                 cached_row_height: Cell::new(0.0),
+                justified: false,
+                cached_cells: RefCell::new(JustifiedLayout::default()),
+                list_columns: None,
+                cached_width: Cell::new(0.0),
+                sections: Vec::new(),
+                section_action: None,
+                on_section_toggle: None,
+                on_section_select: None,
                 keyboard_nav_enabled: true,
             },
             scrollable_id: None,
+            on_scroll: None,
             keyboard_nav_enabled: true,
         }
     }
@@ -95,6 +469,48 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
         self
     }
 
+    /// Lay tiles out in rows that keep each image's aspect ratio and fill the
+    /// width, instead of square cells; rows aim for the thumbnail size
+    pub fn justified(mut self, justified: bool) -> Self {
+        self.inner.justified = justified;
+        self
+    }
+
+    /// Show a details list instead of tiles: a row per item with a small
+    /// thumbnail and the item's columns. The first column gets the width the
+    /// others leave, which are `column_widths` wide.
+    pub fn list(mut self, column_widths: Vec<f32>) -> Self {
+        self.inner.list_columns = Some(column_widths);
+        self
+    }
+
+    /// Group the tiles under section headers; the sections must cover every
+    /// item, in order. Not used by the details list.
+    pub fn sections(mut self, sections: Vec<GallerySection>) -> Self {
+        self.inner.sections = sections;
+        self
+    }
+
+    /// Callback when a section header is clicked, to collapse or expand it
+    pub fn on_section_toggle<F>(mut self, f: F) -> Self
+    where
+        F: Fn(usize) -> M + 'a,
+    {
+        self.inner.on_section_toggle = Some(Box::new(f));
+        self
+    }
+
+    /// Show `label` at the end of every section header, calling back with
+    /// the section when it is clicked
+    pub fn on_section_select<F>(mut self, label: impl Into<String>, f: F) -> Self
+    where
+        F: Fn(usize) -> M + 'a,
+    {
+        self.inner.section_action = Some(label.into());
+        self.inner.on_section_select = Some(Box::new(f));
+        self
+    }
+
     pub fn focused(mut self, index: Option<usize>) -> Self {
         self.inner.focused_index = index;
         self
@@ -105,6 +521,13 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
         self
     }
 
+    /// Items before `index` (e.g. folder tiles) can be focused and activated,
+    /// but never selected
+    pub fn selectable_from(mut self, index: usize) -> Self {
+        self.inner.selectable_from = index;
+        self
+    }
+
     pub fn padding(mut self, padding: impl Into<Padding>) -> Self {
         self.inner.padding = padding.into();
         self
@@ -159,6 +582,16 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
         self
     }
 
+    /// Callback when the selection changes (Ctrl/Shift click, Shift+arrows or
+    /// rubber-band drag)
+    pub fn on_select<F>(mut self, f: F) -> Self
+    where
+        F: Fn(SelectionChange) -> M + 'a,
+    {
+        self.inner.on_select = Some(Box::new(f));
+        self
+    }
+
     /// Callback when scroll is needed (for external scrollable container)
     pub fn on_scroll_request<F>(mut self, f: F) -> Self
     where
@@ -168,13 +601,35 @@ impl<'a, M: Clone + 'static> GalleryGrid<'a, M> {
         self
     }
 
+    /// Callback for Ctrl+wheel over the grid
+    pub fn on_zoom<F>(mut self, f: F) -> Self
+    where
+        F: Fn(ZoomRequest) -> M + 'a,
+    {
+        self.inner.on_zoom = Some(Box::new(f));
+        self
+    }
+
+    /// Callback when the scrollable moves or resizes
+    pub fn on_scroll<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Viewport) -> M + 'a,
+    {
+        self.on_scroll = Some(Box::new(f));
+        self
+    }
+
     pub fn into_element(self) -> Element<'a, M> {
         if let Some(scroll_id) = self.scrollable_id {
-            scrollable(container(self.inner).padding(0))
+            let scrollable = scrollable(container(self.inner).padding(0))
                 .id(scroll_id)
                 .width(Length::Fill)
-                .height(Length::Fill)
-                .into()
+                .height(Length::Fill);
+
+            match self.on_scroll {
+                Some(on_scroll) => scrollable.on_scroll(on_scroll).into(),
+                None => scrollable.into(),
+            }
         } else {
             self.inner.into()
         }
@@ -192,6 +647,7 @@ struct GalleryGridInner<'a, M> {
     thumbnail_size: u32,
     focused_index: Option<usize>,
     selected_indices: Vec<usize>,
+    selectable_from: usize,
     padding: Padding,
     column_spacing: u16,
     row_spacing: u16,
@@ -199,15 +655,44 @@ struct GalleryGridInner<'a, M> {
     height: Length,
     on_focus: Option<Box<dyn Fn(usize) -> M + 'a>>,
     on_activate: Option<Box<dyn Fn(usize) -> M + 'a>>,
+    on_select: Option<Box<dyn Fn(SelectionChange) -> M + 'a>>,
     on_scroll_request: Option<Box<dyn Fn(ScrollRequest) -> M + 'a>>,
+    on_zoom: Option<Box<dyn Fn(ZoomRequest) -> M + 'a>>,
     last_layout: Cell<(usize, u32)>,
     cached_cols: Cell<usize>,
     cached_row_height: Cell<f32>,
+    justified: bool,
+    /// Rows, tiles and section headers of the justified or sectioned layout,
+    /// from the last layout pass
+    cached_cells: RefCell<JustifiedLayout>,
+    /// Widths of the details list columns after the first, in list mode
+    list_columns: Option<Vec<f32>>,
+    cached_width: Cell<f32>,
+    sections: Vec<GallerySection>,
+    section_action: Option<String>,
+    on_section_toggle: Option<Box<dyn Fn(usize) -> M + 'a>>,
+    on_section_select: Option<Box<dyn Fn(usize) -> M + 'a>>,
     keyboard_nav_enabled: bool,
 }
 
 impl<'a, M> GalleryGridInner<'a, M> {
     fn item_at_position(&self, position: Point, bounds: Rectangle) -> Option<usize> {
+        if self.list_columns.is_some() {
+            let local = Point::new(position.x - bounds.x, position.y - bounds.y);
+            let row = ((local.y - self.padding.top) / LIST_ROW_HEIGHT).floor();
+            if row < 0.0 {
+                return None;
+            }
+            let index = row as usize;
+            return (index < self.items.len() && self.cell_rect(index).contains(local))
+                .then_some(index);
+        }
+
+        if self.uses_cells() {
+            let local = Point::new(position.x - bounds.x, position.y - bounds.y);
+            return self.cached_cells.borrow().item_at(local);
+        }
+
         let cols = self.cached_cols.get();
         let row_height = self.cached_row_height.get();
 
@@ -238,9 +723,361 @@ impl<'a, M> GalleryGridInner<'a, M> {
     fn is_selected(&self, index: usize) -> bool {
         self.selected_indices.contains(&index)
     }
+
+    fn is_selectable(&self, index: usize) -> bool {
+        self.on_select.is_some() && index >= self.selectable_from && index < self.items.len()
+    }
+
+    /// Selectable items whose cells overlap `rect`, given relative to the grid
+    fn items_in_rect(&self, rect: Rectangle) -> Vec<usize> {
+        if self.list_columns.is_some() {
+            let first = ((rect.y - self.padding.top) / LIST_ROW_HEIGHT)
+                .floor()
+                .max(0.0) as usize;
+            let last = ((rect.y + rect.height - self.padding.top) / LIST_ROW_HEIGHT)
+                .ceil()
+                .max(0.0) as usize;
+
+            return (first..last.min(self.items.len()))
+                .filter(|&index| self.cell_rect(index).intersects(&rect))
+                .filter(|&index| self.is_selectable(index))
+                .collect();
+        }
+
+        if self.uses_cells() {
+            return self
+                .cached_cells
+                .borrow()
+                .items_in(rect)
+                .filter(|&index| self.is_selectable(index))
+                .collect();
+        }
+
+        let cols = self.cached_cols.get();
+        let row_height = self.cached_row_height.get();
+
+        if cols == 0 || row_height <= 0.0 {
+            return Vec::new();
+        }
+
+        let cell_width = self.thumbnail_size as f32 + (self.column_spacing * 2) as f32;
+        let col_stride = cell_width + self.column_spacing as f32;
+        let row_stride = row_height + self.row_spacing as f32;
+
+        // Work relative to the first cell
+        let rect = Rectangle::new(
+            Point::new(rect.x - self.padding.left, rect.y - self.padding.top),
+            rect.size(),
+        );
+        let first_col = (rect.x / col_stride).floor().max(0.0) as usize;
+        let last_col =
+            (((rect.x + rect.width) / col_stride).floor().max(0.0) as usize).min(cols - 1);
+        let first_row = (rect.y / row_stride).floor().max(0.0) as usize;
+        let last_row = ((rect.y + rect.height) / row_stride).floor().max(0.0) as usize;
+
+        (first_row..=last_row)
+            .flat_map(|row| (first_col..=last_col).map(move |col| (row, col)))
+            .filter(|&(row, col)| {
+                // The band may only touch the gap after a cell
+                let cell = Rectangle::new(
+                    Point::new(col as f32 * col_stride, row as f32 * row_stride),
+                    Size::new(cell_width, row_height),
+                );
+                cell.intersects(&rect)
+            })
+            .map(|(row, col)| row * cols + col)
+            .filter(|&index| self.is_selectable(index))
+            .collect()
+    }
+
+    /// Bounds of the tile at `index`, relative to the grid's top left corner
+    fn cell_rect(&self, index: usize) -> Rectangle {
+        if self.list_columns.is_some() {
+            return Rectangle::new(
+                Point::new(
+                    self.padding.left,
+                    self.padding.top + index as f32 * LIST_ROW_HEIGHT,
+                ),
+                Size::new(
+                    (self.cached_width.get() - self.padding.horizontal()).max(0.0),
+                    LIST_ROW_HEIGHT,
+                ),
+            );
+        }
+
+        if self.uses_cells() {
+            return self
+                .cached_cells
+                .borrow()
+                .cells
+                .get(index)
+                .copied()
+                .unwrap_or(Rectangle::with_size(Size::ZERO));
+        }
+
+        let cols = self.cached_cols.get().max(1);
+        let row_height = self.cached_row_height.get();
+        let cell_size = self.thumbnail_size as f32 + (self.column_spacing * 2) as f32;
+        let (row, col) = (index / cols, index % cols);
+
+        Rectangle::new(
+            Point::new(
+                self.padding.left + col as f32 * (cell_size + self.column_spacing as f32),
+                self.padding.top + row as f32 * (row_height + self.row_spacing as f32),
+            ),
+            Size::new(cell_size, row_height),
+        )
+    }
+
+    /// Whether tiles come from `cached_cells` rather than the column grid
+    fn uses_cells(&self) -> bool {
+        self.list_columns.is_none() && (self.justified || !self.sections.is_empty())
+    }
+
+    /// Whether the item has a tile; items of collapsed sections don't
+    fn is_laid_out(&self, index: usize) -> bool {
+        if !self.uses_cells() {
+            return index < self.items.len();
+        }
+        self.cached_cells
+            .borrow()
+            .cells
+            .get(index)
+            .is_some_and(|cell| cell.width > 0.0)
+    }
+
+    /// Where the header of section `index` goes, relative to the grid: in
+    /// place, or pinned to the top of the view while its section scrolls
+    /// past, until the next header pushes it out
+    fn header_rect(&self, index: usize, view_top: f32) -> Rectangle {
+        let layout = self.cached_cells.borrow();
+        let Some(&header) = layout.headers.get(index) else {
+            return Rectangle::with_size(Size::ZERO);
+        };
+        let section_end = layout
+            .headers
+            .get(index + 1)
+            .map_or(layout.height, |next| next.y);
+
+        Rectangle {
+            y: view_top.min(section_end - header.height).max(header.y),
+            ..header
+        }
+    }
+
+    /// The action at the end of a section header
+    fn header_action_rect(header: Rectangle) -> Rectangle {
+        Rectangle::new(
+            Point::new(header.x + header.width - SECTION_ACTION_WIDTH, header.y),
+            Size::new(SECTION_ACTION_WIDTH, header.height),
+        )
+    }
+
+    /// Section whose header is at `position`, relative to the grid, and
+    /// whether the position is on the header's action
+    fn header_at(&self, position: Point, view_top: f32) -> Option<(usize, bool)> {
+        if !self.uses_cells() {
+            return None;
+        }
+
+        (0..self.sections.len()).find_map(|index| {
+            let header = self.header_rect(index, view_top);
+            header.contains(position).then(|| {
+                let on_action =
+                    self.has_action(index) && Self::header_action_rect(header).contains(position);
+                (index, on_action)
+            })
+        })
+    }
+
+    /// Whether section `index` has selectable items for its header action
+    fn has_action(&self, index: usize) -> bool {
+        self.section_action.is_some()
+            && self.sections[index].items.end > self.selectable_from
+            && !self.sections[index].items.is_empty()
+    }
+
+    /// Opaque, so a pinned header hides the tiles scrolling under it
+    fn draw_section_header(
+        &self,
+        renderer: &mut Renderer,
+        theme: &cosmic::cosmic_theme::Theme,
+        index: usize,
+        header: Rectangle,
+    ) {
+        let section = &self.sections[index];
+
+        renderer.fill_quad(
+            Quad {
+                bounds: header,
+                border: cosmic::iced::Border::default(),
+                shadow: Default::default(),
+            },
+            theme.bg_color().into(),
+        );
+
+        let arrow = if section.collapsed { "▸" } else { "▾" };
+        fill_text(
+            renderer,
+            &format!("{arrow}  {}", section.title),
+            Rectangle::new(
+                header.position(),
+                Size::new(
+                    (header.width - SECTION_ACTION_WIDTH).max(0.0),
+                    header.height,
+                ),
+            ),
+            SECTION_TEXT_SIZE,
+            theme.on_bg_color().into(),
+            alignment::Horizontal::Left,
+        );
+
+        if let Some(ref label) = self.section_action
+            && self.has_action(index)
+        {
+            fill_text(
+                renderer,
+                label,
+                Self::header_action_rect(header),
+                LABEL_TEXT_SIZE,
+                theme.accent_color().into(),
+                alignment::Horizontal::Right,
+            );
+        }
+    }
+
+    /// Items to draw: every tile of the grid, but only the rows of the details
+    /// list that are in view, since the list can be very long
+    fn drawn_items(&self, bounds: Rectangle, viewport: &Rectangle) -> Range<usize> {
+        if self.list_columns.is_none() {
+            return 0..self.items.len();
+        }
+
+        let top = viewport.y - bounds.y - self.padding.top;
+        let first = (top / LIST_ROW_HEIGHT).floor().max(0.0) as usize;
+        let last = ((top + viewport.height) / LIST_ROW_HEIGHT).ceil().max(0.0) as usize;
+
+        first.min(self.items.len())..last.min(self.items.len())
+    }
+
+    /// One row of the details list: thumbnail, then the item's columns
+    fn draw_list_row(
+        &self,
+        renderer: &mut Renderer,
+        theme: &cosmic::cosmic_theme::Theme,
+        index: usize,
+        row: Rectangle,
+        hovered: bool,
+    ) {
+        let item = &self.items[index];
+        let is_selected = self.is_selected(index);
+        let spacing = self.column_spacing as f32;
+
+        let bg_color = if is_selected {
+            theme.accent_color().into()
+        } else if hovered || self.focused_index == Some(index) {
+            Color::from_rgba(1.0, 1.0, 1.0, 0.1)
+        } else {
+            Color::TRANSPARENT
+        };
+        renderer.fill_quad(
+            Quad {
+                bounds: row,
+                border: cosmic::iced::Border {
+                    radius: 4.0.into(),
+                    width: 0.0,
+                    color: Color::TRANSPARENT,
+                },
+                shadow: Default::default(),
+            },
+            bg_color,
+        );
+
+        let thumb_size = LIST_ROW_HEIGHT - LIST_ROW_PADDING * 2.0;
+        let thumb_bounds = Rectangle::new(
+            Point::new(row.x + LIST_ROW_PADDING, row.y + LIST_ROW_PADDING),
+            Size::new(thumb_size, thumb_size),
+        );
+        let text_color: Color = if is_selected {
+            theme.on_accent_color().into()
+        } else {
+            theme.on_bg_color().into()
+        };
+
+        // Folders show their first image, on the same tinted card as their tiles
+        let handle = match item.kind {
+            TileKind::Image => item.handle.as_ref(),
+            TileKind::Folder(ref previews) => previews.first(),
+            TileKind::Parent => None,
+        };
+        if !matches!(item.kind, TileKind::Image) {
+            renderer.fill_quad(
+                Quad {
+                    bounds: thumb_bounds,
+                    border: cosmic::iced::Border {
+                        radius: 4.0.into(),
+                        width: 0.0,
+                        color: Color::TRANSPARENT,
+                    },
+                    shadow: Default::default(),
+                },
+                Color::from_rgba(0.5, 0.5, 0.5, 0.15),
+            );
+        }
+        if let Some(handle) = handle {
+            let aspect = handle_aspect(handle).unwrap_or(1.0);
+            renderer.draw_image(
+                handle.clone().into(),
+                cosmic::iced::widget::image::FilterMethod::Linear,
+                core::calculate_centered_image_bounds(thumb_bounds, aspect, 1.0),
+                cosmic::iced::Radians(0.0),
+                1.0,
+                [0.0; 4],
+            );
+        } else if let TileKind::Parent = item.kind {
+            fill_centered_text(renderer, "..", thumb_bounds, LIST_TEXT_SIZE, text_color);
+        }
+
+        let widths = self.list_columns.as_deref().unwrap_or_default();
+        let fixed: f32 = widths.iter().map(|width| width + spacing).sum();
+        let first_width = (row.width - LIST_ROW_HEIGHT - spacing - fixed).max(0.0);
+
+        let mut x = row.x + LIST_ROW_HEIGHT + spacing;
+        let widths = std::iter::once(first_width).chain(widths.iter().copied());
+        for (content, width) in item.columns.iter().zip(widths) {
+            if !content.is_empty() {
+                fill_text(
+                    renderer,
+                    content,
+                    Rectangle::new(Point::new(x, row.y), Size::new(width, row.height)),
+                    LIST_TEXT_SIZE,
+                    text_color,
+                    alignment::Horizontal::Left,
+                );
+            }
+            x += width + spacing;
+        }
+    }
+
+    /// Rows grow to fit captions as soon as any item has one
+    fn label_height(&self) -> f32 {
+        if self.items.iter().any(|item| item.label.is_some()) {
+            LABEL_HEIGHT
+        } else {
+            0.0
+        }
+    }
 }
 
 impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridInner<'a, M> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
     fn children(&self) -> Vec<Tree> {
         Vec::new() // No child widgets - we render thumbnails directly
     }
@@ -262,23 +1099,67 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
         let max_size = limits.max();
         let available_width = max_size.width - self.padding.horizontal();
 
-        let item_size = self.thumbnail_size as f32;
-        let button_padding = self.column_spacing as f32; // Use spacing as button padding
-        let cell_size = item_size + (button_padding * 2.0);
+        if self.list_columns.is_some() {
+            let total_height = self.items.len() as f32 * LIST_ROW_HEIGHT + self.padding.vertical();
+            self.cached_cols.set(1);
+            self.cached_row_height.set(LIST_ROW_HEIGHT);
+            self.cached_width.set(max_size.width);
 
-        let cols = core::calculate_columns(
-            available_width,
-            cell_size,
-            self.column_spacing as f32,
-            1,
-            None,
+            let content_size = Size::new(max_size.width, total_height);
+            return Node::new(limits.resolve(self.width, self.height, content_size));
+        }
+
+        if self.uses_cells() {
+            let aspects: Vec<f32> = self.items.iter().map(GalleryItem::layout_aspect).collect();
+            let labelled = self.label_height() > 0.0;
+            let layout = if self.sections.is_empty() {
+                justified_metrics(
+                    max_size.width,
+                    &aspects,
+                    self.thumbnail_size,
+                    self.column_spacing,
+                    self.padding,
+                    labelled,
+                )
+            } else {
+                let sections: Vec<Section> = self
+                    .sections
+                    .iter()
+                    .map(|section| Section {
+                        items: section.items.clone(),
+                        collapsed: section.collapsed,
+                    })
+                    .collect();
+                sectioned_metrics(
+                    max_size.width,
+                    &sections,
+                    self.justified.then_some(aspects.as_slice()),
+                    self.thumbnail_size,
+                    self.column_spacing,
+                    self.padding,
+                    labelled,
+                )
+            };
+            let content_size = Size::new(max_size.width, layout.height);
+            *self.cached_cells.borrow_mut() = layout;
+
+            return Node::new(limits.resolve(self.width, self.height, content_size));
+        }
+
+        let GridMetrics {
+            cols,
+            rows,
+            row_height,
+        } = grid_metrics(
+            max_size.width,
             self.items.len(),
+            self.thumbnail_size,
+            self.column_spacing,
+            self.padding,
+            self.label_height() > 0.0,
         );
 
-        let rows = (self.items.len() + cols - 1) / cols;
-
         // Calculate total height
-        let row_height = cell_size;
         let total_height = (rows as f32 * row_height)
             + ((rows.saturating_sub(1)) as f32 * self.row_spacing as f32)
             + self.padding.vertical();
@@ -301,25 +1182,22 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
 
     fn draw(
         &self,
-        _tree: &Tree,
+        tree: &Tree,
         renderer: &mut Renderer,
         theme: &cosmic::Theme,
         _style: &iced_renderer::Style,
         layout: Layout<'_>,
         cursor: Cursor,
-        _viewport: &Rectangle,
+        viewport: &Rectangle,
     ) {
         let bounds = layout.bounds();
-        let cols = self.cached_cols.get();
-        let row_height = self.cached_row_height.get();
 
-        if cols == 0 || self.items.is_empty() {
+        if (!self.uses_cells() && self.cached_cols.get() == 0) || self.items.is_empty() {
             return;
         }
 
-        let item_size = self.thumbnail_size as f32;
         let button_padding = self.column_spacing as f32;
-        let cell_size = item_size + (button_padding * 2.0);
+        let label_height = self.label_height();
 
         let cosmic_theme = theme.cosmic();
 
@@ -332,19 +1210,32 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
             }
         });
 
-        for (index, item) in self.items.iter().enumerate() {
-            let row = index / cols;
-            let col = index % cols;
+        for index in self.drawn_items(bounds, viewport) {
+            let item = &self.items[index];
+            let cell = self.cell_rect(index);
+            let (x, y) = (bounds.x + cell.x, bounds.y + cell.y);
+            let cell_bounds = Rectangle::new(Point::new(x, y), cell.size());
 
-            let x = bounds.x + self.padding.left
-                + (col as f32 * (cell_size + self.column_spacing as f32));
-            let y = bounds.y + self.padding.top
-                + (row as f32 * (row_height + self.row_spacing as f32));
+            // Collapsed and out of view
+            if cell.width <= 0.0 || !cell_bounds.intersects(viewport) {
+                continue;
+            }
 
-            let cell_bounds = Rectangle::new(
-                Point::new(x, y),
-                Size::new(cell_size, cell_size),
-            );
+            if self.list_columns.is_some() {
+                self.draw_list_row(
+                    renderer,
+                    cosmic_theme,
+                    index,
+                    cell_bounds,
+                    hovered_index == Some(index),
+                );
+                continue;
+            }
+
+            // Square in the grid; the image's own shape in justified rows
+            let image_width = cell.width - button_padding * 2.0;
+            let image_height = cell.height - button_padding * 2.0 - label_height;
+            let item_size = image_width.min(image_height);
 
             let is_focused = self.focused_index == Some(index);
             let is_selected = self.is_selected(index);
@@ -375,16 +1266,73 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
             // Draw thumbnail or placeholder
             let image_bounds = Rectangle::new(
                 Point::new(x + button_padding, y + button_padding),
-                Size::new(item_size, item_size),
+                Size::new(image_width, image_height),
             );
 
-            if let Some(ref handle) = item.handle {
+            if !matches!(item.kind, TileKind::Image) {
+                // Folder tiles sit on a tinted card so they read apart from images
+                renderer.fill_quad(
+                    Quad {
+                        bounds: image_bounds,
+                        border: cosmic::iced::Border {
+                            radius: 6.0.into(),
+                            width: 0.0,
+                            color: Color::TRANSPARENT,
+                        },
+                        shadow: Default::default(),
+                    },
+                    Color::from_rgba(0.5, 0.5, 0.5, 0.15),
+                );
+            }
+
+            if let TileKind::Folder(ref previews) = item.kind
+                && !previews.is_empty()
+            {
+                let quarter = (item_size - MOSAIC_GAP) / 2.0;
+                // Centered when the tile isn't square
+                let image_bounds = Rectangle::new(
+                    Point::new(
+                        image_bounds.x + (image_width - item_size) / 2.0,
+                        image_bounds.y + (image_height - item_size) / 2.0,
+                    ),
+                    Size::new(item_size, item_size),
+                );
+
+                for (slot, handle) in previews.iter().take(4).enumerate() {
+                    let slot_bounds = Rectangle::new(
+                        Point::new(
+                            image_bounds.x + (slot % 2) as f32 * (quarter + MOSAIC_GAP),
+                            image_bounds.y + (slot / 2) as f32 * (quarter + MOSAIC_GAP),
+                        ),
+                        Size::new(quarter, quarter),
+                    );
+                    let centered =
+                        core::calculate_centered_image_bounds(slot_bounds, quarter, quarter);
+
+                    renderer.draw_image(
+                        handle.clone().into(),
+                        cosmic::iced::widget::image::FilterMethod::Linear,
+                        centered,
+                        cosmic::iced::Radians(0.0),
+                        1.0,
+                        [0.0; 4],
+                    );
+                }
+            } else if let TileKind::Parent = item.kind {
+                fill_centered_text(
+                    renderer,
+                    "..",
+                    image_bounds,
+                    item_size / 3.0,
+                    cosmic_theme.on_bg_color().into(),
+                );
+            } else if let Some(ref handle) = item.handle {
                 // Get image dimensions from handle if available
                 // For now, assume square and center
                 let centered = core::calculate_centered_image_bounds(
                     image_bounds,
-                    item_size, // Assume square for now
-                    item_size,
+                    image_width, // Square cells assume a square thumbnail
+                    image_height,
                 );
 
                 renderer.draw_image(
@@ -396,31 +1344,117 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                     [0.0; 4], // snap
                 );
             } else {
-                // Draw placeholder (simple gray box)
+                // Draw placeholder (simple gray box, red for a failed load)
                 let placeholder_size = item_size / 2.0;
                 let placeholder_bounds = Rectangle::new(
                     Point::new(
-                        image_bounds.x + (item_size - placeholder_size) / 2.0,
-                        image_bounds.y + (item_size - placeholder_size) / 2.0,
+                        image_bounds.x + (image_width - placeholder_size) / 2.0,
+                        image_bounds.y + (image_height - placeholder_size) / 2.0,
                     ),
                     Size::new(placeholder_size, placeholder_size),
                 );
 
+                let placeholder_color = if item.error.is_some() {
+                    let mut color: Color = cosmic_theme.destructive_color().into();
+                    color.a = 0.3;
+                    color
+                } else {
+                    Color::from_rgba(0.5, 0.5, 0.5, 0.3)
+                };
                 renderer.fill_quad(
                     Quad {
                         bounds: placeholder_bounds,
                         border: cosmic::iced::Border::default(),
                         shadow: Default::default(),
                     },
-                    Color::from_rgba(0.5, 0.5, 0.5, 0.3),
+                    placeholder_color,
                 );
+
+                if item.badges.failed {
+                    fill_centered_text(
+                        renderer,
+                        "!",
+                        placeholder_bounds,
+                        placeholder_size / 2.0,
+                        cosmic_theme.destructive_color().into(),
+                    );
+                }
+            }
+
+            if matches!(item.kind, TileKind::Image) {
+                draw_badges(renderer, cosmic_theme, &item.badges, image_bounds);
+            }
+
+            if let Some(ref label) = item.label {
+                let label_bounds = Rectangle::new(
+                    Point::new(x + button_padding, y + button_padding + image_height),
+                    Size::new(image_width, LABEL_HEIGHT),
+                );
+                let color = if is_selected {
+                    cosmic_theme.on_accent_color().into()
+                } else {
+                    cosmic_theme.on_bg_color().into()
+                };
+
+                fill_centered_text(renderer, label, label_bounds, LABEL_TEXT_SIZE, color);
             }
         }
+
+        if self.uses_cells() {
+            let view_top = viewport.y - bounds.y;
+            for index in 0..self.sections.len() {
+                let header = self.header_rect(index, view_top);
+                let header = Rectangle::new(
+                    Point::new(bounds.x + header.x, bounds.y + header.y),
+                    header.size(),
+                );
+                if header.intersects(viewport) {
+                    self.draw_section_header(renderer, cosmic_theme, index, header);
+                }
+            }
+        }
+
+        // Why the hovered tile failed to load
+        if let (Some(index), Some(position)) = (hovered_index, cursor.position())
+            && let Some(ref error) = self.items[index].error
+        {
+            renderer.with_layer(*viewport, |renderer| {
+                draw_tooltip(renderer, error, position, *viewport);
+            });
+        }
+
+        // Rubber band on top of the tiles
+        let state = tree.state.downcast_ref::<State>();
+        if let Some(Press {
+            origin,
+            band: Some(ref band),
+            ..
+        }) = state.press
+        {
+            let rect = band.rect(origin);
+            let accent: Color = cosmic_theme.accent_color().into();
+
+            renderer.fill_quad(
+                Quad {
+                    bounds: Rectangle::new(
+                        Point::new(bounds.x + rect.x, bounds.y + rect.y),
+                        rect.size(),
+                    ),
+                    border: cosmic::iced::Border {
+                        radius: 2.0.into(),
+                        width: 1.0,
+                        color: accent,
+                    },
+                    shadow: Default::default(),
+                },
+                Color { a: 0.2, ..accent },
+            );
+        }
     }
 
     fn on_event(
         &mut self,
-        _tree: &mut Tree,
+        tree: &mut Tree,
         event: Event,
         layout: Layout<'_>,
         cursor: Cursor,
@@ -430,36 +1464,163 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
         viewport: &Rectangle,
     ) -> Status {
         let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
 
         match event {
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                state.modifiers = modifiers;
+            }
+
+            // Ctrl+wheel resizes the thumbnails instead of scrolling
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) if state.modifiers.control() => {
+                let (Some(on_zoom), Some(position)) = (&self.on_zoom, cursor.position()) else {
+                    return Status::Ignored;
+                };
+                if !bounds.contains(position) {
+                    return Status::Ignored;
+                }
+
+                let steps = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / WHEEL_PIXELS_PER_STEP,
+                };
+                let anchor = self.item_at_position(position, bounds).map(|index| {
+                    let cell = self.cell_rect(index);
+                    ZoomAnchor {
+                        index,
+                        view_y: position.y - viewport.y,
+                        fraction: ((position.y - bounds.y - cell.y) / cell.height.max(1.0))
+                            .clamp(0.0, 1.0),
+                    }
+                });
+
+                shell.publish(on_zoom(ZoomRequest { steps, anchor }));
+                return Status::Captured;
+            }
+
             // Mouse hover - visual only, no messages (draw() handles highlight from cursor)
+            // While the button is held, dragging far enough draws a rubber band
             Event::Mouse(mouse::Event::CursorMoved { .. }) => {
-                // No-op: visual hover highlighting is handled in draw() using cursor position
-                // We don't fire callbacks here to avoid scroll jank from UI refreshes
+                // We don't fire callbacks on hover to avoid scroll jank from UI refreshes
+                let (Some(press), Some(position)) = (state.press.as_mut(), cursor.position())
+                else {
+                    return Status::Ignored;
+                };
+                if self.on_select.is_none() {
+                    return Status::Ignored;
+                }
+
+                let current = Point::new(position.x - bounds.x, position.y - bounds.y);
+                if press.band.is_none() && press.origin.distance(current) < DRAG_THRESHOLD {
+                    return Status::Ignored;
+                }
+
+                let band = press.band.get_or_insert_with(|| Band {
+                    current,
+                    base: if state.modifiers.control() {
+                        self.selected_indices.clone()
+                    } else {
+                        Vec::new()
+                    },
+                    published: Vec::new(),
+                });
+                band.current = current;
+
+                let mut selection = band.base.clone();
+                for index in self.items_in_rect(band.rect(press.origin)) {
+                    if !selection.contains(&index) {
+                        selection.push(index);
+                    }
+                }
+
+                if selection != band.published {
+                    band.published = selection.clone();
+                    if let Some(ref on_select) = self.on_select {
+                        shell.publish(on_select(SelectionChange::Set(selection)));
+                    }
+                }
+                return Status::Captured;
             }
 
-            // Click - activate
+            // Press - activation waits for the release so a drag can select instead
             Event::Mouse(mouse::Event::ButtonPressed(Button::Left)) => {
                 if let Some(position) = cursor.position() {
                     if bounds.contains(position) {
-                        if let Some(index) = self.item_at_position(position, bounds) {
-                            if let Some(ref on_activate) = self.on_activate {
-                                shell.publish(on_activate(index));
+                        let local = Point::new(position.x - bounds.x, position.y - bounds.y);
+                        if let Some((section, on_action)) =
+                            self.header_at(local, viewport.y - bounds.y)
+                        {
+                            let callback = if on_action {
+                                &self.on_section_select
+                            } else {
+                                &self.on_section_toggle
+                            };
+                            if let Some(callback) = callback {
+                                shell.publish(callback(section));
                             }
                             return Status::Captured;
                         }
+
+                        let index = self.item_at_position(position, bounds);
+                        if index.is_none() && self.on_select.is_none() {
+                            return Status::Ignored;
+                        }
+
+                        state.press = Some(Press {
+                            origin: Point::new(position.x - bounds.x, position.y - bounds.y),
+                            index,
+                            band: None,
+                        });
+                        return Status::Captured;
                     }
                 }
             }
 
+            // Release - click with modifiers selects, a plain click activates
+            Event::Mouse(mouse::Event::ButtonReleased(Button::Left)) => {
+                let Some(press) = state.press.take() else {
+                    return Status::Ignored;
+                };
+                // Only a click that ends on the tile it started on counts
+                let released_on = cursor
+                    .position()
+                    .and_then(|position| self.item_at_position(position, bounds));
+                let Some(index) = press
+                    .index
+                    .filter(|&index| press.band.is_none() && released_on == Some(index))
+                else {
+                    return Status::Captured;
+                };
+
+                let modifiers = state.modifiers;
+                if (modifiers.control() || modifiers.shift()) && self.is_selectable(index) {
+                    let change = if modifiers.shift() {
+                        SelectionChange::Extend(index)
+                    } else {
+                        SelectionChange::Toggle(index)
+                    };
+                    if let Some(ref on_select) = self.on_select {
+                        shell.publish(on_select(change));
+                    }
+
+                    self.focused_index = Some(index);
+                    if let Some(ref on_focus) = self.on_focus {
+                        shell.publish(on_focus(index));
+                    }
+                } else if let Some(ref on_activate) = self.on_activate {
+                    shell.publish(on_activate(index));
+                }
+                return Status::Captured;
+            }
+
             // Keyboard navigation (disabled when modal is open)
-            Event::Keyboard(keyboard::Event::KeyPressed { key, .. }) => {
+            Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
                 if !self.keyboard_nav_enabled {
                     return Status::Ignored;
                 }
 
                 let cols = self.cached_cols.get();
-                if cols == 0 || self.items.is_empty() {
+                if (!self.uses_cells() && cols == 0) || self.items.is_empty() {
                     return Status::Ignored;
                 }
 
@@ -467,12 +1628,34 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                 let total = self.items.len();
 
                 let new_index = match key {
+                    // Skip the tiles of collapsed sections
+                    Key::Named(keyboard::key::Named::ArrowLeft) if self.uses_cells() => {
+                        (0..current).rev().find(|&index| self.is_laid_out(index))
+                    }
+                    Key::Named(keyboard::key::Named::ArrowRight) if self.uses_cells() => {
+                        (current + 1..total).find(|&index| self.is_laid_out(index))
+                    }
+                    Key::Named(keyboard::key::Named::Home) if self.uses_cells() => {
+                        (0..total).find(|&index| self.is_laid_out(index))
+                    }
+                    Key::Named(keyboard::key::Named::End) if self.uses_cells() => {
+                        (0..total).rev().find(|&index| self.is_laid_out(index))
+                    }
                     Key::Named(keyboard::key::Named::ArrowLeft) => {
                         if current > 0 { Some(current - 1) } else { None }
                     }
                     Key::Named(keyboard::key::Named::ArrowRight) => {
                         if current + 1 < total { Some(current + 1) } else { None }
                     }
+                    // Rows differ in length, so go to the nearest tile
+                    Key::Named(keyboard::key::Named::ArrowUp) if self.uses_cells() => self
+                        .cached_cells
+                        .borrow()
+                        .nearest_in_adjacent_row(current, true),
+                    Key::Named(keyboard::key::Named::ArrowDown) if self.uses_cells() => self
+                        .cached_cells
+                        .borrow()
+                        .nearest_in_adjacent_row(current, false),
                     Key::Named(keyboard::key::Named::ArrowUp) => {
                         if current >= cols { Some(current - cols) } else { None }
                     }
@@ -491,6 +1674,14 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
                 };
 
                 if let Some(new_idx) = new_index {
+                    // Shift extends the selection from where it started; sent
+                    // before the focus moves so the old focus can serve as anchor
+                    if modifiers.shift() && self.is_selectable(new_idx) {
+                        if let Some(ref on_select) = self.on_select {
+                            shell.publish(on_select(SelectionChange::Extend(new_idx)));
+                        }
+                    }
+
                     self.focused_index = Some(new_idx);
                     if let Some(ref on_focus) = self.on_focus {
                         shell.publish(on_focus(new_idx));
@@ -498,23 +1689,28 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
 
                     // Only scroll if item is out of view
                     if let Some(ref on_scroll_request) = self.on_scroll_request {
-                        let row_height = self.cached_row_height.get();
-                        let row = new_idx / cols;
-                        let row_spacing = self.row_spacing as f32;
-
                         // Item position relative to grid content
-                        let item_top = self.padding.top + (row as f32 * (row_height + row_spacing));
-                        let item_bottom = item_top + row_height;
+                        let cell = self.cell_rect(new_idx);
+                        let item_top = cell.y;
+                        let item_bottom = cell.y + cell.height;
 
                         // Viewport position relative to grid (scroll offset)
                         let scroll_offset = viewport.y - bounds.y;
-                        let visible_top = scroll_offset;
+                        // A pinned section header covers the top of the view
+                        let pinned = if self.uses_cells() && !self.sections.is_empty() {
+                            SECTION_HEADER_HEIGHT
+                        } else {
+                            0.0
+                        };
+                        let visible_top = scroll_offset + pinned;
                         let visible_bottom = scroll_offset + viewport.height;
 
                         // Only scroll if item is not fully visible
                         if item_top < visible_top {
                             // Item is above viewport - scroll up to show it at top
-                            shell.publish(on_scroll_request(ScrollRequest { offset_y: item_top }));
+                            shell.publish(on_scroll_request(ScrollRequest {
+                                offset_y: (item_top - pinned).max(0.0),
+                            }));
                         } else if item_bottom > visible_bottom {
                             // Item is below viewport - scroll down to show it at bottom
                             let new_offset = item_bottom - viewport.height;
@@ -538,12 +1734,16 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
         _tree: &Tree,
         layout: Layout<'_>,
         cursor: Cursor,
-        _viewport: &Rectangle,
+        viewport: &Rectangle,
         _renderer: &Renderer,
     ) -> mouse::Interaction {
         let bounds = layout.bounds();
         if let Some(position) = cursor.position() {
             if bounds.contains(position) {
+                let local = Point::new(position.x - bounds.x, position.y - bounds.y);
+                if self.header_at(local, viewport.y - bounds.y).is_some() {
+                    return mouse::Interaction::Pointer;
+                }
                 if self.item_at_position(position, bounds).is_some() {
                     return mouse::Interaction::Pointer;
                 }
@@ -572,6 +1772,193 @@ impl<'a, M: Clone + 'static> Widget<M, cosmic::Theme, Renderer> for GalleryGridI
     }
 }
 
+/// Single line of text centered in `bounds`; anything wider is cut off at the edge
+fn fill_centered_text(
+    renderer: &mut Renderer,
+    content: &str,
+    bounds: Rectangle,
+    size: f32,
+    color: Color,
+) {
+    fill_text(
+        renderer,
+        content,
+        bounds,
+        size,
+        color,
+        alignment::Horizontal::Center,
+    );
+}
+
+/// Badges in the corners of a thumbnail: animation top left, edit mark top
+/// right, format bottom left and rating bottom right
+fn draw_badges(
+    renderer: &mut Renderer,
+    theme: &cosmic::cosmic_theme::Theme,
+    badges: &TileBadges,
+    image: Rectangle,
+) {
+    let left = image.x + BADGE_MARGIN;
+    let right = image.x + image.width - BADGE_MARGIN;
+    let top = image.y + BADGE_MARGIN;
+    let bottom = image.y + image.height - BADGE_MARGIN - BADGE_HEIGHT;
+
+    if badges.edited {
+        let dot = BADGE_HEIGHT / 2.0;
+        renderer.fill_quad(
+            Quad {
+                bounds: Rectangle::new(Point::new(right - dot, top), Size::new(dot, dot)),
+                border: cosmic::iced::Border {
+                    radius: (dot / 2.0).into(),
+                    width: 1.0,
+                    color: Color::WHITE,
+                },
+                shadow: Default::default(),
+            },
+            theme.accent_color().into(),
+        );
+    }
+
+    if image.width.min(image.height) < BADGE_MIN_SIZE {
+        return;
+    }
+    // Chips don't fit side by side below this width
+    let max_width = (image.width - BADGE_MARGIN * 3.0) / 2.0;
+
+    if badges.animated {
+        draw_chip(renderer, "▶", Point::new(left, top), max_width, false);
+    }
+    if let Some(ref format) = badges.format {
+        draw_chip(renderer, format, Point::new(left, bottom), max_width, false);
+    }
+    if let Some(rating) = badges.rating {
+        let stars = "★".repeat(rating.min(5) as usize);
+        draw_chip(renderer, &stars, Point::new(right, bottom), max_width, true);
+    }
+}
+
+/// Wrapped text on a dark box next to `cursor`, kept inside `viewport`.
+/// Like the chips, its size comes from estimated glyph widths.
+fn draw_tooltip(renderer: &mut Renderer, content: &str, cursor: Point, viewport: Rectangle) {
+    let text_width = content.chars().count() as f32 * LABEL_TEXT_SIZE * 0.55;
+    let width = (text_width + TOOLTIP_PADDING * 2.0).min(TOOLTIP_MAX_WIDTH);
+    let lines = (text_width / (width - TOOLTIP_PADDING * 2.0))
+        .ceil()
+        .max(1.0);
+    let height = lines * LABEL_TEXT_SIZE * 1.3 + TOOLTIP_PADDING * 2.0;
+
+    // Below right of the cursor, flipped where that runs out of the view
+    let mut x = cursor.x + TOOLTIP_OFFSET;
+    if x + width > viewport.x + viewport.width {
+        x = (cursor.x - TOOLTIP_OFFSET - width).max(viewport.x);
+    }
+    let mut y = cursor.y + TOOLTIP_OFFSET;
+    if y + height > viewport.y + viewport.height {
+        y = (cursor.y - TOOLTIP_OFFSET - height).max(viewport.y);
+    }
+    let bounds = Rectangle::new(Point::new(x, y), Size::new(width, height));
+
+    renderer.fill_quad(
+        Quad {
+            bounds,
+            border: cosmic::iced::Border {
+                radius: 4.0.into(),
+                width: 0.0,
+                color: Color::TRANSPARENT,
+            },
+            shadow: Default::default(),
+        },
+        BADGE_BACKGROUND,
+    );
+
+    let text_bounds = bounds.shrink(TOOLTIP_PADDING);
+    renderer.fill_text(
+        adv_text::Text {
+            content: content.to_string(),
+            bounds: text_bounds.size(),
+            size: Pixels(LABEL_TEXT_SIZE),
+            line_height: adv_text::LineHeight::default(),
+            font: renderer.default_font(),
+            horizontal_alignment: alignment::Horizontal::Left,
+            vertical_alignment: alignment::Vertical::Top,
+            shaping: adv_text::Shaping::Advanced,
+            wrapping: adv_text::Wrapping::Word,
+        },
+        text_bounds.position(),
+        Color::WHITE,
+        text_bounds,
+    );
+}
+
+/// Text on a dark rounded chip, starting at `origin` or ending there when
+/// `from_right`. Glyph widths are estimated; text that runs over is cut off.
+fn draw_chip(
+    renderer: &mut Renderer,
+    content: &str,
+    origin: Point,
+    max_width: f32,
+    from_right: bool,
+) {
+    let text_width = content.chars().count() as f32 * BADGE_TEXT_SIZE * 0.65;
+    let width = (text_width + BADGE_HEIGHT / 2.0).min(max_width);
+    if width <= 0.0 {
+        return;
+    }
+    let x = if from_right {
+        origin.x - width
+    } else {
+        origin.x
+    };
+    let bounds = Rectangle::new(Point::new(x, origin.y), Size::new(width, BADGE_HEIGHT));
+
+    renderer.fill_quad(
+        Quad {
+            bounds,
+            border: cosmic::iced::Border {
+                radius: 4.0.into(),
+                width: 0.0,
+                color: Color::TRANSPARENT,
+            },
+            shadow: Default::default(),
+        },
+        BADGE_BACKGROUND,
+    );
+    fill_centered_text(renderer, content, bounds, BADGE_TEXT_SIZE, Color::WHITE);
+}
+
+/// Single line of text, vertically centered in `bounds` and cut off at its edge
+fn fill_text(
+    renderer: &mut Renderer,
+    content: &str,
+    bounds: Rectangle,
+    size: f32,
+    color: Color,
+    horizontal_alignment: alignment::Horizontal,
+) {
+    let position = match horizontal_alignment {
+        alignment::Horizontal::Left => Point::new(bounds.x, bounds.center_y()),
+        alignment::Horizontal::Center => bounds.center(),
+        alignment::Horizontal::Right => Point::new(bounds.x + bounds.width, bounds.center_y()),
+    };
+
+    renderer.fill_text(
+        adv_text::Text {
+            content: content.to_string(),
+            bounds: bounds.size(),
+            size: Pixels(size),
+            line_height: adv_text::LineHeight::default(),
+            font: renderer.default_font(),
+            horizontal_alignment,
+            vertical_alignment: alignment::Vertical::Center,
+            shaping: adv_text::Shaping::Advanced,
+            wrapping: adv_text::Wrapping::None,
+        },
+        position,
+        color,
+        bounds,
+    );
+}
+
 impl<'a, M: Clone + 'static> From<GalleryGridInner<'a, M>> for Element<'a, M> {
     fn from(grid: GalleryGridInner<'a, M>) -> Self {
         Element::new(grid)
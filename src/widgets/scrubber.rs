@@ -0,0 +1,254 @@
+//! Date scrubber shown beside the gallery's scrollbar
+//!
+//! Years and months of the timeline are marked along the height of the
+//! widget, where their photos are in the gallery. Clicking or dragging jumps
+//! the gallery to the nearest mark.
+
+use crate::message::{Message, ViewMessage};
+use cosmic::{
+    Element, Renderer,
+    iced::{
+        Color, Length, Pixels, Point, Rectangle, Size,
+        advanced::{
+            Clipboard, Layout, Shell, Widget,
+            layout::{Limits, Node},
+            renderer::{Quad, Renderer as QuadRenderer},
+            text::{self as adv_text, Renderer as TextRenderer},
+            widget::{Tree, tree},
+        },
+        alignment,
+        event::{Event, Status},
+        mouse::{self, Button, Cursor},
+    },
+};
+
+const WIDTH: f32 = 56.0;
+const TEXT_SIZE: f32 = 11.0;
+/// Room a label needs; minor marks closer than this to the last drawn label
+/// are left out
+const LABEL_HEIGHT: f32 = 16.0;
+
+/// A point of the timeline
+#[derive(Debug, Clone)]
+pub struct ScrubberMark {
+    /// Where the mark's photos start, as a fraction of the gallery's height
+    pub fraction: f32,
+    pub label: String,
+    /// Years are always labelled, months only where there is room
+    pub major: bool,
+    /// Scroll offset that brings the mark's photos to the top of the gallery
+    pub offset: f32,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    dragging: bool,
+    /// Mark last jumped to, so a drag only scrolls when it changes
+    current: Option<usize>,
+}
+
+pub struct Scrubber {
+    marks: Vec<ScrubberMark>,
+}
+
+impl Scrubber {
+    pub fn new(marks: Vec<ScrubberMark>) -> Self {
+        Self { marks }
+    }
+
+    /// Mark nearest to `y`, relative to the widget
+    fn mark_at(&self, y: f32, height: f32) -> Option<usize> {
+        let fraction = (y / height).clamp(0.0, 1.0);
+        (0..self.marks.len()).min_by(|&a, &b| {
+            let distance = |index: usize| (self.marks[index].fraction - fraction).abs();
+            distance(a).total_cmp(&distance(b))
+        })
+    }
+
+    fn mark_y(&self, index: usize, bounds: Rectangle) -> f32 {
+        let y = bounds.y + self.marks[index].fraction * bounds.height;
+        y.clamp(bounds.y, bounds.y + bounds.height - LABEL_HEIGHT)
+    }
+
+    /// Jump to the mark nearest the cursor, unless it is already there
+    fn jump(&self, state: &mut State, y: f32, height: f32, shell: &mut Shell<'_, Message>) {
+        let index = self.mark_at(y, height);
+        if index != state.current {
+            state.current = index;
+            if let Some(index) = index {
+                shell.publish(Message::View(ViewMessage::GalleryScrollTo(
+                    self.marks[index].offset,
+                )));
+            }
+        }
+    }
+}
+
+impl Widget<Message, cosmic::Theme, Renderer> for Scrubber {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        tree::State::new(State::default())
+    }
+
+    fn size(&self) -> Size<Length> {
+        Size::new(Length::Fixed(WIDTH), Length::Fill)
+    }
+
+    fn layout(&self, _tree: &mut Tree, _renderer: &Renderer, limits: &Limits) -> Node {
+        Node::new(limits.width(WIDTH).height(Length::Fill).max())
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &cosmic::Theme,
+        _style: &cosmic::iced::advanced::renderer::Style,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let bounds = layout.bounds();
+        let cosmic_theme = theme.cosmic();
+        let state = tree.state.downcast_ref::<State>();
+
+        let active = state.dragging || cursor.is_over(bounds);
+        if active {
+            renderer.fill_quad(
+                Quad {
+                    bounds,
+                    border: cosmic::iced::Border {
+                        radius: cosmic_theme.corner_radii.radius_s.into(),
+                        ..Default::default()
+                    },
+                    ..Quad::default()
+                },
+                Color::from(cosmic_theme.bg_component_color()),
+            );
+        }
+
+        let highlighted = if state.dragging {
+            state.current
+        } else {
+            cursor
+                .position_in(bounds)
+                .and_then(|position| self.mark_at(position.y, bounds.height))
+        };
+
+        // Years first so months make room for them
+        let mut taken: Vec<f32> = Vec::new();
+        let order = (0..self.marks.len())
+            .filter(|&index| self.marks[index].major)
+            .chain((0..self.marks.len()).filter(|&index| !self.marks[index].major));
+
+        for index in order {
+            let mark = &self.marks[index];
+            let y = self.mark_y(index, bounds);
+            let crowded = taken.iter().any(|&other| (other - y).abs() < LABEL_HEIGHT);
+            if crowded && !mark.major && highlighted != Some(index) {
+                continue;
+            }
+            taken.push(y);
+
+            let color = if highlighted == Some(index) {
+                cosmic_theme.accent_color()
+            } else if mark.major {
+                cosmic_theme.on_bg_color()
+            } else {
+                let mut color = cosmic_theme.on_bg_color();
+                color.alpha = 0.6;
+                color
+            };
+
+            let label_bounds = Rectangle::new(
+                Point::new(bounds.x, y),
+                Size::new(bounds.width - 4.0, LABEL_HEIGHT),
+            );
+            renderer.fill_text(
+                adv_text::Text {
+                    content: mark.label.clone(),
+                    bounds: label_bounds.size(),
+                    size: Pixels(TEXT_SIZE),
+                    line_height: adv_text::LineHeight::default(),
+                    font: renderer.default_font(),
+                    horizontal_alignment: alignment::Horizontal::Right,
+                    vertical_alignment: alignment::Vertical::Center,
+                    shaping: adv_text::Shaping::Advanced,
+                    wrapping: adv_text::Wrapping::None,
+                },
+                Point::new(label_bounds.x + label_bounds.width, label_bounds.center_y()),
+                color.into(),
+                label_bounds,
+            );
+        }
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> Status {
+        let bounds = layout.bounds();
+        let state = tree.state.downcast_mut::<State>();
+
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(Button::Left)) => {
+                if let Some(position) = cursor.position_in(bounds) {
+                    state.dragging = true;
+                    state.current = None;
+                    self.jump(state, position.y, bounds.height, shell);
+                    return Status::Captured;
+                }
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) if state.dragging => {
+                self.jump(state, position.y - bounds.y, bounds.height, shell);
+                return Status::Captured;
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(Button::Left)) if state.dragging => {
+                state.dragging = false;
+                return Status::Captured;
+            }
+            _ => {}
+        }
+
+        Status::Ignored
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        let state = tree.state.downcast_ref::<State>();
+        if state.dragging {
+            mouse::Interaction::Grabbing
+        } else if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+impl<'a> From<Scrubber> for Element<'a, Message> {
+    fn from(scrubber: Scrubber) -> Self {
+        Self::new(scrubber)
+    }
+}
+
+/// Helper function to create a Scrubber over the timeline's marks
+pub fn scrubber(marks: Vec<ScrubberMark>) -> Scrubber {
+    Scrubber::new(marks)
+}